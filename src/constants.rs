@@ -1,2 +1,62 @@
 // Player wallet constants
 pub const MAX_NAME_LENGTH: usize = 50;
+
+// Current PlayerWallet account layout version
+pub const PLAYER_WALLET_VERSION: u8 = 2;
+
+// PDA seeds
+pub const CONFIG_SEED: &[u8] = b"config";
+pub const NAME_BLOCKLIST_SEED: &[u8] = b"name_blocklist";
+pub const TREASURY_SEED: &[u8] = b"treasury";
+pub const PROFILE_INDEX_SEED: &[u8] = b"profile_index";
+pub const PLAYER_WALLET_SEED: &[u8] = b"player_wallet";
+pub const HOLDINGS_SEED: &[u8] = b"holdings";
+pub const PRICE_FEED_SEED: &[u8] = b"price_feed";
+pub const VAULT_SEED: &[u8] = b"vault";
+pub const CURRENCY_CONFIG_SEED: &[u8] = b"currency_config";
+pub const CURRENCY_MINT_SEED: &[u8] = b"currency_mint";
+pub const DISTRIBUTOR_SEED: &[u8] = b"distributor";
+pub const STAKING_CONFIG_SEED: &[u8] = b"staking_config";
+pub const STAKE_POSITION_SEED: &[u8] = b"stake_position";
+pub const INVENTORY_SEED: &[u8] = b"inventory";
+pub const ITEM_REGISTRATION_SEED: &[u8] = b"item_registration";
+pub const MARKETPLACE_CONFIG_SEED: &[u8] = b"marketplace_config";
+pub const LISTING_SEED: &[u8] = b"listing";
+pub const AUCTION_SEED: &[u8] = b"auction";
+pub const RENTAL_SEED: &[u8] = b"rental";
+pub const LEADERBOARD_SEED: &[u8] = b"leaderboard";
+pub const SEASON_SEED: &[u8] = b"season";
+pub const SEASON_STATS_SEED: &[u8] = b"season_stats";
+pub const SEASON_CLOCK_SEED: &[u8] = b"season_clock";
+pub const ACHIEVEMENT_SEED: &[u8] = b"achievement";
+pub const PLAYER_ACHIEVEMENTS_SEED: &[u8] = b"player_achievements";
+
+// Name blocklist constants
+pub const MAX_BLOCKED_NAME_RULES: usize = 256;
+
+// Holdings constants
+pub const MAX_HOLDINGS: usize = 32;
+
+// Valuation constants
+pub const STABLECOIN_DECIMALS: u8 = 6;
+pub const MAX_PRICE_AGE_SECONDS: i64 = 60;
+pub const MAX_PRICE_CONFIDENCE_BPS: u64 = 200;
+
+// Distributor constants
+pub const MAX_DISTRIBUTOR_CLAIMS: u64 = 65_536;
+
+// Staking constants
+pub const REWARD_RATE_SCALE: u64 = 1_000_000_000;
+
+// Inventory constants
+pub const INVENTORY_CAPACITY_INCREMENT: usize = 8;
+pub const MAX_INVENTORY_ITEMS: usize = 256;
+
+// Marketplace constants
+pub const MAX_MARKETPLACE_FEE_BPS: u16 = 1_000;
+
+// Leaderboard constants
+pub const MAX_LEADERBOARD_ENTRIES: usize = 100;
+
+// Achievement constants
+pub const MAX_ACHIEVEMENT_NAME_LENGTH: usize = 32;
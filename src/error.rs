@@ -20,6 +20,123 @@ pub enum PlayerWalletError {
     
     #[error("Unauthorized")]
     Unauthorized,
+    
+    #[error("Name not allowed")]
+    NameNotAllowed,
+    
+    #[error("Blocked name rule not found")]
+    BlockedNameRuleNotFound,
+    
+    #[error("Too many blocked name rules")]
+    TooManyBlockedNameRules,
+    
+    #[error("Insufficient treasury funds")]
+    InsufficientTreasuryFunds,
+    
+    #[error("Account layout is outdated, migrate it first")]
+    MigrationRequired,
+    
+    #[error("Invalid account type")]
+    InvalidAccountType,
+    
+    #[error("Wallet is frozen")]
+    WalletFrozen,
+    
+    #[error("Holding not found")]
+    HoldingNotFound,
+    
+    #[error("Holding already exists")]
+    HoldingAlreadyExists,
+    
+    #[error("Too many holdings")]
+    TooManyHoldings,
+    
+    #[error("Invalid token account")]
+    InvalidTokenAccount,
+    
+    #[error("Invalid price feed")]
+    InvalidPriceFeed,
+    
+    #[error("Price is stale")]
+    StalePrice,
+    
+    #[error("Price confidence interval too wide")]
+    PriceConfidenceTooWide,
+    
+    #[error("Vault still holds tokens")]
+    VaultNotEmpty,
+    
+    #[error("Insufficient SOL balance")]
+    InsufficientSolBalance,
+    
+    #[error("Epoch issuance cap exceeded")]
+    IssuanceCapExceeded,
+    
+    #[error("Invalid merkle proof")]
+    InvalidMerkleProof,
+    
+    #[error("Reward already claimed")]
+    AlreadyClaimed,
+    
+    #[error("Stake is still locked")]
+    StakeLocked,
+    
+    #[error("Insufficient unstaked balance")]
+    InsufficientStake,
+    
+    #[error("Mint extension not supported")]
+    UnsupportedMintExtension,
+    
+    #[error("Mint is not a game item")]
+    InvalidItemMint,
+    
+    #[error("Item not held by the player")]
+    ItemNotHeld,
+    
+    #[error("Item already registered")]
+    ItemAlreadyRegistered,
+    
+    #[error("Item not found")]
+    ItemNotFound,
+    
+    #[error("Inventory is full")]
+    InventoryFull,
+    
+    #[error("Marketplace fee too high")]
+    MarketplaceFeeTooHigh,
+    
+    #[error("Cannot buy your own item")]
+    CannotBuyOwnItem,
+    
+    #[error("Auction is not open for bids")]
+    AuctionNotOpen,
+    
+    #[error("Auction has not ended")]
+    AuctionNotOver,
+    
+    #[error("Bid too low")]
+    BidTooLow,
+    
+    #[error("Item is already rented")]
+    AlreadyRented,
+    
+    #[error("Rental has expired")]
+    RentalExpired,
+    
+    #[error("Rental has not expired")]
+    RentalActive,
+    
+    #[error("Season is already closed")]
+    SeasonClosed,
+    
+    #[error("Season has not closed")]
+    SeasonNotClosed,
+    
+    #[error("Season stats already snapshotted")]
+    SeasonAlreadySnapshotted,
+    
+    #[error("Season stats are no longer available")]
+    SeasonStatsUnavailable,
 }
 
 impl From<PlayerWalletError> for ProgramError {
@@ -0,0 +1,39 @@
+// Events logged for indexers
+// Each event is written with `sol_log_data` as two fields: the first 8 bytes
+// of `sha256("event:<TypeName>")`, then the Borsh-encoded event, so clients
+// can pick events out of transaction logs without parsing `msg!` text.
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{log::sol_log_data, pubkey::Pubkey};
+
+use crate::state::{WalletMetric, DISCRIMINATOR_LEN};
+
+/// An event logged by the program
+pub trait Event: BorshSerialize {
+    const DISCRIMINATOR: [u8; DISCRIMINATOR_LEN];
+
+    /// Write the event to the transaction log
+    fn emit(&self) {
+        // Events are plain structs of fixed-size fields, so encoding cannot fail
+        let data = self.try_to_vec().unwrap_or_default();
+        sol_log_data(&[&Self::DISCRIMINATOR, &data]);
+    }
+}
+
+/// A player wallet reached an achievement's threshold
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct AchievementUnlocked {
+    /// Player wallet account
+    pub player_wallet: Pubkey,
+    /// Achievement number
+    pub achievement_id: u8,
+    /// Metric the achievement tracks
+    pub metric: WalletMetric,
+    /// Metric value when the achievement was unlocked
+    pub value: u64,
+    /// Unix timestamp the achievement was unlocked
+    pub unlocked_at: i64,
+}
+
+impl Event for AchievementUnlocked {
+    const DISCRIMINATOR: [u8; DISCRIMINATOR_LEN] = [125, 160, 118, 30, 180, 209, 171, 62];
+}
@@ -0,0 +1,66 @@
+// `getProgramAccounts` filter builders for player wallets
+// These mirror the RPC `dataSize` and `memcmp` filters without depending on
+// the RPC client crates; convert them with e.g.
+// `RpcFilterType::Memcmp(Memcmp::new_raw_bytes(offset, bytes))`.
+use solana_program::pubkey::Pubkey;
+
+use crate::constants::PLAYER_WALLET_VERSION;
+use crate::state::{AccountType, PlayerWallet, WalletStatus};
+
+/// A single `getProgramAccounts` filter
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AccountFilter {
+    /// Match accounts whose data has exactly this length
+    DataSize(u64),
+    /// Match accounts whose data contains `bytes` at `offset`
+    Memcmp { offset: usize, bytes: Vec<u8> },
+}
+
+/// All player wallets stored with the latest layout
+pub fn player_wallets() -> Vec<AccountFilter> {
+    vec![
+        AccountFilter::DataSize(PlayerWallet::LEN as u64),
+        AccountFilter::Memcmp {
+            offset: PlayerWallet::DISCRIMINATOR_OFFSET,
+            bytes: PlayerWallet::DISCRIMINATOR.to_vec(),
+        },
+        AccountFilter::Memcmp {
+            offset: PlayerWallet::VERSION_OFFSET,
+            bytes: vec![PLAYER_WALLET_VERSION],
+        },
+    ]
+}
+
+/// All player wallets owned by `owner`
+pub fn player_wallets_by_owner(owner: &Pubkey) -> Vec<AccountFilter> {
+    let mut filters = player_wallets();
+    filters.push(AccountFilter::Memcmp {
+        offset: PlayerWallet::WALLET_ADDRESS_OFFSET,
+        bytes: owner.to_bytes().to_vec(),
+    });
+    filters
+}
+
+/// All player wallets with the given status
+pub fn player_wallets_by_status(status: WalletStatus) -> Vec<AccountFilter> {
+    let mut filters = player_wallets();
+    filters.push(AccountFilter::Memcmp {
+        offset: PlayerWallet::STATUS_OFFSET,
+        bytes: vec![status as u8],
+    });
+    filters
+}
+
+/// The player wallet with exactly this name
+pub fn player_wallets_by_name(name: &str) -> Vec<AccountFilter> {
+    let mut filters = player_wallets();
+    filters.push(AccountFilter::Memcmp {
+        offset: PlayerWallet::NAME_LEN_OFFSET,
+        bytes: vec![name.len() as u8],
+    });
+    filters.push(AccountFilter::Memcmp {
+        offset: PlayerWallet::NAME_OFFSET,
+        bytes: name.as_bytes().to_vec(),
+    });
+    filters
+}
@@ -0,0 +1,173 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+    msg,
+};
+
+use crate::state::{
+    save_account, Achievement, PlayerAchievements, PlayerWallet, ProgramConfig, WalletMetric,
+};
+use crate::error::PlayerWalletError;
+use crate::constants::{ACHIEVEMENT_SEED, MAX_ACHIEVEMENT_NAME_LENGTH, PLAYER_ACHIEVEMENTS_SEED};
+use crate::events::{AchievementUnlocked, Event};
+use crate::utils::create_pda_account;
+
+// Unlock achievements for one of the player wallets whose metrics the
+// instruction changed, from its optional trailing accounts: that player's
+// achievements PDA, then the achievement PDAs to check
+// Every achievement reached is unlocked, emitting an `AchievementUnlocked`
+// event for each newly unlocked one.
+pub(super) fn unlock_achievements(
+    program_id: &Pubkey,
+    player_wallets: &[(&Pubkey, &PlayerWallet)],
+    achievement_accounts: &[AccountInfo],
+) -> ProgramResult {
+    let (player_achievements_info, achievement_infos) = match achievement_accounts.split_first() {
+        Some(accounts) => accounts,
+        None => return Ok(()),
+    };
+    let (player_wallet_address, player_wallet) = player_wallets.iter()
+        .find(|(address, _)| *player_achievements_info.key == PlayerAchievements::find_address(address, program_id).0)
+        .copied()
+        .ok_or_else(|| {
+            msg!("Invalid player achievements account");
+            ProgramError::InvalidArgument
+        })?;
+    let mut player_achievements = PlayerAchievements::load(program_id, player_wallet_address, player_achievements_info)?;
+    
+    let now = Clock::get()?.unix_timestamp;
+    let mut unlocked_any = false;
+    for achievement_info in achievement_infos {
+        let achievement = Achievement::load(program_id, achievement_info)?;
+        if !achievement.is_reached(player_wallet) || !player_achievements.unlock(achievement.id) {
+            continue;
+        }
+        
+        unlocked_any = true;
+        msg!("Achievement {} unlocked", achievement.name);
+        AchievementUnlocked {
+            player_wallet: *player_wallet_address,
+            achievement_id: achievement.id,
+            metric: achievement.metric,
+            value: achievement.metric.value(player_wallet),
+            unlocked_at: now,
+        }.emit();
+    }
+    
+    if unlocked_any {
+        save_account(&player_achievements, player_achievements_info)?;
+    }
+    Ok(())
+}
+
+/// Define an achievement unlocked when a wallet metric reaches a threshold
+pub fn process_create_achievement(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    id: u8,
+    name: String,
+    metric: WalletMetric,
+    threshold: u64,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    
+    // Get the required accounts
+    let authority_info = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+    let achievement_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+    
+    ProgramConfig::load_with_authority(program_id, config_info, authority_info)?;
+    
+    if name.is_empty() {
+        msg!("Achievement name cannot be empty");
+        return Err(ProgramError::InvalidArgument);
+    }
+    if name.len() > MAX_ACHIEVEMENT_NAME_LENGTH {
+        return Err(PlayerWalletError::NameTooLong.into());
+    }
+    
+    let (achievement_address, bump) = Achievement::find_address(id, program_id);
+    if *achievement_info.key != achievement_address {
+        msg!("Invalid achievement account");
+        return Err(ProgramError::InvalidArgument);
+    }
+    if achievement_info.owner == program_id {
+        msg!("Achievement {} already exists", id);
+        return Err(PlayerWalletError::AccountAlreadyInitialized.into());
+    }
+    
+    create_pda_account(
+        authority_info,
+        achievement_info,
+        system_program_info,
+        program_id,
+        Achievement::LEN,
+        &[ACHIEVEMENT_SEED, &[id], &[bump]],
+    )?;
+    let achievement = Achievement {
+        is_initialized: true,
+        id,
+        name,
+        metric,
+        threshold,
+        bump,
+    };
+    save_account(&achievement, achievement_info)?;
+    
+    msg!("Achievement {} unlocks at {:?} {}", achievement.name, metric, threshold);
+    Ok(())
+}
+
+/// Create the account tracking a player's unlocked achievements
+pub fn process_create_player_achievements(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    
+    // Get the required accounts
+    let payer_info = next_account_info(account_info_iter)?;
+    let player_wallet_info = next_account_info(account_info_iter)?;
+    let player_achievements_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+    
+    // Ensure the player wallet account belongs to this program
+    if player_wallet_info.owner != program_id {
+        msg!("Player wallet account does not belong to this program");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    PlayerWallet::load(&player_wallet_info.try_borrow_data()?)?;
+    
+    let (player_achievements_address, bump) = PlayerAchievements::find_address(player_wallet_info.key, program_id);
+    if *player_achievements_info.key != player_achievements_address {
+        msg!("Invalid player achievements account");
+        return Err(ProgramError::InvalidArgument);
+    }
+    if player_achievements_info.owner == program_id {
+        return Err(PlayerWalletError::AccountAlreadyInitialized.into());
+    }
+    
+    create_pda_account(
+        payer_info,
+        player_achievements_info,
+        system_program_info,
+        program_id,
+        PlayerAchievements::LEN,
+        &[PLAYER_ACHIEVEMENTS_SEED, player_wallet_info.key.as_ref(), &[bump]],
+    )?;
+    let player_achievements = PlayerAchievements {
+        is_initialized: true,
+        player_wallet: *player_wallet_info.key,
+        bump,
+        unlocked: [0; 32],
+    };
+    save_account(&player_achievements, player_achievements_info)?;
+    
+    msg!("Achievements created for {}", player_wallet_info.key);
+    Ok(())
+}
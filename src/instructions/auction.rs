@@ -0,0 +1,563 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+    msg,
+};
+use spl_associated_token_account::instruction::create_associated_token_account_idempotent;
+
+use super::inventory::remove_escrowed_item;
+use super::achievement::unlock_achievements;
+use super::leaderboard::update_leaderboard;
+use super::marketplace::{check_token_account, refresh_currency_holding};
+use crate::state::{
+    save_account, Auction, AuctionKind, AuctionTerms, CurrencyConfig, MarketplaceConfig, PlayerWallet,
+    SeasonClock,
+};
+use crate::error::PlayerWalletError;
+use crate::constants::AUCTION_SEED;
+use crate::token::{
+    check_item_mint, check_mint_extensions, check_token_program, unpack_mint, unpack_token_account,
+};
+use crate::utils::{close_account, create_pda_account};
+
+// Move `amount` out of one of the auction's token accounts, signed by the auction PDA
+fn transfer_from_auction<'a>(
+    auction: &Auction,
+    auction_info: &AccountInfo<'a>,
+    source_info: &AccountInfo<'a>,
+    mint_info: &AccountInfo<'a>,
+    destination_info: &AccountInfo<'a>,
+    token_program_info: &AccountInfo<'a>,
+    amount: u64,
+) -> ProgramResult {
+    let decimals = unpack_mint(mint_info)?.decimals;
+    invoke_signed(
+        &spl_token_2022::instruction::transfer_checked(
+            token_program_info.key,
+            source_info.key,
+            mint_info.key,
+            destination_info.key,
+            auction_info.key,
+            &[],
+            amount,
+            decimals,
+        )?,
+        &[
+            source_info.clone(),
+            mint_info.clone(),
+            destination_info.clone(),
+            auction_info.clone(),
+            token_program_info.clone(),
+        ],
+        &[&[AUCTION_SEED, auction.mint.as_ref(), &[auction.bump]]],
+    )
+}
+
+// Close one of the auction's token accounts, refunding its rent to the seller's owner
+fn close_auction_token_account<'a>(
+    auction: &Auction,
+    auction_info: &AccountInfo<'a>,
+    token_account_info: &AccountInfo<'a>,
+    seller_owner_info: &AccountInfo<'a>,
+    token_program_info: &AccountInfo<'a>,
+) -> ProgramResult {
+    invoke_signed(
+        &spl_token_2022::instruction::close_account(
+            token_program_info.key,
+            token_account_info.key,
+            seller_owner_info.key,
+            auction_info.key,
+            &[],
+        )?,
+        &[
+            token_account_info.clone(),
+            seller_owner_info.clone(),
+            auction_info.clone(),
+            token_program_info.clone(),
+        ],
+        &[&[AUCTION_SEED, auction.mint.as_ref(), &[auction.bump]]],
+    )
+}
+
+/// Put an item up for auction, escrowing it in the auction's token account
+pub fn process_create_auction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    terms: AuctionTerms,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    
+    // Get the required accounts
+    let owner_info = next_account_info(account_info_iter)?;
+    let player_wallet_info = next_account_info(account_info_iter)?;
+    let auction_info = next_account_info(account_info_iter)?;
+    let mint_info = next_account_info(account_info_iter)?;
+    let token_account_info = next_account_info(account_info_iter)?;
+    let escrow_info = next_account_info(account_info_iter)?;
+    let currency_config_info = next_account_info(account_info_iter)?;
+    let currency_mint_info = next_account_info(account_info_iter)?;
+    let bid_escrow_info = next_account_info(account_info_iter)?;
+    let token_program_info = next_account_info(account_info_iter)?;
+    let currency_token_program_info = next_account_info(account_info_iter)?;
+    let associated_token_program_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+    let inventory_info = next_account_info(account_info_iter)?;
+    
+    check_token_program(token_program_info)?;
+    if *currency_token_program_info.key != spl_token::id() {
+        msg!("Invalid token program");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    
+    // Ensure the player wallet account belongs to this program
+    if player_wallet_info.owner != program_id {
+        msg!("Player wallet account does not belong to this program");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    PlayerWallet::load(&player_wallet_info.try_borrow_data()?)?.check_owner(owner_info)?;
+    
+    // Validate the schedule and prices
+    let now = Clock::get()?.unix_timestamp;
+    if terms.end_time <= terms.start_time || terms.end_time <= now {
+        msg!("Auction must end after it starts and in the future");
+        return Err(ProgramError::InvalidArgument);
+    }
+    let dutch = terms.kind == AuctionKind::Dutch;
+    if terms.start_price == 0 || (dutch && (terms.end_price == 0 || terms.end_price > terms.start_price)) {
+        msg!("Invalid auction prices");
+        return Err(ProgramError::InvalidArgument);
+    }
+    
+    // Only transferable game items can be auctioned
+    check_item_mint(mint_info)?;
+    check_mint_extensions(mint_info)?;
+    
+    let (auction_address, bump) = Auction::find_address(mint_info.key, program_id);
+    if *auction_info.key != auction_address {
+        msg!("Invalid auction account");
+        return Err(ProgramError::InvalidArgument);
+    }
+    if auction_info.owner == program_id {
+        msg!("Item is already auctioned");
+        return Err(PlayerWalletError::AccountAlreadyInitialized.into());
+    }
+    let currency_config = CurrencyConfig::load(program_id, currency_config_info)?;
+    if *currency_mint_info.key != currency_config.mint {
+        msg!("Invalid currency mint");
+        return Err(ProgramError::InvalidArgument);
+    }
+    check_token_account(escrow_info, &auction_address, mint_info.key, token_program_info.key)?;
+    check_token_account(bid_escrow_info, &auction_address, &currency_config.mint, &spl_token::id())?;
+    
+    // The seller funds the auction and both of its token accounts
+    create_pda_account(
+        owner_info,
+        auction_info,
+        system_program_info,
+        program_id,
+        Auction::LEN,
+        &[AUCTION_SEED, mint_info.key.as_ref(), &[bump]],
+    )?;
+    for (token_account_info, token_mint_info, program_info) in [
+        (escrow_info, mint_info, token_program_info),
+        (bid_escrow_info, currency_mint_info, currency_token_program_info),
+    ] {
+        invoke(
+            &create_associated_token_account_idempotent(
+                owner_info.key,
+                &auction_address,
+                token_mint_info.key,
+                program_info.key,
+            ),
+            &[
+                owner_info.clone(),
+                token_account_info.clone(),
+                auction_info.clone(),
+                token_mint_info.clone(),
+                system_program_info.clone(),
+                program_info.clone(),
+                associated_token_program_info.clone(),
+            ],
+        )?;
+    }
+    invoke(
+        &spl_token_2022::instruction::transfer_checked(
+            token_program_info.key,
+            token_account_info.key,
+            mint_info.key,
+            escrow_info.key,
+            owner_info.key,
+            &[],
+            1,
+            0,
+        )?,
+        &[
+            token_account_info.clone(),
+            mint_info.clone(),
+            escrow_info.clone(),
+            owner_info.clone(),
+            token_program_info.clone(),
+        ],
+    )?;
+    
+    // A transfer fee would leave nothing to sell
+    if unpack_token_account(escrow_info)?.amount != 1 {
+        msg!("Item {} did not arrive in escrow", mint_info.key);
+        return Err(PlayerWalletError::InvalidItemMint.into());
+    }
+    
+    // The seller can no longer equip an item that may be sold
+    remove_escrowed_item(program_id, player_wallet_info, inventory_info, mint_info.key)?;
+    
+    let auction = Auction {
+        is_initialized: true,
+        kind: terms.kind,
+        seller: *player_wallet_info.key,
+        mint: *mint_info.key,
+        start_time: terms.start_time,
+        end_time: terms.end_time,
+        start_price: terms.start_price,
+        end_price: terms.end_price,
+        min_bid_increment: terms.min_bid_increment,
+        highest_bid: 0,
+        highest_bidder: Pubkey::default(),
+        highest_bidder_owner: Pubkey::default(),
+        bump,
+    };
+    save_account(&auction, auction_info)?;
+    
+    msg!("Item {} auctioned from {} to {}", mint_info.key, auction.start_time, auction.end_time);
+    Ok(())
+}
+
+/// Bid on an auction, escrowing the bid and refunding the bidder it outbids
+/// English auctions take `amount` as the bid. Dutch auctions take `amount`
+/// as the most the bidder will pay and charge the current price, which wins
+/// the auction outright.
+pub fn process_place_bid(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: u64,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    
+    // Get the required accounts
+    let bidder_info = next_account_info(account_info_iter)?;
+    let player_wallet_info = next_account_info(account_info_iter)?;
+    let auction_info = next_account_info(account_info_iter)?;
+    let mint_info = next_account_info(account_info_iter)?;
+    let currency_config_info = next_account_info(account_info_iter)?;
+    let currency_mint_info = next_account_info(account_info_iter)?;
+    let bidder_currency_account_info = next_account_info(account_info_iter)?;
+    let bid_escrow_info = next_account_info(account_info_iter)?;
+    let refund_account_info = next_account_info(account_info_iter)?;
+    let refund_owner_info = next_account_info(account_info_iter)?;
+    let currency_token_program_info = next_account_info(account_info_iter)?;
+    let associated_token_program_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+    
+    if *currency_token_program_info.key != spl_token::id() {
+        msg!("Invalid token program");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    
+    // Ensure the player wallet account belongs to this program
+    if player_wallet_info.owner != program_id {
+        msg!("Player wallet account does not belong to this program");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    PlayerWallet::load(&player_wallet_info.try_borrow_data()?)?.check_owner(bidder_info)?;
+    
+    let mut auction = Auction::load(program_id, mint_info.key, auction_info)?;
+    if auction.seller == *player_wallet_info.key {
+        return Err(PlayerWalletError::CannotBuyOwnItem.into());
+    }
+    let now = Clock::get()?.unix_timestamp;
+    if !auction.is_open(now) {
+        return Err(PlayerWalletError::AuctionNotOpen.into());
+    }
+    let min_bid = auction.min_bid(now)?;
+    if amount < min_bid {
+        msg!("Bid {} is below the minimum of {}", amount, min_bid);
+        return Err(PlayerWalletError::BidTooLow.into());
+    }
+    let bid = match auction.kind {
+        AuctionKind::English => amount,
+        AuctionKind::Dutch => min_bid,
+    };
+    
+    let currency_config = CurrencyConfig::load(program_id, currency_config_info)?;
+    if *currency_mint_info.key != currency_config.mint {
+        msg!("Invalid currency mint");
+        return Err(ProgramError::InvalidArgument);
+    }
+    let currency_mint = &currency_config.mint;
+    check_token_account(bidder_currency_account_info, bidder_info.key, currency_mint, &spl_token::id())?;
+    check_token_account(bid_escrow_info, auction_info.key, currency_mint, &spl_token::id())?;
+    let decimals = unpack_mint(currency_mint_info)?.decimals;
+    
+    invoke(
+        &spl_token::instruction::transfer_checked(
+            &spl_token::id(),
+            bidder_currency_account_info.key,
+            currency_mint,
+            bid_escrow_info.key,
+            bidder_info.key,
+            &[],
+            bid,
+            decimals,
+        )?,
+        &[
+            bidder_currency_account_info.clone(),
+            currency_mint_info.clone(),
+            bid_escrow_info.clone(),
+            bidder_info.clone(),
+            currency_token_program_info.clone(),
+        ],
+    )?;
+    
+    // Refund the bidder who was outbid, recreating their token account if they closed it
+    if auction.has_bid() {
+        if *refund_owner_info.key != auction.highest_bidder_owner {
+            msg!("Invalid refund account");
+            return Err(ProgramError::InvalidArgument);
+        }
+        check_token_account(refund_account_info, refund_owner_info.key, currency_mint, &spl_token::id())?;
+        invoke(
+            &create_associated_token_account_idempotent(
+                bidder_info.key,
+                refund_owner_info.key,
+                currency_mint,
+                &spl_token::id(),
+            ),
+            &[
+                bidder_info.clone(),
+                refund_account_info.clone(),
+                refund_owner_info.clone(),
+                currency_mint_info.clone(),
+                system_program_info.clone(),
+                currency_token_program_info.clone(),
+                associated_token_program_info.clone(),
+            ],
+        )?;
+        transfer_from_auction(
+            &auction,
+            auction_info,
+            bid_escrow_info,
+            currency_mint_info,
+            refund_account_info,
+            currency_token_program_info,
+            auction.highest_bid,
+        )?;
+    }
+    
+    auction.highest_bid = bid;
+    auction.highest_bidder = *player_wallet_info.key;
+    auction.highest_bidder_owner = *bidder_info.key;
+    save_account(&auction, auction_info)?;
+    
+    msg!("Bid {} on item {}", bid, mint_info.key);
+    Ok(())
+}
+
+/// Settle an auction once it is over, paying the seller and handing over the item
+/// Anyone can settle. Without any bid, the item goes back to the seller and
+/// the seller's accounts are passed as the winner's.
+pub fn process_settle_auction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    
+    // Get the required accounts
+    let payer_info = next_account_info(account_info_iter)?;
+    let seller_wallet_info = next_account_info(account_info_iter)?;
+    let seller_holdings_info = next_account_info(account_info_iter)?;
+    let seller_info = next_account_info(account_info_iter)?;
+    let seller_currency_account_info = next_account_info(account_info_iter)?;
+    let winner_wallet_info = next_account_info(account_info_iter)?;
+    let winner_holdings_info = next_account_info(account_info_iter)?;
+    let winner_info = next_account_info(account_info_iter)?;
+    let winner_currency_account_info = next_account_info(account_info_iter)?;
+    let winner_item_account_info = next_account_info(account_info_iter)?;
+    let auction_info = next_account_info(account_info_iter)?;
+    let mint_info = next_account_info(account_info_iter)?;
+    let escrow_info = next_account_info(account_info_iter)?;
+    let currency_config_info = next_account_info(account_info_iter)?;
+    let currency_mint_info = next_account_info(account_info_iter)?;
+    let bid_escrow_info = next_account_info(account_info_iter)?;
+    let marketplace_config_info = next_account_info(account_info_iter)?;
+    let fee_account_info = next_account_info(account_info_iter)?;
+    let token_program_info = next_account_info(account_info_iter)?;
+    let currency_token_program_info = next_account_info(account_info_iter)?;
+    let associated_token_program_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+    let leaderboard_info = next_account_info(account_info_iter)?;
+    let season_clock_info = next_account_info(account_info_iter)?;
+    
+    // Ensure the payer is a signer
+    if !payer_info.is_signer {
+        msg!("Payer must be a signer");
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    
+    check_token_program(token_program_info)?;
+    if *currency_token_program_info.key != spl_token::id() {
+        msg!("Invalid token program");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    
+    let auction = Auction::load(program_id, mint_info.key, auction_info)?;
+    if !auction.is_over(Clock::get()?.unix_timestamp) {
+        return Err(PlayerWalletError::AuctionNotOver.into());
+    }
+    let winner = if auction.has_bid() { auction.highest_bidder } else { auction.seller };
+    if *seller_wallet_info.key != auction.seller || *winner_wallet_info.key != winner {
+        msg!("Invalid seller or winner wallet");
+        return Err(ProgramError::InvalidArgument);
+    }
+    
+    // Ensure both player wallet accounts belong to this program
+    if seller_wallet_info.owner != program_id || winner_wallet_info.owner != program_id {
+        msg!("Player wallet account does not belong to this program");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    let seller_owner = PlayerWallet::load(&seller_wallet_info.try_borrow_data()?)?.wallet_address;
+    let winner_owner = PlayerWallet::load(&winner_wallet_info.try_borrow_data()?)?.wallet_address;
+    if *seller_info.key != seller_owner || *winner_info.key != winner_owner {
+        msg!("Invalid seller or winner account");
+        return Err(ProgramError::InvalidArgument);
+    }
+    
+    let currency_config = CurrencyConfig::load(program_id, currency_config_info)?;
+    if *currency_mint_info.key != currency_config.mint {
+        msg!("Invalid currency mint");
+        return Err(ProgramError::InvalidArgument);
+    }
+    let currency_mint = &currency_config.mint;
+    check_token_account(escrow_info, auction_info.key, mint_info.key, token_program_info.key)?;
+    check_token_account(bid_escrow_info, auction_info.key, currency_mint, &spl_token::id())?;
+    check_token_account(winner_item_account_info, winner_info.key, mint_info.key, token_program_info.key)?;
+    
+    // Pay the marketplace fee on the winning bid and everything else in the bid
+    // escrow to the seller, so tokens sent to the escrow cannot keep it from closing
+    let escrowed = unpack_token_account(bid_escrow_info)?.amount;
+    let fee = if auction.has_bid() {
+        let marketplace_config = MarketplaceConfig::load(program_id, marketplace_config_info)?;
+        check_token_account(winner_currency_account_info, winner_info.key, currency_mint, &spl_token::id())?;
+        check_token_account(fee_account_info, &marketplace_config.fee_recipient, currency_mint, &spl_token::id())?;
+        marketplace_config.fee_on(auction.highest_bid)
+    } else {
+        0
+    };
+    if escrowed > 0 {
+        check_token_account(seller_currency_account_info, seller_info.key, currency_mint, &spl_token::id())?;
+        let proceeds = escrowed.checked_sub(fee).ok_or(ProgramError::ArithmeticOverflow)?;
+        invoke(
+            &create_associated_token_account_idempotent(
+                payer_info.key,
+                seller_info.key,
+                currency_mint,
+                &spl_token::id(),
+            ),
+            &[
+                payer_info.clone(),
+                seller_currency_account_info.clone(),
+                seller_info.clone(),
+                currency_mint_info.clone(),
+                system_program_info.clone(),
+                currency_token_program_info.clone(),
+                associated_token_program_info.clone(),
+            ],
+        )?;
+        for (destination_info, amount) in [
+            (seller_currency_account_info, proceeds),
+            (fee_account_info, fee),
+        ] {
+            if amount > 0 {
+                transfer_from_auction(
+                    &auction,
+                    auction_info,
+                    bid_escrow_info,
+                    currency_mint_info,
+                    destination_info,
+                    currency_token_program_info,
+                    amount,
+                )?;
+            }
+        }
+    }
+    
+    // Hand over the item, or return it to the seller
+    invoke(
+        &create_associated_token_account_idempotent(
+            payer_info.key,
+            winner_info.key,
+            mint_info.key,
+            token_program_info.key,
+        ),
+        &[
+            payer_info.clone(),
+            winner_item_account_info.clone(),
+            winner_info.clone(),
+            mint_info.clone(),
+            system_program_info.clone(),
+            token_program_info.clone(),
+            associated_token_program_info.clone(),
+        ],
+    )?;
+    transfer_from_auction(
+        &auction,
+        auction_info,
+        escrow_info,
+        mint_info,
+        winner_item_account_info,
+        token_program_info,
+        1,
+    )?;
+    
+    close_auction_token_account(&auction, auction_info, escrow_info, seller_info, token_program_info)?;
+    close_auction_token_account(&auction, auction_info, bid_escrow_info, seller_info, currency_token_program_info)?;
+    close_account(auction_info, seller_info)?;
+    
+    // Record the trade on both sides
+    if auction.has_bid() {
+        let seasons_closed = SeasonClock::seasons_closed(program_id, season_clock_info)?;
+        let mut seller_data = seller_wallet_info.try_borrow_mut_data()?;
+        let seller = PlayerWallet::load_mut(&mut seller_data)?;
+        seller.record_transaction(seasons_closed)?;
+        refresh_currency_holding(
+            program_id,
+            seller_wallet_info,
+            seller,
+            seller_holdings_info,
+            currency_mint,
+            seller_currency_account_info,
+        )?;
+        
+        let mut winner_data = winner_wallet_info.try_borrow_mut_data()?;
+        let winner = PlayerWallet::load_mut(&mut winner_data)?;
+        winner.record_transaction(seasons_closed)?;
+        refresh_currency_holding(
+            program_id,
+            winner_wallet_info,
+            winner,
+            winner_holdings_info,
+            currency_mint,
+            winner_currency_account_info,
+        )?;
+        let player_wallets = [(seller_wallet_info.key, &*seller), (winner_wallet_info.key, &*winner)];
+        update_leaderboard(program_id, leaderboard_info, &player_wallets)?;
+        unlock_achievements(program_id, &player_wallets, account_info_iter.as_slice())?;
+        
+        msg!("Item {} sold at auction for {} with a fee of {}", mint_info.key, auction.highest_bid, fee);
+    } else {
+        msg!("Item {} returned to the seller without bids", mint_info.key);
+    }
+    Ok(())
+}
@@ -3,17 +3,23 @@ use solana_program::{
     entrypoint::ProgramResult,
     program_error::ProgramError,
     pubkey::Pubkey,
-    program::invoke_signed,
+    program::invoke,
     system_instruction,
-    sysvar::{rent::Rent, Sysvar},
     msg,
 };
-use borsh::BorshSerialize;
 
-use crate::state::PlayerWallet;
+use crate::state::{
+    find_player_wallet_address, load_account, save_account, NameBlocklist, PlayerWallet,
+    ProfileIndex, ProgramConfig, Treasury,
+};
 use crate::error::PlayerWalletError;
+use crate::constants::{PLAYER_WALLET_SEED, PROFILE_INDEX_SEED};
+use crate::utils::create_pda_account;
 
 /// Create a new player wallet account
+/// The payer funds rent and the creation fee, so an onboarding service can
+/// sponsor wallets for owners that hold no SOL yet. Each owner can hold many
+/// profiles; the wallet is derived from the owner and the next profile index.
 pub fn process_create_player_wallet(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
@@ -22,47 +28,115 @@ pub fn process_create_player_wallet(
     let account_info_iter = &mut accounts.iter();
     
     // Get the required accounts
+    let payer_info = next_account_info(account_info_iter)?;
     let owner_info = next_account_info(account_info_iter)?;
+    let profile_index_info = next_account_info(account_info_iter)?;
     let player_wallet_info = next_account_info(account_info_iter)?;
     let system_program_info = next_account_info(account_info_iter)?;
+    let name_blocklist_info = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+    let treasury_info = next_account_info(account_info_iter)?;
     
-    // Ensure the owner is a signer
+    // Ensure the payer and the owner are signers
+    if !payer_info.is_signer {
+        msg!("Payer must be a signer");
+        return Err(ProgramError::MissingRequiredSignature);
+    }
     if !owner_info.is_signer {
         msg!("Owner must be a signer");
         return Err(ProgramError::MissingRequiredSignature);
     }
     
-    // Validate the player wallet account
-    if player_wallet_info.owner != program_id {
-        // Account doesn't belong to this program, create it
-        let rent = Rent::get()?;
-        let account_size = PlayerWallet::get_account_size(name.len());
-        let rent_lamports = rent.minimum_balance(account_size);
+    // Reject reserved and blocked names
+    NameBlocklist::load(program_id, name_blocklist_info)?.check_name(&name)?;
+    
+    // Load the owner's profile index, creating it for their first profile
+    let (profile_index_address, profile_index_bump) =
+        ProfileIndex::find_address(owner_info.key, program_id);
+    if *profile_index_info.key != profile_index_address {
+        msg!("Invalid profile index account");
+        return Err(ProgramError::InvalidArgument);
+    }
+    let mut profile_index = if profile_index_info.owner == program_id {
+        load_account(program_id, profile_index_info)?
+    } else {
+        create_pda_account(
+            payer_info,
+            profile_index_info,
+            system_program_info,
+            program_id,
+            ProfileIndex::LEN,
+            &[PROFILE_INDEX_SEED, owner_info.key.as_ref(), &[profile_index_bump]],
+        )?;
+        ProfileIndex {
+            is_initialized: true,
+            owner: *owner_info.key,
+            profile_count: 0,
+            bump: profile_index_bump,
+        }
+    };
+    
+    // Validate the player wallet account against the next profile index
+    let index = profile_index.profile_count;
+    let (player_wallet_address, player_wallet_bump) =
+        find_player_wallet_address(owner_info.key, index, program_id);
+    if *player_wallet_info.key != player_wallet_address {
+        msg!("Player wallet must be the PDA for profile {}", index);
+        return Err(ProgramError::InvalidArgument);
+    }
+    if player_wallet_info.owner == program_id {
+        return Err(PlayerWalletError::AccountAlreadyInitialized.into());
+    }
+    
+    // Create the account
+    create_pda_account(
+        payer_info,
+        player_wallet_info,
+        system_program_info,
+        program_id,
+        PlayerWallet::LEN,
+        &[
+            PLAYER_WALLET_SEED,
+            owner_info.key.as_ref(),
+            &index.to_le_bytes(),
+            &[player_wallet_bump],
+        ],
+    )?;
+    
+    profile_index.profile_count = index
+        .checked_add(1)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    save_account(&profile_index, profile_index_info)?;
+    
+    // Collect the protocol creation fee into the treasury
+    let config = ProgramConfig::load(program_id, config_info)?;
+    if config.creation_fee_lamports > 0 {
+        let mut treasury = Treasury::load(program_id, treasury_info)?;
         
-        // Create the account
-        invoke_signed(
-            &system_instruction::create_account(
-                owner_info.key,
-                player_wallet_info.key,
-                rent_lamports,
-                account_size as u64,
-                program_id,
+        invoke(
+            &system_instruction::transfer(
+                payer_info.key,
+                treasury_info.key,
+                config.creation_fee_lamports,
             ),
             &[
-                owner_info.clone(),
-                player_wallet_info.clone(),
+                payer_info.clone(),
+                treasury_info.clone(),
                 system_program_info.clone(),
             ],
-            &[],
         )?;
+        
+        treasury.total_collected = treasury.total_collected
+            .saturating_add(config.creation_fee_lamports);
+        save_account(&treasury, treasury_info)?;
     }
     
     // Create the player wallet
-    let player_wallet = PlayerWallet::new(name, *owner_info.key)?;
+    let player_wallet = PlayerWallet::new(&name, *owner_info.key)?;
     
-    // Serialize the player wallet data to the account
-    player_wallet.serialize(&mut *player_wallet_info.data.borrow_mut())?;
+    // Write the player wallet data to the account
+    player_wallet.save(player_wallet_info)?;
     
-    msg!("Player wallet created successfully");
+    msg!("Player wallet created successfully as profile {}", index);
     Ok(())
 }
@@ -0,0 +1,227 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+    msg,
+};
+use spl_associated_token_account::get_associated_token_address;
+use spl_token::state::{Account as TokenAccount, Mint};
+
+use crate::state::{
+    load_account, save_account, CurrencyConfig, Holdings, PlayerWallet, ProgramConfig, SeasonClock,
+};
+use crate::error::PlayerWalletError;
+use crate::constants::{CURRENCY_CONFIG_SEED, CURRENCY_MINT_SEED};
+use crate::utils::create_pda_account;
+use super::achievement::unlock_achievements;
+use super::leaderboard::update_leaderboard;
+
+/// Create the game currency mint, with the currency config PDA as mint authority
+pub fn process_initialize_currency(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    decimals: u8,
+    updater: Pubkey,
+    epoch_issuance_cap: u64,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    
+    // Get the required accounts
+    let authority_info = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+    let currency_config_info = next_account_info(account_info_iter)?;
+    let mint_info = next_account_info(account_info_iter)?;
+    let token_program_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+    
+    ProgramConfig::load_with_authority(program_id, config_info, authority_info)?;
+    
+    if *token_program_info.key != spl_token::id() {
+        msg!("Invalid token program");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    
+    // Ensure the currency config and mint accounts are the expected PDAs
+    let (currency_config_address, bump) = CurrencyConfig::find_address(program_id);
+    if *currency_config_info.key != currency_config_address {
+        msg!("Invalid currency config account");
+        return Err(ProgramError::InvalidArgument);
+    }
+    let (mint_address, mint_bump) = CurrencyConfig::find_mint_address(program_id);
+    if *mint_info.key != mint_address {
+        msg!("Invalid currency mint account");
+        return Err(ProgramError::InvalidArgument);
+    }
+    
+    // The currency can only be initialized once
+    if currency_config_info.owner == program_id {
+        return Err(PlayerWalletError::AccountAlreadyInitialized.into());
+    }
+    
+    create_pda_account(
+        authority_info,
+        currency_config_info,
+        system_program_info,
+        program_id,
+        CurrencyConfig::LEN,
+        &[CURRENCY_CONFIG_SEED, &[bump]],
+    )?;
+    
+    let currency_config = CurrencyConfig {
+        is_initialized: true,
+        mint: mint_address,
+        updater,
+        epoch_issuance_cap,
+        current_epoch: 0,
+        issued_this_epoch: 0,
+        bump,
+    };
+    save_account(&currency_config, currency_config_info)?;
+    
+    // Create the mint, owned by the token program
+    create_pda_account(
+        authority_info,
+        mint_info,
+        system_program_info,
+        &spl_token::id(),
+        Mint::LEN,
+        &[CURRENCY_MINT_SEED, &[mint_bump]],
+    )?;
+    invoke(
+        &spl_token::instruction::initialize_mint2(
+            token_program_info.key,
+            mint_info.key,
+            &currency_config_address,
+            None,
+            decimals,
+        )?,
+        &[mint_info.clone(), token_program_info.clone()],
+    )?;
+    
+    msg!("Game currency mint {} initialized", mint_address);
+    Ok(())
+}
+
+/// Change the reward updater and the per-epoch issuance cap
+pub fn process_set_currency_config(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    updater: Pubkey,
+    epoch_issuance_cap: u64,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    
+    // Get the required accounts
+    let authority_info = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+    let currency_config_info = next_account_info(account_info_iter)?;
+    
+    ProgramConfig::load_with_authority(program_id, config_info, authority_info)?;
+    let mut currency_config = CurrencyConfig::load(program_id, currency_config_info)?;
+    
+    currency_config.updater = updater;
+    currency_config.epoch_issuance_cap = epoch_issuance_cap;
+    save_account(&currency_config, currency_config_info)?;
+    
+    msg!("Currency updater set to {}, epoch cap {}", updater, epoch_issuance_cap);
+    Ok(())
+}
+
+/// Mint game currency to a player's token account as a reward
+pub fn process_mint_reward(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: u64,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    
+    // Get the required accounts
+    let updater_info = next_account_info(account_info_iter)?;
+    let currency_config_info = next_account_info(account_info_iter)?;
+    let mint_info = next_account_info(account_info_iter)?;
+    let player_wallet_info = next_account_info(account_info_iter)?;
+    let holdings_info = next_account_info(account_info_iter)?;
+    let token_account_info = next_account_info(account_info_iter)?;
+    let token_program_info = next_account_info(account_info_iter)?;
+    let leaderboard_info = next_account_info(account_info_iter)?;
+    let season_clock_info = next_account_info(account_info_iter)?;
+    
+    // Ensure the updater is a signer
+    if !updater_info.is_signer {
+        msg!("Updater must be a signer");
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    
+    let mut currency_config = CurrencyConfig::load(program_id, currency_config_info)?;
+    if currency_config.updater != *updater_info.key {
+        return Err(PlayerWalletError::Unauthorized.into());
+    }
+    if *mint_info.key != currency_config.mint {
+        msg!("Invalid currency mint account");
+        return Err(ProgramError::InvalidArgument);
+    }
+    if *token_program_info.key != spl_token::id() {
+        msg!("Invalid token program");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    
+    // Ensure the player wallet account belongs to this program
+    if player_wallet_info.owner != program_id {
+        msg!("Player wallet account does not belong to this program");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    let mut data = player_wallet_info.try_borrow_mut_data()?;
+    let player_wallet = PlayerWallet::load_mut(&mut data)?;
+    if player_wallet.is_frozen() {
+        return Err(PlayerWalletError::WalletFrozen.into());
+    }
+    
+    // Rewards go to the owner's associated token account, tracked as a holding
+    if *token_account_info.key != get_associated_token_address(&player_wallet.wallet_address, mint_info.key) {
+        msg!("Invalid token account for mint {}", mint_info.key);
+        return Err(PlayerWalletError::InvalidTokenAccount.into());
+    }
+    if *holdings_info.key != Holdings::find_address(player_wallet_info.key, program_id).0 {
+        msg!("Invalid holdings account");
+        return Err(ProgramError::InvalidArgument);
+    }
+    let mut holdings = load_account::<Holdings>(program_id, holdings_info)?;
+    let holding = holdings.get_mut(mint_info.key)?;
+    
+    currency_config.record_issuance(Clock::get()?.epoch, amount)?;
+    save_account(&currency_config, currency_config_info)?;
+    
+    invoke_signed(
+        &spl_token::instruction::mint_to(
+            token_program_info.key,
+            mint_info.key,
+            token_account_info.key,
+            currency_config_info.key,
+            &[],
+            amount,
+        )?,
+        &[
+            mint_info.clone(),
+            token_account_info.clone(),
+            currency_config_info.clone(),
+            token_program_info.clone(),
+        ],
+        &[&[CURRENCY_CONFIG_SEED, &[currency_config.bump]]],
+    )?;
+    
+    holding.amount = TokenAccount::unpack(&token_account_info.data.borrow())?.amount;
+    player_wallet.roll_over_season(SeasonClock::seasons_closed(program_id, season_clock_info)?);
+    holdings.apply_aggregates(player_wallet)?;
+    save_account(&holdings, holdings_info)?;
+    let player_wallets = [(player_wallet_info.key, &*player_wallet)];
+    update_leaderboard(program_id, leaderboard_info, &player_wallets)?;
+    unlock_achievements(program_id, &player_wallets, account_info_iter.as_slice())?;
+    
+    msg!("Minted {} reward to {}", amount, player_wallet_info.key);
+    Ok(())
+}
@@ -0,0 +1,207 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    msg,
+};
+use spl_associated_token_account::instruction::create_associated_token_account_idempotent;
+
+use crate::state::{
+    load_account, save_account, Distributor, Holdings, PlayerWallet, ProgramConfig, SeasonClock,
+};
+use crate::error::PlayerWalletError;
+use crate::constants::{DISTRIBUTOR_SEED, MAX_DISTRIBUTOR_CLAIMS};
+use crate::merkle;
+use crate::token::{
+    check_mint_extensions, check_token_program, get_token_account_address, unpack_token_account,
+};
+use crate::utils::create_pda_account;
+use super::achievement::unlock_achievements;
+use super::leaderboard::update_leaderboard;
+
+/// Create a merkle reward distribution and its token vault
+/// The vault is the distributor's associated token account and is funded
+/// with a regular token transfer once created.
+pub fn process_create_distributor(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    id: u64,
+    merkle_root: [u8; 32],
+    num_claims: u64,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    
+    // Get the required accounts
+    let authority_info = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+    let distributor_info = next_account_info(account_info_iter)?;
+    let mint_info = next_account_info(account_info_iter)?;
+    let vault_info = next_account_info(account_info_iter)?;
+    let token_program_info = next_account_info(account_info_iter)?;
+    let associated_token_program_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+    
+    ProgramConfig::load_with_authority(program_id, config_info, authority_info)?;
+    
+    check_token_program(token_program_info)?;
+    if num_claims > MAX_DISTRIBUTOR_CLAIMS {
+        msg!("A distributor holds at most {} claims", MAX_DISTRIBUTOR_CLAIMS);
+        return Err(ProgramError::InvalidArgument);
+    }
+    
+    let (distributor_address, bump) = Distributor::find_address(id, program_id);
+    if *distributor_info.key != distributor_address {
+        msg!("Invalid distributor account");
+        return Err(ProgramError::InvalidArgument);
+    }
+    if distributor_info.owner == program_id {
+        return Err(PlayerWalletError::AccountAlreadyInitialized.into());
+    }
+    check_mint_extensions(mint_info)?;
+    if *vault_info.key != get_token_account_address(&distributor_address, mint_info.key, token_program_info.key) {
+        msg!("Invalid distributor vault");
+        return Err(PlayerWalletError::InvalidTokenAccount.into());
+    }
+    
+    create_pda_account(
+        authority_info,
+        distributor_info,
+        system_program_info,
+        program_id,
+        Distributor::get_account_size(num_claims),
+        &[DISTRIBUTOR_SEED, &id.to_le_bytes(), &[bump]],
+    )?;
+    
+    let distributor = Distributor {
+        is_initialized: true,
+        id,
+        mint: *mint_info.key,
+        merkle_root,
+        num_claims,
+        total_claimed: 0,
+        bump,
+        claimed_bitmap: vec![0; Distributor::bitmap_len(num_claims)],
+    };
+    save_account(&distributor, distributor_info)?;
+    
+    invoke(
+        &create_associated_token_account_idempotent(
+            authority_info.key,
+            &distributor_address,
+            mint_info.key,
+            token_program_info.key,
+        ),
+        &[
+            authority_info.clone(),
+            vault_info.clone(),
+            distributor_info.clone(),
+            mint_info.clone(),
+            system_program_info.clone(),
+            token_program_info.clone(),
+            associated_token_program_info.clone(),
+        ],
+    )?;
+    
+    msg!("Distributor {} created with {} claims", id, num_claims);
+    Ok(())
+}
+
+/// Claim a player's share of a distribution with a merkle proof
+pub fn process_claim(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    index: u64,
+    amount: u64,
+    proof: Vec<[u8; 32]>,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    
+    // Get the required accounts
+    let owner_info = next_account_info(account_info_iter)?;
+    let player_wallet_info = next_account_info(account_info_iter)?;
+    let holdings_info = next_account_info(account_info_iter)?;
+    let distributor_info = next_account_info(account_info_iter)?;
+    let mint_info = next_account_info(account_info_iter)?;
+    let vault_info = next_account_info(account_info_iter)?;
+    let token_account_info = next_account_info(account_info_iter)?;
+    let token_program_info = next_account_info(account_info_iter)?;
+    let leaderboard_info = next_account_info(account_info_iter)?;
+    let season_clock_info = next_account_info(account_info_iter)?;
+    
+    check_token_program(token_program_info)?;
+    
+    // Ensure the player wallet account belongs to this program
+    if player_wallet_info.owner != program_id {
+        msg!("Player wallet account does not belong to this program");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    let mut data = player_wallet_info.try_borrow_mut_data()?;
+    let player_wallet = PlayerWallet::load_mut(&mut data)?;
+    player_wallet.check_owner(owner_info)?;
+    
+    let mut distributor = load_account::<Distributor>(program_id, distributor_info)?;
+    if *mint_info.key != distributor.mint {
+        msg!("Invalid distributor mint");
+        return Err(ProgramError::InvalidArgument);
+    }
+    if *vault_info.key != get_token_account_address(distributor_info.key, mint_info.key, token_program_info.key) {
+        msg!("Invalid distributor vault");
+        return Err(PlayerWalletError::InvalidTokenAccount.into());
+    }
+    if *token_account_info.key != get_token_account_address(owner_info.key, mint_info.key, token_program_info.key) {
+        msg!("Invalid token account for mint {}", mint_info.key);
+        return Err(PlayerWalletError::InvalidTokenAccount.into());
+    }
+    
+    // The claim is bound to the player wallet, not to whoever owns it later
+    let leaf = merkle::hash_leaf(index, player_wallet_info.key, amount);
+    if !merkle::verify(&proof, &distributor.merkle_root, leaf) {
+        return Err(PlayerWalletError::InvalidMerkleProof.into());
+    }
+    distributor.set_claimed(index)?;
+    distributor.total_claimed = distributor.total_claimed
+        .checked_add(amount)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    save_account(&distributor, distributor_info)?;
+    
+    if *holdings_info.key != Holdings::find_address(player_wallet_info.key, program_id).0 {
+        msg!("Invalid holdings account");
+        return Err(ProgramError::InvalidArgument);
+    }
+    let mut holdings = load_account::<Holdings>(program_id, holdings_info)?;
+    let holding = holdings.get_mut(mint_info.key)?;
+    
+    invoke_signed(
+        &spl_token_2022::instruction::transfer_checked(
+            token_program_info.key,
+            vault_info.key,
+            mint_info.key,
+            token_account_info.key,
+            distributor_info.key,
+            &[],
+            amount,
+            holding.decimals,
+        )?,
+        &[
+            vault_info.clone(),
+            mint_info.clone(),
+            token_account_info.clone(),
+            distributor_info.clone(),
+            token_program_info.clone(),
+        ],
+        &[&[DISTRIBUTOR_SEED, &distributor.id.to_le_bytes(), &[distributor.bump]]],
+    )?;
+    
+    holding.amount = unpack_token_account(token_account_info)?.amount;
+    player_wallet.roll_over_season(SeasonClock::seasons_closed(program_id, season_clock_info)?);
+    holdings.apply_aggregates(player_wallet)?;
+    save_account(&holdings, holdings_info)?;
+    let player_wallets = [(player_wallet_info.key, &*player_wallet)];
+    update_leaderboard(program_id, leaderboard_info, &player_wallets)?;
+    unlock_achievements(program_id, &player_wallets, account_info_iter.as_slice())?;
+    
+    msg!("Claim {} of distributor {} paid {}", index, distributor.id, amount);
+    Ok(())
+}
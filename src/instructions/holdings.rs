@@ -0,0 +1,231 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    msg,
+};
+
+use crate::state::{load_account, save_account, Holding, Holdings, PlayerWallet, SeasonClock};
+use crate::constants::HOLDINGS_SEED;
+use crate::error::PlayerWalletError;
+use crate::utils::{create_pda_account, resize_account};
+use crate::token::{get_token_account_address, unpack_mint, unpack_token_account};
+use super::achievement::unlock_achievements;
+use super::leaderboard::update_leaderboard;
+
+// Ensure the holdings account is the PDA of the given player wallet
+fn check_holdings_address(
+    program_id: &Pubkey,
+    player_wallet_info: &AccountInfo,
+    holdings_info: &AccountInfo,
+) -> Result<u8, ProgramError> {
+    let (holdings_address, bump) = Holdings::find_address(player_wallet_info.key, program_id);
+    if *holdings_info.key != holdings_address {
+        msg!("Invalid holdings account");
+        return Err(ProgramError::InvalidArgument);
+    }
+    Ok(bump)
+}
+
+// Read a holding's balance from the owner's associated token account for its mint
+// A closed or never-created account at that address holds nothing, so the
+// holding drops to zero instead of keeping a stale amount.
+pub(super) fn read_holding_amount(
+    holding: &Holding,
+    owner: &Pubkey,
+    token_account_info: &AccountInfo,
+) -> Result<u64, ProgramError> {
+    let expected_address = get_token_account_address(owner, &holding.mint, &holding.token_program);
+    if *token_account_info.key != expected_address {
+        msg!("Invalid token account for mint {}", holding.mint);
+        return Err(PlayerWalletError::InvalidTokenAccount.into());
+    }
+    if token_account_info.owner != &holding.token_program {
+        msg!("No token account for mint {}", holding.mint);
+        return Ok(0);
+    }
+    
+    let token_account = unpack_token_account(token_account_info)?;
+    if token_account.owner != *owner || token_account.mint != holding.mint {
+        msg!("Token account {} does not match the holding", token_account_info.key);
+        return Err(PlayerWalletError::InvalidTokenAccount.into());
+    }
+    
+    Ok(token_account.amount)
+}
+
+/// Add a token holding to a player wallet, creating its holdings account on first use
+/// The amount and price start at zero until synced and revalued.
+pub fn process_add_holding(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    mint: Pubkey,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    
+    // Get the required accounts
+    let payer_info = next_account_info(account_info_iter)?;
+    let owner_info = next_account_info(account_info_iter)?;
+    let player_wallet_info = next_account_info(account_info_iter)?;
+    let holdings_info = next_account_info(account_info_iter)?;
+    let mint_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+    let season_clock_info = next_account_info(account_info_iter)?;
+    
+    // Ensure the payer is a signer
+    if !payer_info.is_signer {
+        msg!("Payer must be a signer");
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    
+    // Ensure the player wallet account belongs to this program
+    if player_wallet_info.owner != program_id {
+        msg!("Player wallet account does not belong to this program");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    let mut data = player_wallet_info.try_borrow_mut_data()?;
+    let player_wallet = PlayerWallet::load_mut(&mut data)?;
+    player_wallet.check_owner(owner_info)?;
+    
+    // Load the holdings, creating the account for the first holding
+    let bump = check_holdings_address(program_id, player_wallet_info, holdings_info)?;
+    let mut holdings = if holdings_info.owner == program_id {
+        load_account::<Holdings>(program_id, holdings_info)?
+    } else {
+        create_pda_account(
+            payer_info,
+            holdings_info,
+            system_program_info,
+            program_id,
+            Holdings::get_account_size(0),
+            &[HOLDINGS_SEED, player_wallet_info.key.as_ref(), &[bump]],
+        )?;
+        Holdings {
+            is_initialized: true,
+            player_wallet: *player_wallet_info.key,
+            bump,
+            entries: Vec::new(),
+        }
+    };
+    
+    // Read the decimals from the mint, as they scale the holding's value
+    if *mint_info.key != mint {
+        msg!("Invalid mint account");
+        return Err(ProgramError::InvalidArgument);
+    }
+    let decimals = unpack_mint(mint_info)?.decimals;
+    
+    holdings.add(Holding {
+        mint,
+        amount: 0,
+        decimals,
+        last_price: 0,
+        vault_amount: 0,
+        token_program: *mint_info.owner,
+    })?;
+    player_wallet.roll_over_season(SeasonClock::seasons_closed(program_id, season_clock_info)?);
+    holdings.apply_aggregates(player_wallet)?;
+    
+    resize_account(
+        holdings_info,
+        payer_info,
+        system_program_info,
+        Holdings::get_account_size(holdings.entries.len()),
+    )?;
+    save_account(&holdings, holdings_info)?;
+    
+    msg!("Holding added");
+    Ok(())
+}
+
+/// Refresh one token holding from the owner's associated token account
+/// The owner can update a single holding without passing every token account
+/// to SyncFromTokenAccounts; prices still come from Revalue.
+pub fn process_update_holding(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    mint: Pubkey,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    
+    // Get the required accounts
+    let owner_info = next_account_info(account_info_iter)?;
+    let player_wallet_info = next_account_info(account_info_iter)?;
+    let holdings_info = next_account_info(account_info_iter)?;
+    let token_account_info = next_account_info(account_info_iter)?;
+    let leaderboard_info = next_account_info(account_info_iter)?;
+    let season_clock_info = next_account_info(account_info_iter)?;
+    
+    // Ensure the player wallet account belongs to this program
+    if player_wallet_info.owner != program_id {
+        msg!("Player wallet account does not belong to this program");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    let mut data = player_wallet_info.try_borrow_mut_data()?;
+    let player_wallet = PlayerWallet::load_mut(&mut data)?;
+    player_wallet.check_owner(owner_info)?;
+    
+    check_holdings_address(program_id, player_wallet_info, holdings_info)?;
+    let mut holdings = load_account::<Holdings>(program_id, holdings_info)?;
+    
+    let holding = holdings.get_mut(&mint)?;
+    holding.amount = read_holding_amount(holding, &player_wallet.wallet_address, token_account_info)?;
+    player_wallet.roll_over_season(SeasonClock::seasons_closed(program_id, season_clock_info)?);
+    holdings.apply_aggregates(player_wallet)?;
+    save_account(&holdings, holdings_info)?;
+    let player_wallets = [(player_wallet_info.key, &*player_wallet)];
+    update_leaderboard(program_id, leaderboard_info, &player_wallets)?;
+    unlock_achievements(program_id, &player_wallets, account_info_iter.as_slice())?;
+    
+    msg!("Holding updated");
+    Ok(())
+}
+
+/// Remove a token holding, refunding the freed rent to the owner
+pub fn process_remove_holding(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    mint: Pubkey,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    
+    // Get the required accounts
+    let owner_info = next_account_info(account_info_iter)?;
+    let player_wallet_info = next_account_info(account_info_iter)?;
+    let holdings_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+    let leaderboard_info = next_account_info(account_info_iter)?;
+    let season_clock_info = next_account_info(account_info_iter)?;
+    
+    // Ensure the player wallet account belongs to this program
+    if player_wallet_info.owner != program_id {
+        msg!("Player wallet account does not belong to this program");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    let mut data = player_wallet_info.try_borrow_mut_data()?;
+    let player_wallet = PlayerWallet::load_mut(&mut data)?;
+    player_wallet.check_owner(owner_info)?;
+    
+    check_holdings_address(program_id, player_wallet_info, holdings_info)?;
+    let mut holdings = load_account::<Holdings>(program_id, holdings_info)?;
+    
+    holdings.remove(&mint)?;
+    player_wallet.roll_over_season(SeasonClock::seasons_closed(program_id, season_clock_info)?);
+    holdings.apply_aggregates(player_wallet)?;
+    
+    // Serialize before shrinking so the trailing bytes are simply dropped
+    save_account(&holdings, holdings_info)?;
+    resize_account(
+        holdings_info,
+        owner_info,
+        system_program_info,
+        Holdings::get_account_size(holdings.entries.len()),
+    )?;
+    let player_wallets = [(player_wallet_info.key, &*player_wallet)];
+    update_leaderboard(program_id, leaderboard_info, &player_wallets)?;
+    unlock_achievements(program_id, &player_wallets, account_info_iter.as_slice())?;
+    
+    msg!("Holding removed");
+    Ok(())
+}
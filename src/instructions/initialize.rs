@@ -1,21 +1,66 @@
 use solana_program::{
     account_info::{next_account_info, AccountInfo},
+    bpf_loader_upgradeable::{self, UpgradeableLoaderState},
     entrypoint::ProgramResult,
     program_error::ProgramError,
     pubkey::Pubkey,
     msg,
 };
 
+use crate::state::{save_account, ProgramConfig, Treasury};
+use crate::error::PlayerWalletError;
+use crate::constants::{CONFIG_SEED, TREASURY_SEED};
+use crate::utils::create_pda_account;
+
+// Ensure the signer is the upgrade authority recorded in the program data account
+fn check_upgrade_authority(
+    program_id: &Pubkey,
+    program_data_info: &AccountInfo,
+    authority_info: &AccountInfo,
+) -> ProgramResult {
+    let (program_data_address, _) =
+        Pubkey::find_program_address(&[program_id.as_ref()], &bpf_loader_upgradeable::id());
+    if *program_data_info.key != program_data_address
+        || *program_data_info.owner != bpf_loader_upgradeable::id()
+    {
+        msg!("Invalid program data account");
+        return Err(ProgramError::InvalidArgument);
+    }
+    
+    // Program data layout: u32 state tag (3), u64 deployment slot,
+    // then the optional upgrade authority
+    let data = program_data_info.try_borrow_data()?;
+    if data.len() < UpgradeableLoaderState::size_of_programdata_metadata() || data[..4] != [3, 0, 0, 0] {
+        msg!("Invalid program data account");
+        return Err(ProgramError::InvalidAccountData);
+    }
+    let upgrade_authority = match data[12] {
+        1 => Some(Pubkey::try_from(&data[13..45]).map_err(|_| ProgramError::InvalidAccountData)?),
+        _ => None,
+    };
+    if upgrade_authority != Some(*authority_info.key) {
+        msg!("Only the upgrade authority can initialize the program");
+        return Err(PlayerWalletError::Unauthorized.into());
+    }
+    Ok(())
+}
+
 /// Initialize the program
-/// This is called once when the program is first deployed
+/// This is called once when the program is first deployed and records the
+/// program's upgrade authority as signing authority in the program config
+/// account, next to the treasury that collects protocol fees
 pub fn process_initialize(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
 ) -> ProgramResult {
     let account_info_iter = &mut accounts.iter();
     
-    // Get the program authority account
+    // Get the required accounts
     let authority_info = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+    let treasury_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+    let program_data_info = next_account_info(account_info_iter)?;
     
     // Ensure the authority is a signer
     if !authority_info.is_signer {
@@ -23,6 +68,62 @@ pub fn process_initialize(
         return Err(ProgramError::MissingRequiredSignature);
     }
     
+    // Only the deployer may claim the program authority, so the first
+    // caller cannot front-run it
+    check_upgrade_authority(program_id, program_data_info, authority_info)?;
+    
+    // Ensure the config and treasury accounts are the expected PDAs
+    let (config_address, bump) = ProgramConfig::find_address(program_id);
+    if *config_info.key != config_address {
+        msg!("Invalid program config account");
+        return Err(ProgramError::InvalidArgument);
+    }
+    let (treasury_address, treasury_bump) = Treasury::find_address(program_id);
+    if *treasury_info.key != treasury_address {
+        msg!("Invalid treasury account");
+        return Err(ProgramError::InvalidArgument);
+    }
+    
+    // The program can only be initialized once
+    if config_info.owner == program_id {
+        return Err(PlayerWalletError::AccountAlreadyInitialized.into());
+    }
+    
+    // Create the config account
+    create_pda_account(
+        authority_info,
+        config_info,
+        system_program_info,
+        program_id,
+        ProgramConfig::LEN,
+        &[CONFIG_SEED, &[bump]],
+    )?;
+    
+    let config = ProgramConfig {
+        is_initialized: true,
+        authority: *authority_info.key,
+        bump,
+        creation_fee_lamports: 0,
+    };
+    save_account(&config, config_info)?;
+    
+    // Create the treasury account
+    create_pda_account(
+        authority_info,
+        treasury_info,
+        system_program_info,
+        program_id,
+        Treasury::LEN,
+        &[TREASURY_SEED, &[treasury_bump]],
+    )?;
+    
+    let treasury = Treasury {
+        is_initialized: true,
+        bump: treasury_bump,
+        total_collected: 0,
+    };
+    save_account(&treasury, treasury_info)?;
+    
     msg!("Program initialized successfully");
     Ok(())
 }
@@ -0,0 +1,284 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+    msg,
+};
+
+use crate::state::{
+    find_vault_authority_address, load_account, save_account, Inventory, InventoryItem,
+    ItemRegistration, PlayerWallet, Rental,
+};
+use crate::error::PlayerWalletError;
+use crate::constants::{INVENTORY_SEED, ITEM_REGISTRATION_SEED};
+use crate::token::{check_item_mint, unpack_token_account};
+use crate::utils::{create_pda_account, resize_account};
+
+// Ensure the player holds the item, in the owner's token account or the vault
+// Any token account of the owner or the vault authority works, so an item
+// does not have to sit in an associated token account to be used. A rented
+// item is held by its rental escrow instead.
+fn check_item_held(
+    program_id: &Pubkey,
+    player_wallet_info: &AccountInfo,
+    owner: &Pubkey,
+    item: &InventoryItem,
+    token_account_info: &AccountInfo,
+) -> ProgramResult {
+    let mint = &item.mint;
+    let token_account = unpack_token_account(token_account_info)?;
+    let held_by_player = if item.is_rented() {
+        token_account.owner == Rental::find_address(mint, program_id).0
+    } else {
+        token_account.owner == *owner
+            || token_account.owner == find_vault_authority_address(player_wallet_info.key, program_id).0
+    };
+    if token_account.mint != *mint || !held_by_player || token_account.amount == 0 {
+        msg!("Item {} is not held by the player", mint);
+        return Err(PlayerWalletError::ItemNotHeld.into());
+    }
+    Ok(())
+}
+
+// Load a player wallet's inventory, creating an empty one on first use
+pub(super) fn load_or_create_inventory<'a>(
+    program_id: &Pubkey,
+    payer_info: &AccountInfo<'a>,
+    player_wallet_info: &AccountInfo<'a>,
+    inventory_info: &AccountInfo<'a>,
+    system_program_info: &AccountInfo<'a>,
+) -> Result<Inventory, ProgramError> {
+    if inventory_info.owner == program_id {
+        return Inventory::load(program_id, player_wallet_info.key, inventory_info);
+    }
+    
+    let (inventory_address, bump) = Inventory::find_address(player_wallet_info.key, program_id);
+    if *inventory_info.key != inventory_address {
+        msg!("Invalid inventory account");
+        return Err(ProgramError::InvalidArgument);
+    }
+    create_pda_account(
+        payer_info,
+        inventory_info,
+        system_program_info,
+        program_id,
+        Inventory::get_account_size(0),
+        &[INVENTORY_SEED, player_wallet_info.key.as_ref(), &[bump]],
+    )?;
+    Ok(Inventory {
+        is_initialized: true,
+        player_wallet: *player_wallet_info.key,
+        bump,
+        capacity: 0,
+        items: Vec::new(),
+    })
+}
+
+// Write an inventory, first growing the account to its capacity if needed
+pub(super) fn save_inventory<'a>(
+    inventory: &Inventory,
+    inventory_info: &AccountInfo<'a>,
+    payer_info: &AccountInfo<'a>,
+    system_program_info: &AccountInfo<'a>,
+) -> ProgramResult {
+    let size = Inventory::get_account_size(inventory.capacity as usize);
+    if inventory_info.data_len() < size {
+        resize_account(inventory_info, payer_info, system_program_info, size)?;
+    }
+    save_account(inventory, inventory_info)
+}
+
+// Drop an item the player puts in escrow from their inventory, returning the
+// removed entry if the item was registered
+// The inventory need not exist. Once the item is back, it can be registered again.
+pub(super) fn remove_escrowed_item(
+    program_id: &Pubkey,
+    player_wallet_info: &AccountInfo,
+    inventory_info: &AccountInfo,
+    mint: &Pubkey,
+) -> Result<Option<InventoryItem>, ProgramError> {
+    if *inventory_info.key != Inventory::find_address(player_wallet_info.key, program_id).0 {
+        msg!("Invalid inventory account");
+        return Err(ProgramError::InvalidArgument);
+    }
+    if inventory_info.owner != program_id {
+        return Ok(None);
+    }
+    
+    let mut inventory = Inventory::load(program_id, player_wallet_info.key, inventory_info)?;
+    let removed = inventory.remove(mint).ok();
+    if removed.is_some() {
+        save_account(&inventory, inventory_info)?;
+    }
+    Ok(removed)
+}
+
+/// Register an item NFT held by the player, creating or growing the inventory as needed
+/// An item is registered to one player wallet at a time, recorded in its
+/// registration PDA, so registering it again elsewhere moves it.
+pub fn process_register_item(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    slot: u8,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    
+    // Get the required accounts
+    let payer_info = next_account_info(account_info_iter)?;
+    let owner_info = next_account_info(account_info_iter)?;
+    let player_wallet_info = next_account_info(account_info_iter)?;
+    let inventory_info = next_account_info(account_info_iter)?;
+    let mint_info = next_account_info(account_info_iter)?;
+    let token_account_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+    let registration_info = next_account_info(account_info_iter)?;
+    let previous_inventory_info = next_account_info(account_info_iter)?;
+    
+    // Ensure the payer is a signer
+    if !payer_info.is_signer {
+        msg!("Payer must be a signer");
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    
+    // Ensure the player wallet account belongs to this program
+    if player_wallet_info.owner != program_id {
+        msg!("Player wallet account does not belong to this program");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    PlayerWallet::load(&player_wallet_info.try_borrow_data()?)?.check_owner(owner_info)?;
+    
+    let item = InventoryItem {
+        mint: *mint_info.key,
+        slot,
+        equipped: false,
+        usable_until: 0,
+    };
+    check_item_mint(mint_info)?;
+    check_item_held(program_id, player_wallet_info, owner_info.key, &item, token_account_info)?;
+    
+    // Load the item's registration, creating it the first time the item is registered
+    let (registration_address, registration_bump) = ItemRegistration::find_address(mint_info.key, program_id);
+    if *registration_info.key != registration_address {
+        msg!("Invalid item registration account");
+        return Err(ProgramError::InvalidArgument);
+    }
+    let mut registration = if registration_info.owner == program_id {
+        load_account::<ItemRegistration>(program_id, registration_info)?
+    } else {
+        create_pda_account(
+            payer_info,
+            registration_info,
+            system_program_info,
+            program_id,
+            ItemRegistration::LEN,
+            &[ITEM_REGISTRATION_SEED, mint_info.key.as_ref(), &[registration_bump]],
+        )?;
+        ItemRegistration {
+            is_initialized: true,
+            mint: *mint_info.key,
+            player_wallet: *player_wallet_info.key,
+            bump: registration_bump,
+        }
+    };
+    
+    // An item lives in one inventory only, so registering it elsewhere drops it
+    // from the profile it was registered to, which may have sold it or belong
+    // to the same owner
+    if registration.player_wallet != *player_wallet_info.key {
+        if previous_inventory_info.owner == program_id {
+            let mut previous_inventory = Inventory::load(
+                program_id,
+                &registration.player_wallet,
+                previous_inventory_info,
+            )?;
+            if previous_inventory.remove(mint_info.key).is_ok() {
+                save_account(&previous_inventory, previous_inventory_info)?;
+            }
+        } else if *previous_inventory_info.key != Inventory::find_address(&registration.player_wallet, program_id).0 {
+            msg!("Invalid inventory account");
+            return Err(ProgramError::InvalidArgument);
+        }
+        registration.player_wallet = *player_wallet_info.key;
+    }
+    save_account(&registration, registration_info)?;
+    
+    // Load the inventory, creating the account for the first item
+    let mut inventory = load_or_create_inventory(
+        program_id,
+        payer_info,
+        player_wallet_info,
+        inventory_info,
+        system_program_info,
+    )?;
+    inventory.add(item)?;
+    save_inventory(&inventory, inventory_info, payer_info, system_program_info)?;
+    
+    msg!("Item {} registered in slot {}", mint_info.key, slot);
+    Ok(())
+}
+
+/// Equip a registered item the player still holds
+pub fn process_equip_item(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    mint: Pubkey,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    
+    // Get the required accounts
+    let owner_info = next_account_info(account_info_iter)?;
+    let player_wallet_info = next_account_info(account_info_iter)?;
+    let inventory_info = next_account_info(account_info_iter)?;
+    let token_account_info = next_account_info(account_info_iter)?;
+    
+    // Ensure the player wallet account belongs to this program
+    if player_wallet_info.owner != program_id {
+        msg!("Player wallet account does not belong to this program");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    PlayerWallet::load(&player_wallet_info.try_borrow_data()?)?.check_owner(owner_info)?;
+    
+    let mut inventory = Inventory::load(program_id, player_wallet_info.key, inventory_info)?;
+    let item = inventory.get_mut(&mint)?;
+    if item.is_rented() && Clock::get()?.unix_timestamp >= item.usable_until {
+        msg!("Rental of item {} expired at {}", mint, item.usable_until);
+        return Err(PlayerWalletError::RentalExpired.into());
+    }
+    check_item_held(program_id, player_wallet_info, owner_info.key, item, token_account_info)?;
+    inventory.equip(&mint)?;
+    save_account(&inventory, inventory_info)?;
+    
+    msg!("Item {} equipped", mint);
+    Ok(())
+}
+
+/// Unequip a registered item
+pub fn process_unequip_item(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    mint: Pubkey,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    
+    // Get the required accounts
+    let owner_info = next_account_info(account_info_iter)?;
+    let player_wallet_info = next_account_info(account_info_iter)?;
+    let inventory_info = next_account_info(account_info_iter)?;
+    
+    // Ensure the player wallet account belongs to this program
+    if player_wallet_info.owner != program_id {
+        msg!("Player wallet account does not belong to this program");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    PlayerWallet::load(&player_wallet_info.try_borrow_data()?)?.check_owner(owner_info)?;
+    
+    let mut inventory = Inventory::load(program_id, player_wallet_info.key, inventory_info)?;
+    inventory.unequip(&mint)?;
+    save_account(&inventory, inventory_info)?;
+    
+    msg!("Item {} unequipped", mint);
+    Ok(())
+}
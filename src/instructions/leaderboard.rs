@@ -0,0 +1,102 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+    msg,
+};
+
+use crate::state::{save_account, Leaderboard, PlayerWallet, ProgramConfig, WalletMetric};
+use crate::constants::{LEADERBOARD_SEED, MAX_LEADERBOARD_ENTRIES};
+use crate::utils::create_pda_account;
+
+// Rank player wallets whose metrics the instruction changed
+// The leaderboard PDA is always passed so no rank can be left stale; nothing
+// is recorded until the leaderboard has been configured.
+pub(super) fn update_leaderboard(
+    program_id: &Pubkey,
+    leaderboard_info: &AccountInfo,
+    player_wallets: &[(&Pubkey, &PlayerWallet)],
+) -> ProgramResult {
+    if *leaderboard_info.key != Leaderboard::find_address(program_id).0 {
+        msg!("Invalid leaderboard account");
+        return Err(ProgramError::InvalidArgument);
+    }
+    if leaderboard_info.owner != program_id {
+        return Ok(());
+    }
+    
+    let mut leaderboard = Leaderboard::load(program_id, leaderboard_info)?;
+    let now = Clock::get()?.unix_timestamp;
+    let mut ranked_any = false;
+    for (player_wallet_address, player_wallet) in player_wallets {
+        let score = leaderboard.metric.value(player_wallet);
+        ranked_any |= leaderboard.record(player_wallet_address, score, now);
+    }
+    if ranked_any {
+        save_account(&leaderboard, leaderboard_info)?;
+    }
+    Ok(())
+}
+
+/// Set the metric and size of the leaderboard
+/// Changing the metric clears the board, shrinking it drops the lowest players.
+pub fn process_set_leaderboard_config(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    metric: WalletMetric,
+    size: u16,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    
+    // Get the required accounts
+    let authority_info = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+    let leaderboard_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+    
+    ProgramConfig::load_with_authority(program_id, config_info, authority_info)?;
+    
+    if size == 0 || size as usize > MAX_LEADERBOARD_ENTRIES {
+        msg!("Leaderboard size must be between 1 and {}", MAX_LEADERBOARD_ENTRIES);
+        return Err(ProgramError::InvalidArgument);
+    }
+    
+    let leaderboard = if leaderboard_info.owner == program_id {
+        let mut leaderboard = Leaderboard::load(program_id, leaderboard_info)?;
+        if leaderboard.metric != metric {
+            leaderboard.entries.clear();
+        }
+        leaderboard.metric = metric;
+        leaderboard.size = size;
+        leaderboard.entries.truncate(size as usize);
+        leaderboard
+    } else {
+        let (leaderboard_address, bump) = Leaderboard::find_address(program_id);
+        if *leaderboard_info.key != leaderboard_address {
+            msg!("Invalid leaderboard account");
+            return Err(ProgramError::InvalidArgument);
+        }
+        create_pda_account(
+            authority_info,
+            leaderboard_info,
+            system_program_info,
+            program_id,
+            Leaderboard::LEN,
+            &[LEADERBOARD_SEED, &[bump]],
+        )?;
+        Leaderboard {
+            is_initialized: true,
+            metric,
+            size,
+            bump,
+            entries: Vec::new(),
+        }
+    };
+    save_account(&leaderboard, leaderboard_info)?;
+    
+    msg!("Leaderboard ranks the top {} by {:?}", size, metric);
+    Ok(())
+}
@@ -0,0 +1,514 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    msg,
+};
+use spl_associated_token_account::instruction::create_associated_token_account_idempotent;
+
+use super::inventory::remove_escrowed_item;
+use super::achievement::unlock_achievements;
+use super::leaderboard::update_leaderboard;
+use crate::state::{
+    load_account, save_account, CurrencyConfig, Holdings, Listing, MarketplaceConfig, PlayerWallet,
+    ProgramConfig, SeasonClock,
+};
+use crate::error::PlayerWalletError;
+use crate::constants::{LISTING_SEED, MARKETPLACE_CONFIG_SEED, MAX_MARKETPLACE_FEE_BPS};
+use crate::token::{
+    check_item_mint, check_mint_extensions, check_token_program, get_token_account_address,
+    unpack_mint, unpack_token_account,
+};
+use crate::utils::{close_account, create_pda_account};
+
+// Ensure a token account is the associated token account of `wallet` for `mint`
+pub(super) fn check_token_account(
+    token_account_info: &AccountInfo,
+    wallet: &Pubkey,
+    mint: &Pubkey,
+    token_program_id: &Pubkey,
+) -> ProgramResult {
+    if *token_account_info.key != get_token_account_address(wallet, mint, token_program_id) {
+        msg!("Invalid token account for mint {}", mint);
+        return Err(PlayerWalletError::InvalidTokenAccount.into());
+    }
+    Ok(())
+}
+
+// Refresh the player's game currency holding from their token account, if they track one
+pub(super) fn refresh_currency_holding(
+    program_id: &Pubkey,
+    player_wallet_info: &AccountInfo,
+    player_wallet: &mut PlayerWallet,
+    holdings_info: &AccountInfo,
+    mint: &Pubkey,
+    token_account_info: &AccountInfo,
+) -> ProgramResult {
+    if *holdings_info.key != Holdings::find_address(player_wallet_info.key, program_id).0 {
+        msg!("Invalid holdings account");
+        return Err(ProgramError::InvalidArgument);
+    }
+    if holdings_info.owner != program_id {
+        return Ok(());
+    }
+    
+    let mut holdings = load_account::<Holdings>(program_id, holdings_info)?;
+    if let Some(holding) = holdings.entries.iter_mut().find(|holding| holding.mint == *mint) {
+        holding.amount = unpack_token_account(token_account_info)?.amount;
+        holdings.apply_aggregates(player_wallet)?;
+        save_account(&holdings, holdings_info)?;
+    }
+    Ok(())
+}
+
+// Send the escrowed item to `destination_info`, then close the escrow and the
+// listing, refunding their rent to the seller's owner
+fn release_escrow<'a>(
+    listing: &Listing,
+    listing_info: &AccountInfo<'a>,
+    mint_info: &AccountInfo<'a>,
+    escrow_info: &AccountInfo<'a>,
+    destination_info: &AccountInfo<'a>,
+    seller_owner_info: &AccountInfo<'a>,
+    token_program_info: &AccountInfo<'a>,
+) -> ProgramResult {
+    let listing_seeds: &[&[u8]] = &[LISTING_SEED, listing.mint.as_ref(), &[listing.bump]];
+    
+    invoke_signed(
+        &spl_token_2022::instruction::transfer_checked(
+            token_program_info.key,
+            escrow_info.key,
+            mint_info.key,
+            destination_info.key,
+            listing_info.key,
+            &[],
+            1,
+            0,
+        )?,
+        &[
+            escrow_info.clone(),
+            mint_info.clone(),
+            destination_info.clone(),
+            listing_info.clone(),
+            token_program_info.clone(),
+        ],
+        &[listing_seeds],
+    )?;
+    invoke_signed(
+        &spl_token_2022::instruction::close_account(
+            token_program_info.key,
+            escrow_info.key,
+            seller_owner_info.key,
+            listing_info.key,
+            &[],
+        )?,
+        &[
+            escrow_info.clone(),
+            seller_owner_info.clone(),
+            listing_info.clone(),
+            token_program_info.clone(),
+        ],
+        &[listing_seeds],
+    )?;
+    
+    close_account(listing_info, seller_owner_info)
+}
+
+/// Set the marketplace fee and the wallet receiving it
+pub fn process_set_marketplace_config(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    fee_bps: u16,
+    fee_recipient: Pubkey,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    
+    // Get the required accounts
+    let authority_info = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+    let marketplace_config_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+    
+    ProgramConfig::load_with_authority(program_id, config_info, authority_info)?;
+    
+    if fee_bps > MAX_MARKETPLACE_FEE_BPS {
+        msg!("Marketplace fee cannot exceed {} bps", MAX_MARKETPLACE_FEE_BPS);
+        return Err(PlayerWalletError::MarketplaceFeeTooHigh.into());
+    }
+    
+    let marketplace_config = if marketplace_config_info.owner == program_id {
+        let mut marketplace_config = MarketplaceConfig::load(program_id, marketplace_config_info)?;
+        marketplace_config.fee_bps = fee_bps;
+        marketplace_config.fee_recipient = fee_recipient;
+        marketplace_config
+    } else {
+        let (marketplace_config_address, bump) = MarketplaceConfig::find_address(program_id);
+        if *marketplace_config_info.key != marketplace_config_address {
+            msg!("Invalid marketplace config account");
+            return Err(ProgramError::InvalidArgument);
+        }
+        create_pda_account(
+            authority_info,
+            marketplace_config_info,
+            system_program_info,
+            program_id,
+            MarketplaceConfig::LEN,
+            &[MARKETPLACE_CONFIG_SEED, &[bump]],
+        )?;
+        MarketplaceConfig {
+            is_initialized: true,
+            fee_bps,
+            fee_recipient,
+            bump,
+        }
+    };
+    save_account(&marketplace_config, marketplace_config_info)?;
+    
+    msg!("Marketplace fee {} bps paid to {}", fee_bps, fee_recipient);
+    Ok(())
+}
+
+/// List an item for sale, escrowing it in the listing's token account
+pub fn process_list_item(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    price: u64,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    
+    // Get the required accounts
+    let owner_info = next_account_info(account_info_iter)?;
+    let player_wallet_info = next_account_info(account_info_iter)?;
+    let listing_info = next_account_info(account_info_iter)?;
+    let mint_info = next_account_info(account_info_iter)?;
+    let token_account_info = next_account_info(account_info_iter)?;
+    let escrow_info = next_account_info(account_info_iter)?;
+    let token_program_info = next_account_info(account_info_iter)?;
+    let associated_token_program_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+    let inventory_info = next_account_info(account_info_iter)?;
+    
+    check_token_program(token_program_info)?;
+    
+    // Ensure the player wallet account belongs to this program
+    if player_wallet_info.owner != program_id {
+        msg!("Player wallet account does not belong to this program");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    PlayerWallet::load(&player_wallet_info.try_borrow_data()?)?.check_owner(owner_info)?;
+    
+    if price == 0 {
+        msg!("Price must be positive");
+        return Err(ProgramError::InvalidArgument);
+    }
+    
+    // Only transferable game items can be traded
+    check_item_mint(mint_info)?;
+    check_mint_extensions(mint_info)?;
+    
+    let (listing_address, bump) = Listing::find_address(mint_info.key, program_id);
+    if *listing_info.key != listing_address {
+        msg!("Invalid listing account");
+        return Err(ProgramError::InvalidArgument);
+    }
+    if listing_info.owner == program_id {
+        msg!("Item is already listed");
+        return Err(PlayerWalletError::AccountAlreadyInitialized.into());
+    }
+    check_token_account(escrow_info, &listing_address, mint_info.key, token_program_info.key)?;
+    
+    create_pda_account(
+        owner_info,
+        listing_info,
+        system_program_info,
+        program_id,
+        Listing::LEN,
+        &[LISTING_SEED, mint_info.key.as_ref(), &[bump]],
+    )?;
+    invoke(
+        &create_associated_token_account_idempotent(
+            owner_info.key,
+            &listing_address,
+            mint_info.key,
+            token_program_info.key,
+        ),
+        &[
+            owner_info.clone(),
+            escrow_info.clone(),
+            listing_info.clone(),
+            mint_info.clone(),
+            system_program_info.clone(),
+            token_program_info.clone(),
+            associated_token_program_info.clone(),
+        ],
+    )?;
+    invoke(
+        &spl_token_2022::instruction::transfer_checked(
+            token_program_info.key,
+            token_account_info.key,
+            mint_info.key,
+            escrow_info.key,
+            owner_info.key,
+            &[],
+            1,
+            0,
+        )?,
+        &[
+            token_account_info.clone(),
+            mint_info.clone(),
+            escrow_info.clone(),
+            owner_info.clone(),
+            token_program_info.clone(),
+        ],
+    )?;
+    
+    // A transfer fee would leave nothing to sell
+    if unpack_token_account(escrow_info)?.amount != 1 {
+        msg!("Item {} did not arrive in escrow", mint_info.key);
+        return Err(PlayerWalletError::InvalidItemMint.into());
+    }
+    
+    // The seller can no longer equip an item that may be sold
+    remove_escrowed_item(program_id, player_wallet_info, inventory_info, mint_info.key)?;
+    
+    let listing = Listing {
+        is_initialized: true,
+        seller: *player_wallet_info.key,
+        mint: *mint_info.key,
+        price,
+        bump,
+    };
+    save_account(&listing, listing_info)?;
+    
+    msg!("Item {} listed for {}", mint_info.key, price);
+    Ok(())
+}
+
+/// Cancel a listing, returning the item to the seller
+pub fn process_cancel_listing(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    
+    // Get the required accounts
+    let owner_info = next_account_info(account_info_iter)?;
+    let player_wallet_info = next_account_info(account_info_iter)?;
+    let listing_info = next_account_info(account_info_iter)?;
+    let mint_info = next_account_info(account_info_iter)?;
+    let token_account_info = next_account_info(account_info_iter)?;
+    let escrow_info = next_account_info(account_info_iter)?;
+    let token_program_info = next_account_info(account_info_iter)?;
+    
+    check_token_program(token_program_info)?;
+    
+    // Ensure the player wallet account belongs to this program
+    if player_wallet_info.owner != program_id {
+        msg!("Player wallet account does not belong to this program");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    PlayerWallet::load(&player_wallet_info.try_borrow_data()?)?.check_owner(owner_info)?;
+    
+    let listing = Listing::load(program_id, mint_info.key, listing_info)?;
+    if listing.seller != *player_wallet_info.key {
+        return Err(PlayerWalletError::Unauthorized.into());
+    }
+    check_token_account(escrow_info, listing_info.key, mint_info.key, token_program_info.key)?;
+    
+    release_escrow(
+        &listing,
+        listing_info,
+        mint_info,
+        escrow_info,
+        token_account_info,
+        owner_info,
+        token_program_info,
+    )?;
+    
+    msg!("Listing of item {} cancelled", mint_info.key);
+    Ok(())
+}
+
+/// Buy a listed item, paying the seller and the marketplace fee in game currency
+pub fn process_buy_item(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    price: u64,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    
+    // Get the required accounts
+    let buyer_info = next_account_info(account_info_iter)?;
+    let buyer_wallet_info = next_account_info(account_info_iter)?;
+    let buyer_holdings_info = next_account_info(account_info_iter)?;
+    let buyer_item_account_info = next_account_info(account_info_iter)?;
+    let buyer_currency_account_info = next_account_info(account_info_iter)?;
+    let seller_wallet_info = next_account_info(account_info_iter)?;
+    let seller_holdings_info = next_account_info(account_info_iter)?;
+    let seller_info = next_account_info(account_info_iter)?;
+    let seller_currency_account_info = next_account_info(account_info_iter)?;
+    let listing_info = next_account_info(account_info_iter)?;
+    let mint_info = next_account_info(account_info_iter)?;
+    let escrow_info = next_account_info(account_info_iter)?;
+    let currency_config_info = next_account_info(account_info_iter)?;
+    let currency_mint_info = next_account_info(account_info_iter)?;
+    let marketplace_config_info = next_account_info(account_info_iter)?;
+    let fee_account_info = next_account_info(account_info_iter)?;
+    let token_program_info = next_account_info(account_info_iter)?;
+    let currency_token_program_info = next_account_info(account_info_iter)?;
+    let associated_token_program_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+    let leaderboard_info = next_account_info(account_info_iter)?;
+    let season_clock_info = next_account_info(account_info_iter)?;
+    
+    check_token_program(token_program_info)?;
+    if *currency_token_program_info.key != spl_token::id() {
+        msg!("Invalid token program");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    
+    let listing = Listing::load(program_id, mint_info.key, listing_info)?;
+    if *seller_wallet_info.key != listing.seller {
+        msg!("Invalid seller wallet");
+        return Err(ProgramError::InvalidArgument);
+    }
+    if *buyer_wallet_info.key == listing.seller {
+        return Err(PlayerWalletError::CannotBuyOwnItem.into());
+    }
+    if price != listing.price {
+        msg!("Listing price is {}, not {}", listing.price, price);
+        return Err(ProgramError::InvalidArgument);
+    }
+    
+    // Ensure both player wallet accounts belong to this program
+    if buyer_wallet_info.owner != program_id || seller_wallet_info.owner != program_id {
+        msg!("Player wallet account does not belong to this program");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    let mut buyer_data = buyer_wallet_info.try_borrow_mut_data()?;
+    let buyer = PlayerWallet::load_mut(&mut buyer_data)?;
+    buyer.check_owner(buyer_info)?;
+    let mut seller_data = seller_wallet_info.try_borrow_mut_data()?;
+    let seller = PlayerWallet::load_mut(&mut seller_data)?;
+    if *seller_info.key != seller.wallet_address {
+        msg!("Invalid seller account");
+        return Err(ProgramError::InvalidArgument);
+    }
+    
+    let currency_config = CurrencyConfig::load(program_id, currency_config_info)?;
+    if *currency_mint_info.key != currency_config.mint {
+        msg!("Invalid currency mint");
+        return Err(ProgramError::InvalidArgument);
+    }
+    let marketplace_config = MarketplaceConfig::load(program_id, marketplace_config_info)?;
+    
+    let currency_mint = &currency_config.mint;
+    check_token_account(buyer_currency_account_info, buyer_info.key, currency_mint, &spl_token::id())?;
+    check_token_account(seller_currency_account_info, seller_info.key, currency_mint, &spl_token::id())?;
+    check_token_account(fee_account_info, &marketplace_config.fee_recipient, currency_mint, &spl_token::id())?;
+    check_token_account(buyer_item_account_info, buyer_info.key, mint_info.key, token_program_info.key)?;
+    check_token_account(escrow_info, listing_info.key, mint_info.key, token_program_info.key)?;
+    
+    // Pay the seller and the marketplace
+    let decimals = unpack_mint(currency_mint_info)?.decimals;
+    let fee = marketplace_config.fee_on(price);
+    let payments = [
+        (seller_currency_account_info, price - fee),
+        (fee_account_info, fee),
+    ];
+    invoke(
+        &create_associated_token_account_idempotent(
+            buyer_info.key,
+            seller_info.key,
+            currency_mint,
+            &spl_token::id(),
+        ),
+        &[
+            buyer_info.clone(),
+            seller_currency_account_info.clone(),
+            seller_info.clone(),
+            currency_mint_info.clone(),
+            system_program_info.clone(),
+            currency_token_program_info.clone(),
+            associated_token_program_info.clone(),
+        ],
+    )?;
+    for (destination_info, amount) in payments.into_iter().filter(|(_, amount)| *amount > 0) {
+        invoke(
+            &spl_token::instruction::transfer_checked(
+                &spl_token::id(),
+                buyer_currency_account_info.key,
+                currency_mint,
+                destination_info.key,
+                buyer_info.key,
+                &[],
+                amount,
+                decimals,
+            )?,
+            &[
+                buyer_currency_account_info.clone(),
+                currency_mint_info.clone(),
+                destination_info.clone(),
+                buyer_info.clone(),
+                currency_token_program_info.clone(),
+            ],
+        )?;
+    }
+    
+    // Hand over the item
+    invoke(
+        &create_associated_token_account_idempotent(
+            buyer_info.key,
+            buyer_info.key,
+            mint_info.key,
+            token_program_info.key,
+        ),
+        &[
+            buyer_info.clone(),
+            buyer_item_account_info.clone(),
+            buyer_info.clone(),
+            mint_info.clone(),
+            system_program_info.clone(),
+            token_program_info.clone(),
+            associated_token_program_info.clone(),
+        ],
+    )?;
+    release_escrow(
+        &listing,
+        listing_info,
+        mint_info,
+        escrow_info,
+        buyer_item_account_info,
+        seller_info,
+        token_program_info,
+    )?;
+    
+    // Record the trade on both sides
+    let seasons_closed = SeasonClock::seasons_closed(program_id, season_clock_info)?;
+    buyer.record_transaction(seasons_closed)?;
+    seller.record_transaction(seasons_closed)?;
+    refresh_currency_holding(
+        program_id,
+        buyer_wallet_info,
+        buyer,
+        buyer_holdings_info,
+        currency_mint,
+        buyer_currency_account_info,
+    )?;
+    refresh_currency_holding(
+        program_id,
+        seller_wallet_info,
+        seller,
+        seller_holdings_info,
+        currency_mint,
+        seller_currency_account_info,
+    )?;
+    let player_wallets = [(buyer_wallet_info.key, &*buyer), (seller_wallet_info.key, &*seller)];
+    update_leaderboard(program_id, leaderboard_info, &player_wallets)?;
+    unlock_achievements(program_id, &player_wallets, account_info_iter.as_slice())?;
+    
+    msg!("Item {} sold for {} with a fee of {}", mint_info.key, price, fee);
+    Ok(())
+}
@@ -0,0 +1,56 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    msg,
+};
+
+use crate::state::PlayerWallet;
+use crate::constants::PLAYER_WALLET_VERSION;
+use crate::utils::resize_account;
+
+/// Rewrite a player wallet stored with an older layout to the latest layout
+/// Anyone can migrate a wallet; the payer covers any additional rent
+pub fn process_migrate_wallet(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    
+    // Get the required accounts
+    let payer_info = next_account_info(account_info_iter)?;
+    let player_wallet_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+    
+    // Ensure the payer is a signer
+    if !payer_info.is_signer {
+        msg!("Payer must be a signer");
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    
+    // Ensure the player wallet account belongs to this program
+    if player_wallet_info.owner != program_id {
+        msg!("Player wallet account does not belong to this program");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    
+    // Decode whatever layout the account currently holds
+    if PlayerWallet::load(&player_wallet_info.data.borrow()).is_ok() {
+        msg!("Player wallet already uses the latest layout");
+        return Ok(());
+    }
+    let player_wallet = PlayerWallet::unpack_any_version(&player_wallet_info.data.borrow())?;
+    
+    // Grow the account and rewrite it with the latest layout
+    resize_account(
+        player_wallet_info,
+        payer_info,
+        system_program_info,
+        PlayerWallet::LEN,
+    )?;
+    player_wallet.save(player_wallet_info)?;
+    
+    msg!("Player wallet migrated to version {}", PLAYER_WALLET_VERSION);
+    Ok(())
+}
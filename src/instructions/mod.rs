@@ -1,46 +1,806 @@
 mod initialize;
 mod create_player_wallet;
 mod update_player_wallet;
+mod name_blocklist;
+mod treasury;
+mod migrate_wallet;
+mod set_wallet_status;
+mod holdings;
+mod sync_from_token_accounts;
+mod price_feed;
+mod revalue;
+mod vault;
+mod sol_balance;
+mod transfer;
+mod currency;
+mod distributor;
+mod staking;
+mod inventory;
+mod marketplace;
+mod auction;
+mod rental;
+mod leaderboard;
+mod season;
+mod achievement;
 
 pub use initialize::*;
 pub use create_player_wallet::*;
 pub use update_player_wallet::*;
+pub use name_blocklist::*;
+pub use treasury::*;
+pub use migrate_wallet::*;
+pub use set_wallet_status::*;
+pub use holdings::*;
+pub use sync_from_token_accounts::*;
+pub use price_feed::*;
+pub use revalue::*;
+pub use vault::*;
+pub use sol_balance::*;
+pub use transfer::*;
+pub use currency::*;
+pub use distributor::*;
+pub use staking::*;
+pub use inventory::*;
+pub use marketplace::*;
+pub use auction::*;
+pub use rental::*;
+pub use leaderboard::*;
+pub use season::*;
+pub use achievement::*;
 
 use borsh::{BorshDeserialize, BorshSerialize};
-use solana_program::program_error::ProgramError;
+use solana_program::{program_error::ProgramError, pubkey::Pubkey};
 use crate::error::PlayerWalletError;
+use crate::state::{AuctionTerms, BlockedNameRule, WalletMetric, WalletStatus};
 
 #[derive(BorshSerialize, BorshDeserialize, Debug)]
 pub enum PlayerWalletInstruction {
-    /// Initialize the program
+    /// Initialize the program, restricted to its upgrade authority
     /// Accounts expected:
-    /// 0. `[signer]` The program authority
+    /// 0. `[signer, writable]` The program authority, which must be the upgrade authority
+    /// 1. `[writable]` The program config PDA
+    /// 2. `[writable]` The treasury PDA
+    /// 3. `[]` The system program
+    /// 4. `[]` The program data account of this program
     Initialize,
     
-    /// Create a new player wallet
+    /// Create a new player wallet as the owner's next profile
     /// Accounts expected:
-    /// 0. `[signer]` The account owner
-    /// 1. `[writable]` The player wallet account to create
-    /// 2. `[]` The system program
+    /// 0. `[signer, writable]` The payer, funding rent and the creation fee
+    /// 1. `[signer]` The account owner
+    /// 2. `[writable]` The owner's profile index PDA
+    /// 3. `[writable]` The player wallet PDA for the next profile index
+    /// 4. `[]` The system program
+    /// 5. `[]` The name blocklist PDA
+    /// 6. `[]` The program config PDA
+    /// 7. `[writable]` The treasury PDA
     CreatePlayerWallet {
         /// Player name (max 50 characters, letters and numbers only)
         name: String,
     },
     
     /// Update player wallet data
+    /// Token counts and values are derived from the holdings by AddHolding,
+    /// UpdateHolding, RemoveHolding, SyncFromTokenAccounts and Revalue, never
+    /// set directly, and transactions are only counted by trades and transfers.
     /// Accounts expected:
     /// 0. `[signer]` The account owner
     /// 1. `[writable]` The player wallet account to update
+    /// 2. `[]` The season clock PDA, which need not exist
     UpdatePlayerWallet {
-        /// Number of tokens
-        nb_tokens: u32,
-        /// Total amount of tokens
-        amount_total_tokens: u32,
-        /// Total value in stablecoin
-        amount_total_value_stablecoin: u32,
         /// Portfolio date (timestamp)
         date_portfolio: u64,
     },
+    
+    /// Add a rule to the name blocklist
+    /// Accounts expected:
+    /// 0. `[signer, writable]` The program authority
+    /// 1. `[]` The program config PDA
+    /// 2. `[writable]` The name blocklist PDA
+    /// 3. `[]` The system program
+    AddBlockedName {
+        /// Rule to add
+        rule: BlockedNameRule,
+    },
+    
+    /// Remove a rule from the name blocklist
+    /// Accounts expected:
+    /// 0. `[signer, writable]` The program authority
+    /// 1. `[]` The program config PDA
+    /// 2. `[writable]` The name blocklist PDA
+    /// 3. `[]` The system program
+    RemoveBlockedName {
+        /// Rule to remove
+        rule: BlockedNameRule,
+    },
+    
+    /// Set the fee charged on wallet creation
+    /// Accounts expected:
+    /// 0. `[signer]` The program authority
+    /// 1. `[writable]` The program config PDA
+    SetCreationFee {
+        /// Fee in lamports
+        creation_fee_lamports: u64,
+    },
+    
+    /// Withdraw collected fees from the treasury
+    /// Accounts expected:
+    /// 0. `[signer]` The program authority
+    /// 1. `[]` The program config PDA
+    /// 2. `[writable]` The treasury PDA
+    /// 3. `[writable]` The destination account
+    WithdrawTreasury {
+        /// Amount in lamports
+        amount: u64,
+    },
+    
+    /// Migrate a player wallet to the latest account layout
+    /// Accounts expected:
+    /// 0. `[signer, writable]` The payer, funding any additional rent
+    /// 1. `[writable]` The player wallet account to migrate
+    /// 2. `[]` The system program
+    MigrateWallet,
+    
+    /// Freeze or unfreeze a player wallet
+    /// Accounts expected:
+    /// 0. `[signer]` The program authority
+    /// 1. `[]` The program config PDA
+    /// 2. `[writable]` The player wallet account
+    SetWalletStatus {
+        /// New wallet status
+        status: WalletStatus,
+    },
+    
+    /// Add a token holding, keeping the wallet aggregates derived from the holdings
+    /// Accounts expected:
+    /// 0. `[signer, writable]` The payer, funding the holdings account rent
+    /// 1. `[signer]` The account owner
+    /// 2. `[writable]` The player wallet account
+    /// 3. `[writable]` The player wallet's holdings PDA
+    /// 4. `[]` The token mint
+    /// 5. `[]` The system program
+    /// 6. `[]` The season clock PDA, which need not exist
+    AddHolding {
+        /// Token mint
+        mint: Pubkey,
+    },
+    
+    /// Refresh a token holding's amount from the owner's associated token account
+    /// Accounts expected:
+    /// 0. `[signer]` The account owner
+    /// 1. `[writable]` The player wallet account
+    /// 2. `[writable]` The player wallet's holdings PDA
+    /// 3. `[]` The owner's associated token account for the mint
+    /// 4. `[writable]` The leaderboard PDA, which need not exist
+    /// 5. `[]` The season clock PDA, which need not exist
+    /// 6. `[writable]` Optional: the player's achievements PDA, to unlock achievements
+    /// 7. `[]` Optional: any number of achievement PDAs to check
+    UpdateHolding {
+        /// Token mint
+        mint: Pubkey,
+    },
+    
+    /// Remove a token holding
+    /// Accounts expected:
+    /// 0. `[signer, writable]` The account owner, receiving the freed rent
+    /// 1. `[writable]` The player wallet account
+    /// 2. `[writable]` The player wallet's holdings PDA
+    /// 3. `[]` The system program
+    /// 4. `[writable]` The leaderboard PDA, which need not exist
+    /// 5. `[]` The season clock PDA, which need not exist
+    /// 6. `[writable]` Optional: the player's achievements PDA, to unlock achievements
+    /// 7. `[]` Optional: any number of achievement PDAs to check
+    RemoveHolding {
+        /// Token mint
+        mint: Pubkey,
+    },
+    
+    /// Recompute holdings and wallet aggregates from the owner's token accounts
+    /// Accounts expected:
+    /// 0. `[writable]` The player wallet account
+    /// 1. `[writable]` The player wallet's holdings PDA
+    /// 2. `[writable]` The leaderboard PDA, which need not exist
+    /// 3. `[]` The season clock PDA, which need not exist
+    /// 4. `[]` The owner's associated token account for each holding, one per
+    ///    holding in holdings order
+    /// 5. `[writable]` Optional: the player's achievements PDA, to unlock achievements
+    /// 6. `[]` Optional: any number of achievement PDAs to check
+    SyncFromTokenAccounts,
+    
+    /// Create the price feed of a mint
+    /// Accounts expected:
+    /// 0. `[signer, writable]` The program authority
+    /// 1. `[]` The program config PDA
+    /// 2. `[writable]` The mint's price feed PDA
+    /// 3. `[]` The system program
+    CreatePriceFeed {
+        /// Token mint priced by the feed
+        mint: Pubkey,
+        /// Key allowed to publish prices
+        publisher: Pubkey,
+    },
+    
+    /// Publish a new price to a price feed
+    /// Accounts expected:
+    /// 0. `[signer]` The price feed publisher
+    /// 1. `[writable]` The price feed PDA
+    PublishPrice {
+        /// Price of one whole token in stablecoin, as `price * 10^expo`
+        price: i64,
+        /// Decimal exponent of the price and confidence
+        expo: i32,
+        /// Confidence interval around the price
+        conf: u64,
+    },
+    
+    /// Recompute the stablecoin value of a player's holdings from price feeds
+    /// Accounts expected:
+    /// 0. `[writable]` The player wallet account
+    /// 1. `[writable]` The player wallet's holdings PDA
+    /// 2. `[writable]` The leaderboard PDA, which need not exist
+    /// 3. `[]` The season clock PDA, which need not exist
+    /// 4. `[]` The price feed PDA of each holding, which need not exist, one per
+    ///    holding in holdings order
+    /// 5. `[writable]` Optional: the player's achievements PDA, to unlock achievements
+    /// 6. `[]` Optional: any number of achievement PDAs to check
+    Revalue,
+    
+    /// Deposit SPL tokens into the player's vault
+    /// Accounts expected:
+    /// 0. `[signer, writable]` The payer, funding the vault token account rent
+    /// 1. `[signer]` The account owner
+    /// 2. `[writable]` The player wallet account
+    /// 3. `[writable]` The player wallet's holdings PDA
+    /// 4. `[]` The token mint
+    /// 5. `[writable]` The owner's associated token account
+    /// 6. `[]` The player wallet's vault authority PDA
+    /// 7. `[writable]` The vault authority's associated token account
+    /// 8. `[]` The SPL token or Token-2022 program owning the mint
+    /// 9. `[]` The associated token account program
+    /// 10. `[]` The system program
+    /// 11. `[writable]` The leaderboard PDA, which need not exist
+    /// 12. `[]` The season clock PDA, which need not exist
+    /// 13. `[writable]` Optional: the player's achievements PDA, to unlock achievements
+    /// 14. `[]` Optional: any number of achievement PDAs to check
+    Deposit {
+        /// Amount in base units
+        amount: u64,
+    },
+    
+    /// Withdraw SPL tokens from the player's vault
+    /// Accounts expected:
+    /// 0. `[signer]` The account owner
+    /// 1. `[writable]` The player wallet account
+    /// 2. `[writable]` The player wallet's holdings PDA
+    /// 3. `[]` The token mint
+    /// 4. `[writable]` The owner's associated token account
+    /// 5. `[]` The player wallet's vault authority PDA
+    /// 6. `[writable]` The vault authority's associated token account
+    /// 7. `[]` The SPL token or Token-2022 program owning the mint
+    /// 8. `[]` The player wallet's stake position PDA, which need not exist
+    /// 9. `[writable]` The leaderboard PDA, which need not exist
+    /// 10. `[]` The season clock PDA, which need not exist
+    /// 11. `[writable]` Optional: the player's achievements PDA, to unlock achievements
+    /// 12. `[]` Optional: any number of achievement PDAs to check
+    Withdraw {
+        /// Amount in base units
+        amount: u64,
+    },
+    
+    /// Deposit SOL into the player wallet account
+    /// Accounts expected:
+    /// 0. `[signer, writable]` The account owner
+    /// 1. `[writable]` The player wallet account
+    /// 2. `[]` The system program
+    DepositSol {
+        /// Amount in lamports
+        amount: u64,
+    },
+    
+    /// Withdraw deposited SOL, never touching the rent-exempt minimum
+    /// Accounts expected:
+    /// 0. `[signer, writable]` The account owner
+    /// 1. `[writable]` The player wallet account
+    WithdrawSol {
+        /// Amount in lamports
+        amount: u64,
+    },
+    
+    /// Transfer deposited SOL to another player wallet
+    /// Accounts expected:
+    /// 0. `[signer]` The sender's account owner
+    /// 1. `[writable]` The sender's player wallet account
+    /// 2. `[writable]` The recipient's player wallet account
+    /// 3. `[writable]` The leaderboard PDA, which need not exist
+    /// 4. `[]` The season clock PDA, which need not exist
+    /// 5. `[writable]` Optional: either player's achievements PDA, to unlock their achievements
+    /// 6. `[]` Optional: any number of achievement PDAs to check
+    Transfer {
+        /// Amount in lamports
+        amount: u64,
+    },
+    
+    /// Create the game currency mint, controlled by the program
+    /// Accounts expected:
+    /// 0. `[signer, writable]` The program authority
+    /// 1. `[]` The program config PDA
+    /// 2. `[writable]` The currency config PDA, used as mint authority
+    /// 3. `[writable]` The game currency mint PDA
+    /// 4. `[]` The SPL token program
+    /// 5. `[]` The system program
+    InitializeCurrency {
+        /// Decimals of the game currency
+        decimals: u8,
+        /// Key allowed to mint rewards
+        updater: Pubkey,
+        /// Maximum amount mintable per epoch, in base units
+        epoch_issuance_cap: u64,
+    },
+    
+    /// Change the reward updater and the per-epoch issuance cap
+    /// Accounts expected:
+    /// 0. `[signer]` The program authority
+    /// 1. `[]` The program config PDA
+    /// 2. `[writable]` The currency config PDA
+    SetCurrencyConfig {
+        /// Key allowed to mint rewards
+        updater: Pubkey,
+        /// Maximum amount mintable per epoch, in base units
+        epoch_issuance_cap: u64,
+    },
+    
+    /// Mint game currency to a player as a reward
+    /// Accounts expected:
+    /// 0. `[signer]` The currency updater
+    /// 1. `[writable]` The currency config PDA
+    /// 2. `[writable]` The game currency mint PDA
+    /// 3. `[writable]` The player wallet account
+    /// 4. `[writable]` The player wallet's holdings PDA, holding the game currency
+    /// 5. `[writable]` The owner's associated token account for the game currency
+    /// 6. `[]` The SPL token program
+    /// 7. `[writable]` The leaderboard PDA, which need not exist
+    /// 8. `[]` The season clock PDA, which need not exist
+    /// 9. `[writable]` Optional: the player's achievements PDA, to unlock achievements
+    /// 10. `[]` Optional: any number of achievement PDAs to check
+    MintReward {
+        /// Amount in base units
+        amount: u64,
+    },
+    
+    /// Create a merkle reward distribution and its token vault
+    /// Accounts expected:
+    /// 0. `[signer, writable]` The program authority
+    /// 1. `[]` The program config PDA
+    /// 2. `[writable]` The distributor PDA
+    /// 3. `[]` The distributed token mint
+    /// 4. `[writable]` The distributor's associated token account
+    /// 5. `[]` The SPL token or Token-2022 program owning the mint
+    /// 6. `[]` The associated token account program
+    /// 7. `[]` The system program
+    CreateDistributor {
+        /// Identifier of the distribution
+        id: u64,
+        /// Root of the claims merkle tree
+        merkle_root: [u8; 32],
+        /// Number of claims in the tree
+        num_claims: u64,
+    },
+    
+    /// Claim a share of a distribution with a merkle proof
+    /// Accounts expected:
+    /// 0. `[signer]` The account owner
+    /// 1. `[writable]` The player wallet account named in the claim
+    /// 2. `[writable]` The player wallet's holdings PDA, holding the distributed token
+    /// 3. `[writable]` The distributor PDA
+    /// 4. `[]` The distributed token mint
+    /// 5. `[writable]` The distributor's associated token account
+    /// 6. `[writable]` The owner's associated token account
+    /// 7. `[]` The SPL token or Token-2022 program owning the mint
+    /// 8. `[writable]` The leaderboard PDA, which need not exist
+    /// 9. `[]` The season clock PDA, which need not exist
+    /// 10. `[writable]` Optional: the player's achievements PDA, to unlock achievements
+    /// 11. `[]` Optional: any number of achievement PDAs to check
+    Claim {
+        /// Index of the claim in the tree
+        index: u64,
+        /// Amount claimed, in base units
+        amount: u64,
+        /// Sibling hashes from the leaf up to the root
+        proof: Vec<[u8; 32]>,
+    },
+    
+    /// Set the staking reward rate and lock period
+    /// Accounts expected:
+    /// 0. `[signer, writable]` The program authority
+    /// 1. `[]` The program config PDA
+    /// 2. `[writable]` The staking config PDA
+    /// 3. `[]` The system program
+    SetStakingConfig {
+        /// Rewards per second for each `REWARD_RATE_SCALE` staked base units
+        reward_rate: u64,
+        /// Seconds staked tokens stay locked after the last stake
+        lock_period_seconds: i64,
+    },
+    
+    /// Stake game currency held in the player's vault
+    /// Accounts expected:
+    /// 0. `[signer, writable]` The account owner, funding the stake position rent
+    /// 1. `[]` The player wallet account
+    /// 2. `[]` The currency config PDA
+    /// 3. `[]` The staking config PDA
+    /// 4. `[writable]` The player wallet's stake position PDA
+    /// 5. `[]` The player's vault token account for the game currency
+    /// 6. `[]` The system program
+    Stake {
+        /// Amount in base units
+        amount: u64,
+    },
+    
+    /// Unstake game currency once the lock period is over
+    /// Accounts expected:
+    /// 0. `[signer]` The account owner
+    /// 1. `[]` The player wallet account
+    /// 2. `[]` The staking config PDA
+    /// 3. `[writable]` The player wallet's stake position PDA
+    Unstake {
+        /// Amount in base units
+        amount: u64,
+    },
+    
+    /// Mint accrued staking rewards into the player's vault
+    /// Accounts expected:
+    /// 0. `[signer]` The account owner
+    /// 1. `[writable]` The player wallet account
+    /// 2. `[writable]` The player wallet's holdings PDA, holding the game currency
+    /// 3. `[writable]` The currency config PDA, tracking the epoch issuance cap
+    /// 4. `[writable]` The game currency mint PDA
+    /// 5. `[]` The staking config PDA
+    /// 6. `[writable]` The player wallet's stake position PDA
+    /// 7. `[writable]` The player's vault token account for the game currency
+    /// 8. `[]` The SPL token program
+    /// 9. `[writable]` The leaderboard PDA, which need not exist
+    /// 10. `[]` The season clock PDA, which need not exist
+    /// 11. `[writable]` Optional: the player's achievements PDA, to unlock achievements
+    /// 12. `[]` Optional: any number of achievement PDAs to check
+    ClaimStakingRewards,
+    
+    /// Register an item NFT held by the player in their inventory
+    /// Accounts expected:
+    /// 0. `[signer, writable]` The payer for the inventory account rent
+    /// 1. `[signer]` The account owner
+    /// 2. `[]` The player wallet account
+    /// 3. `[writable]` The player wallet's inventory PDA
+    /// 4. `[]` The item mint
+    /// 5. `[]` A token account of the owner or the player's vault holding the item
+    /// 6. `[]` The system program
+    /// 7. `[writable]` The item's registration PDA
+    /// 8. `[writable]` The inventory PDA of the player wallet the item is registered
+    ///    to, only read when that is another wallet
+    RegisterItem {
+        /// Equipment slot the item goes in
+        slot: u8,
+    },
+    
+    /// Equip a registered item, unequipping the item in the same slot
+    /// Accounts expected:
+    /// 0. `[signer]` The account owner
+    /// 1. `[]` The player wallet account
+    /// 2. `[writable]` The player wallet's inventory PDA
+    /// 3. `[]` A token account of the owner or the player's vault holding the item,
+    ///    or the rental escrow for a rented item
+    EquipItem {
+        /// Item mint
+        mint: Pubkey,
+    },
+    
+    /// Unequip a registered item
+    /// Accounts expected:
+    /// 0. `[signer]` The account owner
+    /// 1. `[]` The player wallet account
+    /// 2. `[writable]` The player wallet's inventory PDA
+    UnequipItem {
+        /// Item mint
+        mint: Pubkey,
+    },
+    
+    /// Set the marketplace fee and the wallet receiving it
+    /// Accounts expected:
+    /// 0. `[signer, writable]` The program authority
+    /// 1. `[]` The program config PDA
+    /// 2. `[writable]` The marketplace config PDA
+    /// 3. `[]` The system program
+    SetMarketplaceConfig {
+        /// Fee charged on each sale, in basis points (max `MAX_MARKETPLACE_FEE_BPS`)
+        fee_bps: u16,
+        /// Wallet whose game currency associated token account receives the fees
+        fee_recipient: Pubkey,
+    },
+    
+    /// List an item for sale, escrowing it in a token account of the listing PDA
+    /// The item leaves the seller's inventory; after a cancellation it can be
+    /// registered again.
+    /// Accounts expected:
+    /// 0. `[signer, writable]` The account owner, funding the listing rent
+    /// 1. `[]` The seller's player wallet account
+    /// 2. `[writable]` The item's listing PDA
+    /// 3. `[]` The item mint
+    /// 4. `[writable]` The owner's token account holding the item
+    /// 5. `[writable]` The listing's associated token account for the item
+    /// 6. `[]` The SPL token or Token-2022 program owning the item mint
+    /// 7. `[]` The associated token account program
+    /// 8. `[]` The system program
+    /// 9. `[writable]` The seller's inventory PDA, which need not exist
+    ListItem {
+        /// Price in game currency base units
+        price: u64,
+    },
+    
+    /// Cancel a listing, returning the item and the rent to the seller
+    /// Accounts expected:
+    /// 0. `[signer, writable]` The account owner
+    /// 1. `[]` The seller's player wallet account
+    /// 2. `[writable]` The item's listing PDA
+    /// 3. `[]` The item mint
+    /// 4. `[writable]` The owner's token account receiving the item
+    /// 5. `[writable]` The listing's associated token account for the item
+    /// 6. `[]` The SPL token or Token-2022 program owning the item mint
+    CancelListing,
+    
+    /// Buy a listed item, paying the seller and the marketplace fee in game currency
+    /// Accounts expected:
+    /// 0. `[signer, writable]` The buyer's account owner
+    /// 1. `[writable]` The buyer's player wallet account
+    /// 2. `[writable]` The buyer's holdings PDA, which need not exist
+    /// 3. `[writable]` The buyer's associated token account for the item
+    /// 4. `[writable]` The buyer's associated token account for the game currency
+    /// 5. `[writable]` The seller's player wallet account
+    /// 6. `[writable]` The seller's holdings PDA, which need not exist
+    /// 7. `[writable]` The seller's account owner, refunded the listing rent
+    /// 8. `[writable]` The seller's associated token account for the game currency
+    /// 9. `[writable]` The item's listing PDA
+    /// 10. `[]` The item mint
+    /// 11. `[writable]` The listing's associated token account for the item
+    /// 12. `[]` The currency config PDA
+    /// 13. `[]` The game currency mint PDA
+    /// 14. `[]` The marketplace config PDA
+    /// 15. `[writable]` The fee recipient's associated token account for the game currency
+    /// 16. `[]` The SPL token or Token-2022 program owning the item mint
+    /// 17. `[]` The SPL token program
+    /// 18. `[]` The associated token account program
+    /// 19. `[]` The system program
+    /// 20. `[writable]` The leaderboard PDA, which need not exist
+    /// 21. `[]` The season clock PDA, which need not exist
+    /// 22. `[writable]` Optional: either player's achievements PDA, to unlock their achievements
+    /// 23. `[]` Optional: any number of achievement PDAs to check
+    BuyItem {
+        /// Price the buyer agreed to, which must match the listing
+        price: u64,
+    },
+    
+    /// Put an item up for an English or Dutch auction in game currency
+    /// The item leaves the seller's inventory; if it comes back unsold, it can
+    /// be registered again.
+    /// Accounts expected:
+    /// 0. `[signer, writable]` The account owner, funding the auction rent
+    /// 1. `[]` The seller's player wallet account
+    /// 2. `[writable]` The item's auction PDA
+    /// 3. `[]` The item mint
+    /// 4. `[writable]` The owner's token account holding the item
+    /// 5. `[writable]` The auction's associated token account for the item
+    /// 6. `[]` The currency config PDA
+    /// 7. `[]` The game currency mint PDA
+    /// 8. `[writable]` The auction's associated token account for the game currency
+    /// 9. `[]` The SPL token or Token-2022 program owning the item mint
+    /// 10. `[]` The SPL token program
+    /// 11. `[]` The associated token account program
+    /// 12. `[]` The system program
+    /// 13. `[writable]` The seller's inventory PDA, which need not exist
+    CreateAuction {
+        /// Mode, schedule and prices of the auction
+        terms: AuctionTerms,
+    },
+    
+    /// Bid on an open auction, refunding the bidder it outbids
+    /// Accounts 8 and 9 are unused until the auction has a bid.
+    /// Accounts expected:
+    /// 0. `[signer, writable]` The bidder's account owner
+    /// 1. `[]` The bidder's player wallet account
+    /// 2. `[writable]` The item's auction PDA
+    /// 3. `[]` The item mint
+    /// 4. `[]` The currency config PDA
+    /// 5. `[]` The game currency mint PDA
+    /// 6. `[writable]` The bidder's associated token account for the game currency
+    /// 7. `[writable]` The auction's associated token account for the game currency
+    /// 8. `[writable]` The previous highest bidder's associated token account for the game currency
+    /// 9. `[]` The previous highest bidder's account owner
+    /// 10. `[]` The SPL token program
+    /// 11. `[]` The associated token account program
+    /// 12. `[]` The system program
+    PlaceBid {
+        /// Bid (English) or most the bidder will pay (Dutch), in game currency base units
+        amount: u64,
+    },
+    
+    /// Settle an auction that is over, paying the seller and handing over the item
+    /// The seller receives the whole bid escrow balance, less the fee on the
+    /// winning bid. Without bids, the seller's accounts are also passed as the winner's.
+    /// Accounts expected:
+    /// 0. `[signer, writable]` The payer for any token account created
+    /// 1. `[writable]` The seller's player wallet account
+    /// 2. `[writable]` The seller's holdings PDA, which need not exist
+    /// 3. `[writable]` The seller's account owner, refunded the auction rent
+    /// 4. `[writable]` The seller's associated token account for the game currency
+    /// 5. `[writable]` The winner's player wallet account
+    /// 6. `[writable]` The winner's holdings PDA, which need not exist
+    /// 7. `[]` The winner's account owner
+    /// 8. `[]` The winner's associated token account for the game currency
+    /// 9. `[writable]` The winner's associated token account for the item
+    /// 10. `[writable]` The item's auction PDA
+    /// 11. `[]` The item mint
+    /// 12. `[writable]` The auction's associated token account for the item
+    /// 13. `[]` The currency config PDA
+    /// 14. `[]` The game currency mint PDA
+    /// 15. `[writable]` The auction's associated token account for the game currency
+    /// 16. `[]` The marketplace config PDA
+    /// 17. `[writable]` The fee recipient's associated token account for the game currency
+    /// 18. `[]` The SPL token or Token-2022 program owning the item mint
+    /// 19. `[]` The SPL token program
+    /// 20. `[]` The associated token account program
+    /// 21. `[]` The system program
+    /// 22. `[writable]` The leaderboard PDA, which need not exist
+    /// 23. `[]` The season clock PDA, which need not exist
+    /// 24. `[writable]` Optional: either player's achievements PDA, to unlock their achievements
+    /// 25. `[]` Optional: any number of achievement PDAs to check
+    SettleAuction,
+    
+    /// Escrow an item so other players can rent it for a fixed period
+    /// The item leaves the lender's inventory until it is reclaimed.
+    /// Accounts expected:
+    /// 0. `[signer, writable]` The account owner, funding the rental rent
+    /// 1. `[]` The lender's player wallet account
+    /// 2. `[writable]` The item's rental PDA
+    /// 3. `[]` The item mint
+    /// 4. `[writable]` The owner's token account holding the item
+    /// 5. `[writable]` The rental's associated token account for the item
+    /// 6. `[]` The SPL token or Token-2022 program owning the item mint
+    /// 7. `[]` The associated token account program
+    /// 8. `[]` The system program
+    /// 9. `[writable]` The lender's inventory PDA, which need not exist
+    CreateRental {
+        /// Price of one rental period, in game currency base units
+        price: u64,
+        /// Length of a rental period in seconds
+        duration_seconds: i64,
+    },
+    
+    /// Rent an item for one period, adding it to the renter's inventory until it expires
+    /// Accounts expected:
+    /// 0. `[signer, writable]` The renter's account owner
+    /// 1. `[writable]` The renter's player wallet account
+    /// 2. `[writable]` The renter's holdings PDA, which need not exist
+    /// 3. `[writable]` The renter's inventory PDA, created on first use
+    /// 4. `[writable]` The renter's associated token account for the game currency
+    /// 5. `[writable]` The lender's player wallet account
+    /// 6. `[writable]` The lender's holdings PDA, which need not exist
+    /// 7. `[]` The lender's account owner
+    /// 8. `[writable]` The lender's associated token account for the game currency
+    /// 9. `[writable]` The item's rental PDA
+    /// 10. `[]` The item mint
+    /// 11. `[]` The currency config PDA
+    /// 12. `[]` The game currency mint PDA
+    /// 13. `[]` The SPL token program
+    /// 14. `[]` The associated token account program
+    /// 15. `[]` The system program
+    /// 16. `[writable]` The leaderboard PDA, which need not exist
+    /// 17. `[]` The season clock PDA, which need not exist
+    /// 18. `[writable]` Optional: either player's achievements PDA, to unlock their achievements
+    /// 19. `[]` Optional: any number of achievement PDAs to check
+    RentItem {
+        /// Price the renter agreed to, which must match the rental
+        price: u64,
+        /// Equipment slot the item goes in, defined by the game
+        slot: u8,
+    },
+    
+    /// Return an item to its lender and close the rental
+    /// Anyone may reclaim an expired rental. The lender may also withdraw an
+    /// item nobody is renting, in which case account 4 is unused. An item the
+    /// lender had registered goes back to its slot in the lender's inventory,
+    /// unequipped.
+    /// Accounts expected:
+    /// 0. `[signer, writable]` The caller, paying for the lender's token account
+    ///    and inventory growth if needed
+    /// 1. `[]` The lender's player wallet account
+    /// 2. `[writable]` The lender's account owner, refunded the rental rent
+    /// 3. `[writable]` The lender's associated token account for the item
+    /// 4. `[writable]` The renter's inventory PDA
+    /// 5. `[writable]` The item's rental PDA
+    /// 6. `[]` The item mint
+    /// 7. `[writable]` The rental's associated token account for the item
+    /// 8. `[]` The SPL token or Token-2022 program owning the item mint
+    /// 9. `[]` The associated token account program
+    /// 10. `[]` The system program
+    /// 11. `[writable]` The lender's inventory PDA, unused if the lender had not registered the item
+    ReclaimRental,
+    
+    /// Set the metric and size of the leaderboard, creating it on first use
+    /// Accounts expected:
+    /// 0. `[signer, writable]` The program authority
+    /// 1. `[]` The program config PDA
+    /// 2. `[writable]` The leaderboard PDA
+    /// 3. `[]` The system program
+    SetLeaderboardConfig {
+        /// Player wallet field to rank by
+        metric: WalletMetric,
+        /// Number of players ranked, at most `MAX_LEADERBOARD_ENTRIES`
+        size: u16,
+    },
+    
+    /// Create a season
+    /// Accounts expected:
+    /// 0. `[signer, writable]` The program authority
+    /// 1. `[]` The program config PDA
+    /// 2. `[writable]` The season PDA
+    /// 3. `[]` The system program
+    /// 4. `[writable]` The season clock PDA, created with the first season
+    CreateSeason {
+        /// Season number, starting at 1
+        id: u32,
+        /// Unix timestamp the season starts
+        start_time: i64,
+        /// Unix timestamp the season ends
+        end_time: i64,
+    },
+    
+    /// Close a season so player stats can be snapshotted
+    /// Accounts expected:
+    /// 0. `[signer]` The program authority
+    /// 1. `[]` The program config PDA
+    /// 2. `[writable]` The season PDA
+    /// 3. `[writable]` The season clock PDA
+    CloseSeason {
+        /// Season number
+        id: u32,
+    },
+    
+    /// Snapshot a player's metrics as they were when a season closed
+    /// Seasons can be snapshotted in any order, each once, until the player's
+    /// metrics change after the next close.
+    /// Accounts expected:
+    /// 0. `[signer, writable]` The payer for the season stats account
+    /// 1. `[]` The player wallet account
+    /// 2. `[]` The season PDA
+    /// 3. `[writable]` The player's season stats PDA
+    /// 4. `[]` The system program
+    SnapshotSeasonStats {
+        /// Season number
+        season_id: u32,
+    },
+    
+    /// Define an achievement unlocked when a wallet metric reaches a threshold
+    /// Accounts expected:
+    /// 0. `[signer, writable]` The program authority
+    /// 1. `[]` The program config PDA
+    /// 2. `[writable]` The achievement PDA
+    /// 3. `[]` The system program
+    CreateAchievement {
+        /// Achievement number, also its bit in each player's achievements bitmap
+        id: u8,
+        /// Display name, at most `MAX_ACHIEVEMENT_NAME_LENGTH` bytes
+        name: String,
+        /// Wallet metric the achievement tracks
+        metric: WalletMetric,
+        /// Metric value that unlocks the achievement
+        threshold: u64,
+    },
+    
+    /// Create the account tracking a player's unlocked achievements
+    /// Accounts expected:
+    /// 0. `[signer, writable]` The payer for the account
+    /// 1. `[]` The player wallet account
+    /// 2. `[writable]` The player's achievements PDA
+    /// 3. `[]` The system program
+    CreatePlayerAchievements,
 }
 
 impl PlayerWalletInstruction {
@@ -56,24 +816,340 @@ impl PlayerWalletInstruction {
                 Self::CreatePlayerWallet { name }
             },
             2 => {
-                let payload: UpdatePlayerWalletPayload = BorshDeserialize::deserialize(&mut &rest[..])
+                let date_portfolio: u64 = BorshDeserialize::deserialize(&mut &rest[..])
+                    .map_err(|_| PlayerWalletError::InvalidInstruction)?;
+                Self::UpdatePlayerWallet { date_portfolio }
+            },
+            3 => {
+                let rule: BlockedNameRule = BorshDeserialize::deserialize(&mut &rest[..])
+                    .map_err(|_| PlayerWalletError::InvalidInstruction)?;
+                Self::AddBlockedName { rule }
+            },
+            4 => {
+                let rule: BlockedNameRule = BorshDeserialize::deserialize(&mut &rest[..])
+                    .map_err(|_| PlayerWalletError::InvalidInstruction)?;
+                Self::RemoveBlockedName { rule }
+            },
+            5 => {
+                let creation_fee_lamports: u64 = BorshDeserialize::deserialize(&mut &rest[..])
+                    .map_err(|_| PlayerWalletError::InvalidInstruction)?;
+                Self::SetCreationFee { creation_fee_lamports }
+            },
+            6 => {
+                let amount: u64 = BorshDeserialize::deserialize(&mut &rest[..])
+                    .map_err(|_| PlayerWalletError::InvalidInstruction)?;
+                Self::WithdrawTreasury { amount }
+            },
+            7 => Self::MigrateWallet,
+            8 => {
+                let status: WalletStatus = BorshDeserialize::deserialize(&mut &rest[..])
+                    .map_err(|_| PlayerWalletError::InvalidInstruction)?;
+                Self::SetWalletStatus { status }
+            },
+            9 => {
+                let mint: Pubkey = BorshDeserialize::deserialize(&mut &rest[..])
+                    .map_err(|_| PlayerWalletError::InvalidInstruction)?;
+                Self::AddHolding { mint }
+            },
+            10 => {
+                let mint: Pubkey = BorshDeserialize::deserialize(&mut &rest[..])
+                    .map_err(|_| PlayerWalletError::InvalidInstruction)?;
+                Self::UpdateHolding { mint }
+            },
+            11 => {
+                let mint: Pubkey = BorshDeserialize::deserialize(&mut &rest[..])
+                    .map_err(|_| PlayerWalletError::InvalidInstruction)?;
+                Self::RemoveHolding { mint }
+            },
+            12 => Self::SyncFromTokenAccounts,
+            13 => {
+                let payload: CreatePriceFeedPayload = BorshDeserialize::deserialize(&mut &rest[..])
+                    .map_err(|_| PlayerWalletError::InvalidInstruction)?;
+                Self::CreatePriceFeed {
+                    mint: payload.mint,
+                    publisher: payload.publisher,
+                }
+            },
+            14 => {
+                let payload: PublishPricePayload = BorshDeserialize::deserialize(&mut &rest[..])
+                    .map_err(|_| PlayerWalletError::InvalidInstruction)?;
+                Self::PublishPrice {
+                    price: payload.price,
+                    expo: payload.expo,
+                    conf: payload.conf,
+                }
+            },
+            15 => Self::Revalue,
+            16 => {
+                let amount: u64 = BorshDeserialize::deserialize(&mut &rest[..])
+                    .map_err(|_| PlayerWalletError::InvalidInstruction)?;
+                Self::Deposit { amount }
+            },
+            17 => {
+                let amount: u64 = BorshDeserialize::deserialize(&mut &rest[..])
+                    .map_err(|_| PlayerWalletError::InvalidInstruction)?;
+                Self::Withdraw { amount }
+            },
+            18 => {
+                let amount: u64 = BorshDeserialize::deserialize(&mut &rest[..])
+                    .map_err(|_| PlayerWalletError::InvalidInstruction)?;
+                Self::DepositSol { amount }
+            },
+            19 => {
+                let amount: u64 = BorshDeserialize::deserialize(&mut &rest[..])
+                    .map_err(|_| PlayerWalletError::InvalidInstruction)?;
+                Self::WithdrawSol { amount }
+            },
+            20 => {
+                let amount: u64 = BorshDeserialize::deserialize(&mut &rest[..])
+                    .map_err(|_| PlayerWalletError::InvalidInstruction)?;
+                Self::Transfer { amount }
+            },
+            21 => {
+                let payload: InitializeCurrencyPayload = BorshDeserialize::deserialize(&mut &rest[..])
+                    .map_err(|_| PlayerWalletError::InvalidInstruction)?;
+                Self::InitializeCurrency {
+                    decimals: payload.decimals,
+                    updater: payload.updater,
+                    epoch_issuance_cap: payload.epoch_issuance_cap,
+                }
+            },
+            22 => {
+                let payload: SetCurrencyConfigPayload = BorshDeserialize::deserialize(&mut &rest[..])
+                    .map_err(|_| PlayerWalletError::InvalidInstruction)?;
+                Self::SetCurrencyConfig {
+                    updater: payload.updater,
+                    epoch_issuance_cap: payload.epoch_issuance_cap,
+                }
+            },
+            23 => {
+                let amount: u64 = BorshDeserialize::deserialize(&mut &rest[..])
+                    .map_err(|_| PlayerWalletError::InvalidInstruction)?;
+                Self::MintReward { amount }
+            },
+            24 => {
+                let payload: CreateDistributorPayload = BorshDeserialize::deserialize(&mut &rest[..])
+                    .map_err(|_| PlayerWalletError::InvalidInstruction)?;
+                Self::CreateDistributor {
+                    id: payload.id,
+                    merkle_root: payload.merkle_root,
+                    num_claims: payload.num_claims,
+                }
+            },
+            25 => {
+                let payload: ClaimPayload = BorshDeserialize::deserialize(&mut &rest[..])
+                    .map_err(|_| PlayerWalletError::InvalidInstruction)?;
+                Self::Claim {
+                    index: payload.index,
+                    amount: payload.amount,
+                    proof: payload.proof,
+                }
+            },
+            26 => {
+                let payload: SetStakingConfigPayload = BorshDeserialize::deserialize(&mut &rest[..])
+                    .map_err(|_| PlayerWalletError::InvalidInstruction)?;
+                Self::SetStakingConfig {
+                    reward_rate: payload.reward_rate,
+                    lock_period_seconds: payload.lock_period_seconds,
+                }
+            },
+            27 => {
+                let amount: u64 = BorshDeserialize::deserialize(&mut &rest[..])
+                    .map_err(|_| PlayerWalletError::InvalidInstruction)?;
+                Self::Stake { amount }
+            },
+            28 => {
+                let amount: u64 = BorshDeserialize::deserialize(&mut &rest[..])
+                    .map_err(|_| PlayerWalletError::InvalidInstruction)?;
+                Self::Unstake { amount }
+            },
+            29 => Self::ClaimStakingRewards,
+            30 => {
+                let slot: u8 = BorshDeserialize::deserialize(&mut &rest[..])
+                    .map_err(|_| PlayerWalletError::InvalidInstruction)?;
+                Self::RegisterItem { slot }
+            },
+            31 => {
+                let mint: Pubkey = BorshDeserialize::deserialize(&mut &rest[..])
+                    .map_err(|_| PlayerWalletError::InvalidInstruction)?;
+                Self::EquipItem { mint }
+            },
+            32 => {
+                let mint: Pubkey = BorshDeserialize::deserialize(&mut &rest[..])
+                    .map_err(|_| PlayerWalletError::InvalidInstruction)?;
+                Self::UnequipItem { mint }
+            },
+            33 => {
+                let payload: SetMarketplaceConfigPayload = BorshDeserialize::deserialize(&mut &rest[..])
+                    .map_err(|_| PlayerWalletError::InvalidInstruction)?;
+                Self::SetMarketplaceConfig {
+                    fee_bps: payload.fee_bps,
+                    fee_recipient: payload.fee_recipient,
+                }
+            },
+            34 => {
+                let price: u64 = BorshDeserialize::deserialize(&mut &rest[..])
+                    .map_err(|_| PlayerWalletError::InvalidInstruction)?;
+                Self::ListItem { price }
+            },
+            35 => Self::CancelListing,
+            36 => {
+                let price: u64 = BorshDeserialize::deserialize(&mut &rest[..])
+                    .map_err(|_| PlayerWalletError::InvalidInstruction)?;
+                Self::BuyItem { price }
+            },
+            37 => {
+                let terms: AuctionTerms = BorshDeserialize::deserialize(&mut &rest[..])
+                    .map_err(|_| PlayerWalletError::InvalidInstruction)?;
+                Self::CreateAuction { terms }
+            },
+            38 => {
+                let amount: u64 = BorshDeserialize::deserialize(&mut &rest[..])
+                    .map_err(|_| PlayerWalletError::InvalidInstruction)?;
+                Self::PlaceBid { amount }
+            },
+            39 => Self::SettleAuction,
+            40 => {
+                let payload: CreateRentalPayload = BorshDeserialize::deserialize(&mut &rest[..])
+                    .map_err(|_| PlayerWalletError::InvalidInstruction)?;
+                Self::CreateRental {
+                    price: payload.price,
+                    duration_seconds: payload.duration_seconds,
+                }
+            },
+            41 => {
+                let payload: RentItemPayload = BorshDeserialize::deserialize(&mut &rest[..])
+                    .map_err(|_| PlayerWalletError::InvalidInstruction)?;
+                Self::RentItem {
+                    price: payload.price,
+                    slot: payload.slot,
+                }
+            },
+            42 => Self::ReclaimRental,
+            43 => {
+                let payload: SetLeaderboardConfigPayload = BorshDeserialize::deserialize(&mut &rest[..])
                     .map_err(|_| PlayerWalletError::InvalidInstruction)?;
-                Self::UpdatePlayerWallet {
-                    nb_tokens: payload.nb_tokens,
-                    amount_total_tokens: payload.amount_total_tokens,
-                    amount_total_value_stablecoin: payload.amount_total_value_stablecoin,
-                    date_portfolio: payload.date_portfolio,
+                Self::SetLeaderboardConfig {
+                    metric: payload.metric,
+                    size: payload.size,
                 }
             },
+            44 => {
+                let payload: CreateSeasonPayload = BorshDeserialize::deserialize(&mut &rest[..])
+                    .map_err(|_| PlayerWalletError::InvalidInstruction)?;
+                Self::CreateSeason {
+                    id: payload.id,
+                    start_time: payload.start_time,
+                    end_time: payload.end_time,
+                }
+            },
+            45 => {
+                let id: u32 = BorshDeserialize::deserialize(&mut &rest[..])
+                    .map_err(|_| PlayerWalletError::InvalidInstruction)?;
+                Self::CloseSeason { id }
+            },
+            46 => {
+                let season_id: u32 = BorshDeserialize::deserialize(&mut &rest[..])
+                    .map_err(|_| PlayerWalletError::InvalidInstruction)?;
+                Self::SnapshotSeasonStats { season_id }
+            },
+            47 => {
+                let payload: CreateAchievementPayload = BorshDeserialize::deserialize(&mut &rest[..])
+                    .map_err(|_| PlayerWalletError::InvalidInstruction)?;
+                Self::CreateAchievement {
+                    id: payload.id,
+                    name: payload.name,
+                    metric: payload.metric,
+                    threshold: payload.threshold,
+                }
+            },
+            48 => Self::CreatePlayerAchievements,
             _ => return Err(PlayerWalletError::InvalidInstruction.into()),
         })
     }
 }
 
 #[derive(BorshSerialize, BorshDeserialize)]
-struct UpdatePlayerWalletPayload {
-    nb_tokens: u32,
-    amount_total_tokens: u32,
-    amount_total_value_stablecoin: u32,
-    date_portfolio: u64,
+struct CreatePriceFeedPayload {
+    mint: Pubkey,
+    publisher: Pubkey,
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+struct PublishPricePayload {
+    price: i64,
+    expo: i32,
+    conf: u64,
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+struct InitializeCurrencyPayload {
+    decimals: u8,
+    updater: Pubkey,
+    epoch_issuance_cap: u64,
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+struct SetCurrencyConfigPayload {
+    updater: Pubkey,
+    epoch_issuance_cap: u64,
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+struct CreateDistributorPayload {
+    id: u64,
+    merkle_root: [u8; 32],
+    num_claims: u64,
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+struct ClaimPayload {
+    index: u64,
+    amount: u64,
+    proof: Vec<[u8; 32]>,
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+struct SetStakingConfigPayload {
+    reward_rate: u64,
+    lock_period_seconds: i64,
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+struct SetMarketplaceConfigPayload {
+    fee_bps: u16,
+    fee_recipient: Pubkey,
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+struct CreateRentalPayload {
+    price: u64,
+    duration_seconds: i64,
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+struct RentItemPayload {
+    price: u64,
+    slot: u8,
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+struct SetLeaderboardConfigPayload {
+    metric: WalletMetric,
+    size: u16,
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+struct CreateSeasonPayload {
+    id: u32,
+    start_time: i64,
+    end_time: i64,
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+struct CreateAchievementPayload {
+    id: u8,
+    name: String,
+    metric: WalletMetric,
+    threshold: u64,
 }
@@ -0,0 +1,108 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    pubkey::Pubkey,
+    msg,
+};
+
+use crate::state::{save_account, BlockedNameRule, NameBlocklist, ProgramConfig};
+use crate::error::PlayerWalletError;
+use crate::constants::{MAX_BLOCKED_NAME_RULES, NAME_BLOCKLIST_SEED};
+use crate::utils::{create_pda_account, resize_account};
+
+/// Add a rule to the name blocklist, creating the blocklist on first use
+pub fn process_add_blocked_name(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    rule: BlockedNameRule,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    
+    // Get the required accounts
+    let authority_info = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+    let blocklist_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+    
+    ProgramConfig::load_with_authority(program_id, config_info, authority_info)?;
+    
+    // An empty prefix or fragment would block every name
+    let rule = rule.canonicalize();
+    if let BlockedNameRule::Prefix(text) | BlockedNameRule::Substring(text) = &rule {
+        if text.is_empty() || !text.chars().all(|c| c.is_alphanumeric()) {
+            return Err(PlayerWalletError::InvalidNameFormat.into());
+        }
+    }
+    
+    let mut blocklist = NameBlocklist::load(program_id, blocklist_info)?;
+    if blocklist.rules.contains(&rule) {
+        msg!("Rule already present");
+        return Ok(());
+    }
+    if blocklist.rules.len() >= MAX_BLOCKED_NAME_RULES {
+        return Err(PlayerWalletError::TooManyBlockedNameRules.into());
+    }
+    
+    let already_created = blocklist.is_initialized;
+    blocklist.is_initialized = true;
+    blocklist.rules.push(rule);
+    let account_size = blocklist.get_account_size();
+    
+    if already_created {
+        resize_account(blocklist_info, authority_info, system_program_info, account_size)?;
+    } else {
+        let (_, bump) = NameBlocklist::find_address(program_id);
+        create_pda_account(
+            authority_info,
+            blocklist_info,
+            system_program_info,
+            program_id,
+            account_size,
+            &[NAME_BLOCKLIST_SEED, &[bump]],
+        )?;
+    }
+    
+    save_account(&blocklist, blocklist_info)?;
+    
+    msg!("Blocked name rule added");
+    Ok(())
+}
+
+/// Remove a rule from the name blocklist
+pub fn process_remove_blocked_name(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    rule: BlockedNameRule,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    
+    // Get the required accounts
+    let authority_info = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+    let blocklist_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+    
+    ProgramConfig::load_with_authority(program_id, config_info, authority_info)?;
+    
+    let mut blocklist = NameBlocklist::load(program_id, blocklist_info)?;
+    if !blocklist.is_initialized {
+        return Err(PlayerWalletError::AccountNotInitialized.into());
+    }
+    
+    let rule = rule.canonicalize();
+    let position = blocklist.rules.iter().position(|r| *r == rule)
+        .ok_or(PlayerWalletError::BlockedNameRuleNotFound)?;
+    blocklist.rules.remove(position);
+    
+    // Serialize before shrinking so the trailing bytes are simply dropped
+    save_account(&blocklist, blocklist_info)?;
+    resize_account(
+        blocklist_info,
+        authority_info,
+        system_program_info,
+        blocklist.get_account_size(),
+    )?;
+    
+    msg!("Blocked name rule removed");
+    Ok(())
+}
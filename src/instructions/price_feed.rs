@@ -0,0 +1,100 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+    msg,
+};
+
+use crate::state::{load_account, save_account, PriceFeed, ProgramConfig};
+use crate::error::PlayerWalletError;
+use crate::constants::PRICE_FEED_SEED;
+use crate::utils::create_pda_account;
+
+/// Create the price feed of a mint and appoint its publisher
+pub fn process_create_price_feed(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    mint: Pubkey,
+    publisher: Pubkey,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    
+    // Get the required accounts
+    let authority_info = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+    let price_feed_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+    
+    ProgramConfig::load_with_authority(program_id, config_info, authority_info)?;
+    
+    let (price_feed_address, bump) = PriceFeed::find_address(&mint, program_id);
+    if *price_feed_info.key != price_feed_address {
+        msg!("Invalid price feed account");
+        return Err(ProgramError::InvalidArgument);
+    }
+    if price_feed_info.owner == program_id {
+        return Err(PlayerWalletError::AccountAlreadyInitialized.into());
+    }
+    
+    create_pda_account(
+        authority_info,
+        price_feed_info,
+        system_program_info,
+        program_id,
+        PriceFeed::LEN,
+        &[PRICE_FEED_SEED, mint.as_ref(), &[bump]],
+    )?;
+    
+    let price_feed = PriceFeed {
+        is_initialized: true,
+        mint,
+        publisher,
+        price: 0,
+        expo: 0,
+        conf: 0,
+        publish_time: 0,
+        bump,
+    };
+    save_account(&price_feed, price_feed_info)?;
+    
+    msg!("Price feed created for mint {}", mint);
+    Ok(())
+}
+
+/// Publish a new price to a price feed
+pub fn process_publish_price(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    price: i64,
+    expo: i32,
+    conf: u64,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    
+    // Get the required accounts
+    let publisher_info = next_account_info(account_info_iter)?;
+    let price_feed_info = next_account_info(account_info_iter)?;
+    
+    // Ensure the publisher is a signer
+    if !publisher_info.is_signer {
+        msg!("Publisher must be a signer");
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    
+    let mut price_feed = load_account::<PriceFeed>(program_id, price_feed_info)?;
+    if price_feed.publisher != *publisher_info.key {
+        return Err(PlayerWalletError::Unauthorized.into());
+    }
+    
+    price_feed.price = price;
+    price_feed.expo = expo;
+    price_feed.conf = conf;
+    price_feed.publish_time = Clock::get()?.unix_timestamp;
+    save_account(&price_feed, price_feed_info)?;
+    
+    msg!("Price published for mint {}", price_feed.mint);
+    Ok(())
+}
@@ -0,0 +1,442 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+    msg,
+};
+use spl_associated_token_account::instruction::create_associated_token_account_idempotent;
+
+use super::inventory::{load_or_create_inventory, remove_escrowed_item, save_inventory};
+use super::achievement::unlock_achievements;
+use super::leaderboard::update_leaderboard;
+use super::marketplace::{check_token_account, refresh_currency_holding};
+use crate::state::{
+    save_account, CurrencyConfig, Inventory, InventoryItem, PlayerWallet, Rental,
+    SeasonClock,
+};
+use crate::error::PlayerWalletError;
+use crate::constants::RENTAL_SEED;
+use crate::token::{
+    check_item_mint, check_mint_extensions, check_token_program, unpack_mint, unpack_token_account,
+};
+use crate::utils::{close_account, create_pda_account};
+
+/// Escrow an item to rent it out at a fixed price per period
+pub fn process_create_rental(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    price: u64,
+    duration_seconds: i64,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    
+    // Get the required accounts
+    let owner_info = next_account_info(account_info_iter)?;
+    let player_wallet_info = next_account_info(account_info_iter)?;
+    let rental_info = next_account_info(account_info_iter)?;
+    let mint_info = next_account_info(account_info_iter)?;
+    let token_account_info = next_account_info(account_info_iter)?;
+    let escrow_info = next_account_info(account_info_iter)?;
+    let token_program_info = next_account_info(account_info_iter)?;
+    let associated_token_program_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+    let inventory_info = next_account_info(account_info_iter)?;
+    
+    check_token_program(token_program_info)?;
+    
+    // Ensure the player wallet account belongs to this program
+    if player_wallet_info.owner != program_id {
+        msg!("Player wallet account does not belong to this program");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    PlayerWallet::load(&player_wallet_info.try_borrow_data()?)?.check_owner(owner_info)?;
+    
+    if price == 0 || duration_seconds <= 0 {
+        msg!("Price and duration must be positive");
+        return Err(ProgramError::InvalidArgument);
+    }
+    
+    // Only transferable game items can be lent
+    check_item_mint(mint_info)?;
+    check_mint_extensions(mint_info)?;
+    
+    let (rental_address, bump) = Rental::find_address(mint_info.key, program_id);
+    if *rental_info.key != rental_address {
+        msg!("Invalid rental account");
+        return Err(ProgramError::InvalidArgument);
+    }
+    if rental_info.owner == program_id {
+        msg!("Item is already up for rent");
+        return Err(PlayerWalletError::AccountAlreadyInitialized.into());
+    }
+    check_token_account(escrow_info, &rental_address, mint_info.key, token_program_info.key)?;
+    
+    create_pda_account(
+        owner_info,
+        rental_info,
+        system_program_info,
+        program_id,
+        Rental::LEN,
+        &[RENTAL_SEED, mint_info.key.as_ref(), &[bump]],
+    )?;
+    invoke(
+        &create_associated_token_account_idempotent(
+            owner_info.key,
+            &rental_address,
+            mint_info.key,
+            token_program_info.key,
+        ),
+        &[
+            owner_info.clone(),
+            escrow_info.clone(),
+            rental_info.clone(),
+            mint_info.clone(),
+            system_program_info.clone(),
+            token_program_info.clone(),
+            associated_token_program_info.clone(),
+        ],
+    )?;
+    invoke(
+        &spl_token_2022::instruction::transfer_checked(
+            token_program_info.key,
+            token_account_info.key,
+            mint_info.key,
+            escrow_info.key,
+            owner_info.key,
+            &[],
+            1,
+            0,
+        )?,
+        &[
+            token_account_info.clone(),
+            mint_info.clone(),
+            escrow_info.clone(),
+            owner_info.clone(),
+            token_program_info.clone(),
+        ],
+    )?;
+    
+    // A transfer fee would leave nothing to lend
+    if unpack_token_account(escrow_info)?.amount != 1 {
+        msg!("Item {} did not arrive in escrow", mint_info.key);
+        return Err(PlayerWalletError::InvalidItemMint.into());
+    }
+    
+    // The lender cannot use the item while it is lent; its slot is kept for the reclaim
+    let lender_item = remove_escrowed_item(program_id, player_wallet_info, inventory_info, mint_info.key)?;
+    
+    let rental = Rental {
+        is_initialized: true,
+        lender: *player_wallet_info.key,
+        mint: *mint_info.key,
+        price,
+        duration_seconds,
+        renter: Pubkey::default(),
+        expires_at: 0,
+        lender_slot: lender_item.map(|item| item.slot),
+        bump,
+    };
+    save_account(&rental, rental_info)?;
+    
+    msg!("Item {} up for rent at {} per {} seconds", mint_info.key, price, duration_seconds);
+    Ok(())
+}
+
+/// Rent an item for one period, paying the lender upfront in game currency
+pub fn process_rent_item(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    price: u64,
+    slot: u8,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    
+    // Get the required accounts
+    let renter_info = next_account_info(account_info_iter)?;
+    let renter_wallet_info = next_account_info(account_info_iter)?;
+    let renter_holdings_info = next_account_info(account_info_iter)?;
+    let renter_inventory_info = next_account_info(account_info_iter)?;
+    let renter_currency_account_info = next_account_info(account_info_iter)?;
+    let lender_wallet_info = next_account_info(account_info_iter)?;
+    let lender_holdings_info = next_account_info(account_info_iter)?;
+    let lender_info = next_account_info(account_info_iter)?;
+    let lender_currency_account_info = next_account_info(account_info_iter)?;
+    let rental_info = next_account_info(account_info_iter)?;
+    let mint_info = next_account_info(account_info_iter)?;
+    let currency_config_info = next_account_info(account_info_iter)?;
+    let currency_mint_info = next_account_info(account_info_iter)?;
+    let currency_token_program_info = next_account_info(account_info_iter)?;
+    let associated_token_program_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+    let leaderboard_info = next_account_info(account_info_iter)?;
+    let season_clock_info = next_account_info(account_info_iter)?;
+    
+    if *currency_token_program_info.key != spl_token::id() {
+        msg!("Invalid token program");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    
+    let mut rental = Rental::load(program_id, mint_info.key, rental_info)?;
+    if *lender_wallet_info.key != rental.lender {
+        msg!("Invalid lender wallet");
+        return Err(ProgramError::InvalidArgument);
+    }
+    if *renter_wallet_info.key == rental.lender {
+        return Err(PlayerWalletError::CannotBuyOwnItem.into());
+    }
+    if rental.is_rented() {
+        return Err(PlayerWalletError::AlreadyRented.into());
+    }
+    if price != rental.price {
+        msg!("Rental price is {}, not {}", rental.price, price);
+        return Err(ProgramError::InvalidArgument);
+    }
+    
+    // Ensure both player wallet accounts belong to this program
+    if renter_wallet_info.owner != program_id || lender_wallet_info.owner != program_id {
+        msg!("Player wallet account does not belong to this program");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    let mut renter_data = renter_wallet_info.try_borrow_mut_data()?;
+    let renter = PlayerWallet::load_mut(&mut renter_data)?;
+    renter.check_owner(renter_info)?;
+    let mut lender_data = lender_wallet_info.try_borrow_mut_data()?;
+    let lender = PlayerWallet::load_mut(&mut lender_data)?;
+    if *lender_info.key != lender.wallet_address {
+        msg!("Invalid lender account");
+        return Err(ProgramError::InvalidArgument);
+    }
+    
+    let currency_config = CurrencyConfig::load(program_id, currency_config_info)?;
+    if *currency_mint_info.key != currency_config.mint {
+        msg!("Invalid currency mint");
+        return Err(ProgramError::InvalidArgument);
+    }
+    let currency_mint = &currency_config.mint;
+    check_token_account(renter_currency_account_info, renter_info.key, currency_mint, &spl_token::id())?;
+    check_token_account(lender_currency_account_info, lender_info.key, currency_mint, &spl_token::id())?;
+    
+    // Pay the lender
+    invoke(
+        &create_associated_token_account_idempotent(
+            renter_info.key,
+            lender_info.key,
+            currency_mint,
+            &spl_token::id(),
+        ),
+        &[
+            renter_info.clone(),
+            lender_currency_account_info.clone(),
+            lender_info.clone(),
+            currency_mint_info.clone(),
+            system_program_info.clone(),
+            currency_token_program_info.clone(),
+            associated_token_program_info.clone(),
+        ],
+    )?;
+    invoke(
+        &spl_token::instruction::transfer_checked(
+            &spl_token::id(),
+            renter_currency_account_info.key,
+            currency_mint,
+            lender_currency_account_info.key,
+            renter_info.key,
+            &[],
+            price,
+            unpack_mint(currency_mint_info)?.decimals,
+        )?,
+        &[
+            renter_currency_account_info.clone(),
+            currency_mint_info.clone(),
+            lender_currency_account_info.clone(),
+            renter_info.clone(),
+            currency_token_program_info.clone(),
+        ],
+    )?;
+    
+    // Start the rental period
+    rental.renter = *renter_wallet_info.key;
+    rental.expires_at = Clock::get()?.unix_timestamp
+        .checked_add(rental.duration_seconds)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    save_account(&rental, rental_info)?;
+    
+    // Make the item usable from the renter's inventory, replacing any stale entry
+    let mut inventory = load_or_create_inventory(
+        program_id,
+        renter_info,
+        renter_wallet_info,
+        renter_inventory_info,
+        system_program_info,
+    )?;
+    let _ = inventory.remove(mint_info.key);
+    inventory.add(InventoryItem {
+        mint: *mint_info.key,
+        slot,
+        equipped: false,
+        usable_until: rental.expires_at,
+    })?;
+    save_inventory(&inventory, renter_inventory_info, renter_info, system_program_info)?;
+    
+    // Record the trade on both sides
+    let seasons_closed = SeasonClock::seasons_closed(program_id, season_clock_info)?;
+    renter.record_transaction(seasons_closed)?;
+    lender.record_transaction(seasons_closed)?;
+    refresh_currency_holding(
+        program_id,
+        renter_wallet_info,
+        renter,
+        renter_holdings_info,
+        currency_mint,
+        renter_currency_account_info,
+    )?;
+    refresh_currency_holding(
+        program_id,
+        lender_wallet_info,
+        lender,
+        lender_holdings_info,
+        currency_mint,
+        lender_currency_account_info,
+    )?;
+    let player_wallets = [(renter_wallet_info.key, &*renter), (lender_wallet_info.key, &*lender)];
+    update_leaderboard(program_id, leaderboard_info, &player_wallets)?;
+    unlock_achievements(program_id, &player_wallets, account_info_iter.as_slice())?;
+    
+    msg!("Item {} rented for {} until {}", mint_info.key, price, rental.expires_at);
+    Ok(())
+}
+
+/// Return a rented item to its lender once the rental has expired, or an
+/// unrented one at the lender's request, and close the rental
+pub fn process_reclaim_rental(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    
+    // Get the required accounts
+    let caller_info = next_account_info(account_info_iter)?;
+    let lender_wallet_info = next_account_info(account_info_iter)?;
+    let lender_info = next_account_info(account_info_iter)?;
+    let lender_item_account_info = next_account_info(account_info_iter)?;
+    let renter_inventory_info = next_account_info(account_info_iter)?;
+    let rental_info = next_account_info(account_info_iter)?;
+    let mint_info = next_account_info(account_info_iter)?;
+    let escrow_info = next_account_info(account_info_iter)?;
+    let token_program_info = next_account_info(account_info_iter)?;
+    let associated_token_program_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+    let lender_inventory_info = next_account_info(account_info_iter)?;
+    
+    if !caller_info.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    check_token_program(token_program_info)?;
+    
+    let rental = Rental::load(program_id, mint_info.key, rental_info)?;
+    if *lender_wallet_info.key != rental.lender {
+        msg!("Invalid lender wallet");
+        return Err(ProgramError::InvalidArgument);
+    }
+    
+    // Ensure the lender wallet account belongs to this program
+    if lender_wallet_info.owner != program_id {
+        msg!("Player wallet account does not belong to this program");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    let lender = PlayerWallet::load(&lender_wallet_info.try_borrow_data()?)?.wallet_address;
+    if *lender_info.key != lender {
+        msg!("Invalid lender account");
+        return Err(ProgramError::InvalidArgument);
+    }
+    
+    // Anyone may end an expired rental, only the lender may withdraw an idle item
+    let now = Clock::get()?.unix_timestamp;
+    if rental.is_rented() {
+        if !rental.is_expired(now) {
+            msg!("Rental of item {} ends at {}", mint_info.key, rental.expires_at);
+            return Err(PlayerWalletError::RentalActive.into());
+        }
+        let mut inventory = Inventory::load(program_id, &rental.renter, renter_inventory_info)?;
+        let _ = inventory.remove(mint_info.key);
+        save_account(&inventory, renter_inventory_info)?;
+    } else if *caller_info.key != lender {
+        return Err(PlayerWalletError::Unauthorized.into());
+    }
+    
+    check_token_account(lender_item_account_info, &lender, mint_info.key, token_program_info.key)?;
+    check_token_account(escrow_info, rental_info.key, mint_info.key, token_program_info.key)?;
+    
+    // Return the item and close the escrow and rental, refunding their rent to the lender
+    invoke(
+        &create_associated_token_account_idempotent(
+            caller_info.key,
+            &lender,
+            mint_info.key,
+            token_program_info.key,
+        ),
+        &[
+            caller_info.clone(),
+            lender_item_account_info.clone(),
+            lender_info.clone(),
+            mint_info.clone(),
+            system_program_info.clone(),
+            token_program_info.clone(),
+            associated_token_program_info.clone(),
+        ],
+    )?;
+    let rental_seeds: &[&[u8]] = &[RENTAL_SEED, rental.mint.as_ref(), &[rental.bump]];
+    invoke_signed(
+        &spl_token_2022::instruction::transfer_checked(
+            token_program_info.key,
+            escrow_info.key,
+            mint_info.key,
+            lender_item_account_info.key,
+            rental_info.key,
+            &[],
+            1,
+            0,
+        )?,
+        &[
+            escrow_info.clone(),
+            mint_info.clone(),
+            lender_item_account_info.clone(),
+            rental_info.clone(),
+            token_program_info.clone(),
+        ],
+        &[rental_seeds],
+    )?;
+    invoke_signed(
+        &spl_token_2022::instruction::close_account(
+            token_program_info.key,
+            escrow_info.key,
+            lender_info.key,
+            rental_info.key,
+            &[],
+        )?,
+        &[
+            escrow_info.clone(),
+            lender_info.clone(),
+            rental_info.clone(),
+            token_program_info.clone(),
+        ],
+        &[rental_seeds],
+    )?;
+    close_account(rental_info, lender_info)?;
+    
+    // Put the item back in the lender's inventory, unless it has filled up since
+    if let Some(slot) = rental.lender_slot {
+        let mut inventory = Inventory::load(program_id, lender_wallet_info.key, lender_inventory_info)?;
+        let item = InventoryItem { mint: rental.mint, slot, equipped: false, usable_until: 0 };
+        if inventory.add(item).is_ok() {
+            save_inventory(&inventory, lender_inventory_info, caller_info, system_program_info)?;
+        }
+    }
+    
+    msg!("Item {} returned to its lender", mint_info.key);
+    Ok(())
+}
@@ -0,0 +1,97 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+    msg,
+};
+
+use crate::state::{load_account, save_account, Holdings, PlayerWallet, PriceFeed, SeasonClock};
+use super::achievement::unlock_achievements;
+use super::leaderboard::update_leaderboard;
+use crate::error::PlayerWalletError;
+
+/// Recompute the stablecoin value of a player's holdings from on-chain price feeds
+/// Anyone can revalue a wallet. One price feed PDA is expected per holding, in
+/// holdings order. A holding whose feed does not exist or whose price is
+/// stale or imprecise is valued at zero, so one unpriced or delisted mint
+/// cannot block the rest of the revaluation.
+pub fn process_revalue(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    
+    // Get the required accounts
+    let player_wallet_info = next_account_info(account_info_iter)?;
+    let holdings_info = next_account_info(account_info_iter)?;
+    let leaderboard_info = next_account_info(account_info_iter)?;
+    let season_clock_info = next_account_info(account_info_iter)?;
+    
+    // Ensure the player wallet account belongs to this program
+    if player_wallet_info.owner != program_id {
+        msg!("Player wallet account does not belong to this program");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    let mut data = player_wallet_info.try_borrow_mut_data()?;
+    let player_wallet = PlayerWallet::load_mut(&mut data)?;
+    
+    if *holdings_info.key != Holdings::find_address(player_wallet_info.key, program_id).0 {
+        msg!("Invalid holdings account");
+        return Err(ProgramError::InvalidArgument);
+    }
+    let mut holdings = load_account::<Holdings>(program_id, holdings_info)?;
+    
+    if account_info_iter.as_slice().len() < holdings.entries.len() {
+        msg!("Expected {} price feeds", holdings.entries.len());
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+    let (price_feed_infos, achievement_accounts) = account_info_iter.as_slice().split_at(holdings.entries.len());
+    
+    let now = Clock::get()?.unix_timestamp;
+    let mut total_value: u64 = 0;
+    for (holding, price_feed_info) in holdings.entries.iter_mut().zip(price_feed_infos) {
+        // The feed PDA may not exist yet, for a mint nobody prices
+        let priced = if price_feed_info.owner == program_id {
+            let price_feed = load_account::<PriceFeed>(program_id, price_feed_info)?;
+            if price_feed.mint != holding.mint {
+                msg!("Price feed {} does not price mint {}", price_feed_info.key, holding.mint);
+                return Err(PlayerWalletError::InvalidPriceFeed.into());
+            }
+            price_feed.get_checked_price(now).ok().map(|price| (price_feed, price))
+        } else if *price_feed_info.key == PriceFeed::find_address(&holding.mint, program_id).0 {
+            None
+        } else {
+            msg!("Invalid price feed for mint {}", holding.mint);
+            return Err(PlayerWalletError::InvalidPriceFeed.into());
+        };
+        
+        let value = match priced {
+            Some((price_feed, price)) => {
+                holding.last_price = price_feed.unit_price(price)?;
+                price_feed.value_of(price, holding.total_amount()?, holding.decimals)?
+            },
+            None => {
+                msg!("No usable price for mint {}, valued at zero", holding.mint);
+                holding.last_price = 0;
+                0
+            },
+        };
+        total_value = total_value
+            .checked_add(value)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+    }
+    
+    player_wallet.roll_over_season(SeasonClock::seasons_closed(program_id, season_clock_info)?);
+    player_wallet.amount_total_value_stablecoin = total_value;
+    player_wallet.date_portfolio = now as u64;
+    save_account(&holdings, holdings_info)?;
+    let player_wallets = [(player_wallet_info.key, &*player_wallet)];
+    update_leaderboard(program_id, leaderboard_info, &player_wallets)?;
+    unlock_achievements(program_id, &player_wallets, achievement_accounts)?;
+    
+    msg!("Player wallet revalued at {}", total_value);
+    Ok(())
+}
@@ -0,0 +1,198 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+    msg,
+};
+
+use crate::state::{save_account, PlayerWallet, ProgramConfig, Season, SeasonClock, SeasonStats};
+use crate::error::PlayerWalletError;
+use crate::constants::{SEASON_CLOCK_SEED, SEASON_SEED, SEASON_STATS_SEED};
+use crate::utils::create_pda_account;
+
+/// Create a season with its schedule, and the season clock with the first season
+pub fn process_create_season(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    id: u32,
+    start_time: i64,
+    end_time: i64,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    
+    // Get the required accounts
+    let authority_info = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+    let season_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+    let season_clock_info = next_account_info(account_info_iter)?;
+    
+    ProgramConfig::load_with_authority(program_id, config_info, authority_info)?;
+    
+    if id == 0 || end_time <= start_time {
+        msg!("Season id must be positive and end after it starts");
+        return Err(ProgramError::InvalidArgument);
+    }
+    
+    let (season_address, bump) = Season::find_address(id, program_id);
+    if *season_info.key != season_address {
+        msg!("Invalid season account");
+        return Err(ProgramError::InvalidArgument);
+    }
+    if season_info.owner == program_id {
+        msg!("Season {} already exists", id);
+        return Err(PlayerWalletError::AccountAlreadyInitialized.into());
+    }
+    
+    create_pda_account(
+        authority_info,
+        season_info,
+        system_program_info,
+        program_id,
+        Season::LEN,
+        &[SEASON_SEED, &id.to_le_bytes(), &[bump]],
+    )?;
+    let season = Season {
+        is_initialized: true,
+        id,
+        start_time,
+        end_time,
+        is_closed: false,
+        close_index: 0,
+        bump,
+    };
+    save_account(&season, season_info)?;
+    
+    if season_clock_info.owner != program_id {
+        let (season_clock_address, bump) = SeasonClock::find_address(program_id);
+        if *season_clock_info.key != season_clock_address {
+            msg!("Invalid season clock account");
+            return Err(ProgramError::InvalidArgument);
+        }
+        create_pda_account(
+            authority_info,
+            season_clock_info,
+            system_program_info,
+            program_id,
+            SeasonClock::LEN,
+            &[SEASON_CLOCK_SEED, &[bump]],
+        )?;
+        let season_clock = SeasonClock {
+            is_initialized: true,
+            seasons_closed: 0,
+            bump,
+        };
+        save_account(&season_clock, season_clock_info)?;
+    }
+    
+    msg!("Season {} runs from {} to {}", id, start_time, end_time);
+    Ok(())
+}
+
+/// Close a season, ending it now if it was scheduled to end later
+/// Player wallets keep their metrics at the close and start counting the next
+/// season's transactions the first time their metrics change afterwards.
+pub fn process_close_season(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    id: u32,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    
+    // Get the required accounts
+    let authority_info = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+    let season_info = next_account_info(account_info_iter)?;
+    let season_clock_info = next_account_info(account_info_iter)?;
+    
+    ProgramConfig::load_with_authority(program_id, config_info, authority_info)?;
+    
+    let mut season = Season::load(program_id, id, season_info)?;
+    if season.is_closed {
+        return Err(PlayerWalletError::SeasonClosed.into());
+    }
+    let now = Clock::get()?.unix_timestamp;
+    if now < season.start_time {
+        msg!("Season {} starts at {}", id, season.start_time);
+        return Err(ProgramError::InvalidArgument);
+    }
+    
+    let mut season_clock = SeasonClock::load(program_id, season_clock_info)?;
+    season_clock.seasons_closed = season_clock.seasons_closed
+        .checked_add(1)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    save_account(&season_clock, season_clock_info)?;
+    
+    season.is_closed = true;
+    season.end_time = season.end_time.min(now);
+    season.close_index = season_clock.seasons_closed;
+    save_account(&season, season_info)?;
+    
+    msg!("Season {} closed at {}", id, season.end_time);
+    Ok(())
+}
+
+/// Snapshot a player's metrics for a closed season
+/// Anyone can snapshot a player, so stats are kept even for inactive players.
+/// Seasons can be snapshotted in any order, each once. Wallets keep their
+/// metrics at a close until they change again after the next close, so a
+/// season must be snapshotted before then.
+pub fn process_snapshot_season_stats(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    season_id: u32,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    
+    // Get the required accounts
+    let payer_info = next_account_info(account_info_iter)?;
+    let player_wallet_info = next_account_info(account_info_iter)?;
+    let season_info = next_account_info(account_info_iter)?;
+    let season_stats_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+    
+    let season = Season::load(program_id, season_id, season_info)?;
+    if !season.is_closed {
+        return Err(PlayerWalletError::SeasonNotClosed.into());
+    }
+    
+    // Ensure the player wallet account belongs to this program
+    if player_wallet_info.owner != program_id {
+        msg!("Player wallet account does not belong to this program");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    let data = player_wallet_info.try_borrow_data()?;
+    let player_wallet = PlayerWallet::load(&data)?;
+    
+    let (season_stats_address, bump) = SeasonStats::find_address(season_id, player_wallet_info.key, program_id);
+    if *season_stats_info.key != season_stats_address {
+        msg!("Invalid season stats account");
+        return Err(ProgramError::InvalidArgument);
+    }
+    if season_stats_info.owner == program_id {
+        msg!("Season {} stats already snapshotted", season_id);
+        return Err(PlayerWalletError::SeasonAlreadySnapshotted.into());
+    }
+    let season_stats = SeasonStats::snapshot(
+        &season,
+        player_wallet_info.key,
+        player_wallet,
+        Clock::get()?.unix_timestamp,
+        bump,
+    )?;
+    create_pda_account(
+        payer_info,
+        season_stats_info,
+        system_program_info,
+        program_id,
+        SeasonStats::LEN,
+        &[SEASON_STATS_SEED, &season_id.to_le_bytes(), player_wallet_info.key.as_ref(), &[bump]],
+    )?;
+    save_account(&season_stats, season_stats_info)?;
+    
+    msg!("Season {} stats snapshotted for {}", season_id, player_wallet_info.key);
+    Ok(())
+}
@@ -0,0 +1,38 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    msg,
+};
+
+use crate::state::{PlayerWallet, ProgramConfig, WalletStatus};
+
+/// Freeze or unfreeze a player wallet
+pub fn process_set_wallet_status(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    status: WalletStatus,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    
+    // Get the required accounts
+    let authority_info = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+    let player_wallet_info = next_account_info(account_info_iter)?;
+    
+    ProgramConfig::load_with_authority(program_id, config_info, authority_info)?;
+    
+    // Ensure the player wallet account belongs to this program
+    if player_wallet_info.owner != program_id {
+        msg!("Player wallet account does not belong to this program");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    
+    let mut data = player_wallet_info.try_borrow_mut_data()?;
+    let player_wallet = PlayerWallet::load_mut(&mut data)?;
+    player_wallet.status = status as u8;
+    
+    msg!("Player wallet status set to {:?}", status);
+    Ok(())
+}
@@ -0,0 +1,95 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    program::invoke,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    system_instruction,
+    sysvar::{rent::Rent, Sysvar},
+    msg,
+};
+
+use crate::state::PlayerWallet;
+use crate::error::PlayerWalletError;
+
+/// Deposit lamports from the owner into the player wallet account
+pub fn process_deposit_sol(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: u64,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    
+    // Get the required accounts
+    let owner_info = next_account_info(account_info_iter)?;
+    let player_wallet_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+    
+    // Ensure the player wallet account belongs to this program
+    if player_wallet_info.owner != program_id {
+        msg!("Player wallet account does not belong to this program");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    PlayerWallet::load(&player_wallet_info.try_borrow_data()?)?.check_owner(owner_info)?;
+    
+    // The wallet data must not be borrowed while the system program credits it
+    invoke(
+        &system_instruction::transfer(owner_info.key, player_wallet_info.key, amount),
+        &[
+            owner_info.clone(),
+            player_wallet_info.clone(),
+            system_program_info.clone(),
+        ],
+    )?;
+    
+    let mut data = player_wallet_info.try_borrow_mut_data()?;
+    let player_wallet = PlayerWallet::load_mut(&mut data)?;
+    player_wallet.sol_balance = player_wallet.sol_balance
+        .checked_add(amount)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    
+    msg!("Deposited {} lamports", amount);
+    Ok(())
+}
+
+/// Withdraw deposited lamports from the player wallet account to the owner
+pub fn process_withdraw_sol(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: u64,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    
+    // Get the required accounts
+    let owner_info = next_account_info(account_info_iter)?;
+    let player_wallet_info = next_account_info(account_info_iter)?;
+    
+    // Ensure the player wallet account belongs to this program
+    if player_wallet_info.owner != program_id {
+        msg!("Player wallet account does not belong to this program");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    let rent_exempt_minimum = Rent::get()?.minimum_balance(player_wallet_info.data_len());
+    let mut data = player_wallet_info.try_borrow_mut_data()?;
+    let player_wallet = PlayerWallet::load_mut(&mut data)?;
+    player_wallet.check_owner(owner_info)?;
+    
+    if amount > player_wallet.sol_balance {
+        msg!("Requested {} lamports, {} deposited", amount, player_wallet.sol_balance);
+        return Err(PlayerWalletError::InsufficientSolBalance.into());
+    }
+    
+    // The player wallet must stay rent-exempt
+    let available = player_wallet_info.lamports().saturating_sub(rent_exempt_minimum);
+    if amount > available {
+        msg!("Requested {} lamports, {} available above rent", amount, available);
+        return Err(PlayerWalletError::InsufficientSolBalance.into());
+    }
+    
+    player_wallet.sol_balance -= amount;
+    **player_wallet_info.try_borrow_mut_lamports()? -= amount;
+    **owner_info.try_borrow_mut_lamports()? += amount;
+    
+    msg!("Withdrew {} lamports", amount);
+    Ok(())
+}
@@ -0,0 +1,309 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    program::invoke_signed,
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+    msg,
+};
+use spl_associated_token_account::get_associated_token_address;
+use spl_token::state::Account as TokenAccount;
+
+use crate::state::{
+    find_vault_authority_address, load_account, save_account, CurrencyConfig, Holdings,
+    PlayerWallet, ProgramConfig, SeasonClock, StakePosition, StakingConfig,
+};
+use crate::error::PlayerWalletError;
+use crate::constants::{CURRENCY_CONFIG_SEED, STAKE_POSITION_SEED, STAKING_CONFIG_SEED};
+use crate::utils::create_pda_account;
+use super::achievement::unlock_achievements;
+use super::leaderboard::update_leaderboard;
+
+// Ensure a token account is the player's vault for `mint` and read its balance
+fn vault_balance(
+    program_id: &Pubkey,
+    player_wallet_info: &AccountInfo,
+    vault_token_account_info: &AccountInfo,
+    mint: &Pubkey,
+) -> Result<u64, ProgramError> {
+    let vault_authority = find_vault_authority_address(player_wallet_info.key, program_id).0;
+    if *vault_token_account_info.key != get_associated_token_address(&vault_authority, mint) {
+        msg!("Invalid vault token account for mint {}", mint);
+        return Err(PlayerWalletError::InvalidTokenAccount.into());
+    }
+    Ok(TokenAccount::unpack(&vault_token_account_info.data.borrow())?.amount)
+}
+
+/// Set the staking reward rate and lock period, creating the staking config on first use
+pub fn process_set_staking_config(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    reward_rate: u64,
+    lock_period_seconds: i64,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    
+    // Get the required accounts
+    let authority_info = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+    let staking_config_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+    
+    ProgramConfig::load_with_authority(program_id, config_info, authority_info)?;
+    
+    if lock_period_seconds < 0 {
+        msg!("Lock period cannot be negative");
+        return Err(ProgramError::InvalidArgument);
+    }
+    
+    let staking_config = if staking_config_info.owner == program_id {
+        let mut staking_config = StakingConfig::load(program_id, staking_config_info)?;
+        staking_config.reward_rate = reward_rate;
+        staking_config.lock_period_seconds = lock_period_seconds;
+        staking_config
+    } else {
+        let (staking_config_address, bump) = StakingConfig::find_address(program_id);
+        if *staking_config_info.key != staking_config_address {
+            msg!("Invalid staking config account");
+            return Err(ProgramError::InvalidArgument);
+        }
+        create_pda_account(
+            authority_info,
+            staking_config_info,
+            system_program_info,
+            program_id,
+            StakingConfig::LEN,
+            &[STAKING_CONFIG_SEED, &[bump]],
+        )?;
+        StakingConfig {
+            is_initialized: true,
+            reward_rate,
+            lock_period_seconds,
+            bump,
+        }
+    };
+    save_account(&staking_config, staking_config_info)?;
+    
+    msg!("Staking reward rate {}, lock period {}s", reward_rate, lock_period_seconds);
+    Ok(())
+}
+
+/// Stake game currency held in the player's vault, restarting the lock period
+pub fn process_stake(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: u64,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    
+    // Get the required accounts
+    let owner_info = next_account_info(account_info_iter)?;
+    let player_wallet_info = next_account_info(account_info_iter)?;
+    let currency_config_info = next_account_info(account_info_iter)?;
+    let staking_config_info = next_account_info(account_info_iter)?;
+    let stake_position_info = next_account_info(account_info_iter)?;
+    let vault_token_account_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+    
+    // Ensure the player wallet account belongs to this program
+    if player_wallet_info.owner != program_id {
+        msg!("Player wallet account does not belong to this program");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    PlayerWallet::load(&player_wallet_info.try_borrow_data()?)?.check_owner(owner_info)?;
+    
+    let currency_config = CurrencyConfig::load(program_id, currency_config_info)?;
+    let staking_config = StakingConfig::load(program_id, staking_config_info)?;
+    let now = Clock::get()?.unix_timestamp;
+    
+    // Load the stake position, creating it on the first stake
+    let mut stake_position = if stake_position_info.owner == program_id {
+        StakePosition::load(program_id, player_wallet_info.key, stake_position_info)?
+    } else {
+        let (stake_position_address, bump) = StakePosition::find_address(player_wallet_info.key, program_id);
+        if *stake_position_info.key != stake_position_address {
+            msg!("Invalid stake position account");
+            return Err(ProgramError::InvalidArgument);
+        }
+        create_pda_account(
+            owner_info,
+            stake_position_info,
+            system_program_info,
+            program_id,
+            StakePosition::LEN,
+            &[STAKE_POSITION_SEED, player_wallet_info.key.as_ref(), &[bump]],
+        )?;
+        StakePosition {
+            is_initialized: true,
+            player_wallet: *player_wallet_info.key,
+            mint: currency_config.mint,
+            amount: 0,
+            pending_rewards: 0,
+            last_update_time: now,
+            locked_until: now,
+            bump,
+        }
+    };
+    
+    // Only tokens in the vault that are not staked yet can be staked
+    let balance = vault_balance(program_id, player_wallet_info, vault_token_account_info, &stake_position.mint)?;
+    let staked = stake_position.amount
+        .checked_add(amount)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    if staked > balance {
+        msg!("Requested {} staked of {} in the vault", staked, balance);
+        return Err(PlayerWalletError::InsufficientStake.into());
+    }
+    
+    stake_position.accrue(now, staking_config.reward_rate)?;
+    stake_position.amount = staked;
+    stake_position.locked_until = now
+        .checked_add(staking_config.lock_period_seconds)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    save_account(&stake_position, stake_position_info)?;
+    
+    msg!("Staked {}, locked until {}", amount, stake_position.locked_until);
+    Ok(())
+}
+
+/// Unstake game currency once the lock period is over
+pub fn process_unstake(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: u64,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    
+    // Get the required accounts
+    let owner_info = next_account_info(account_info_iter)?;
+    let player_wallet_info = next_account_info(account_info_iter)?;
+    let staking_config_info = next_account_info(account_info_iter)?;
+    let stake_position_info = next_account_info(account_info_iter)?;
+    
+    // Ensure the player wallet account belongs to this program
+    if player_wallet_info.owner != program_id {
+        msg!("Player wallet account does not belong to this program");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    PlayerWallet::load(&player_wallet_info.try_borrow_data()?)?.check_owner(owner_info)?;
+    
+    let staking_config = StakingConfig::load(program_id, staking_config_info)?;
+    let mut stake_position = StakePosition::load(program_id, player_wallet_info.key, stake_position_info)?;
+    
+    let now = Clock::get()?.unix_timestamp;
+    if !stake_position.is_unlocked(now) {
+        msg!("Stake locked until {}", stake_position.locked_until);
+        return Err(PlayerWalletError::StakeLocked.into());
+    }
+    if amount > stake_position.amount {
+        msg!("Requested {} of {} staked", amount, stake_position.amount);
+        return Err(PlayerWalletError::InsufficientStake.into());
+    }
+    
+    // Rewards earned so far stay claimable
+    stake_position.accrue(now, staking_config.reward_rate)?;
+    stake_position.amount -= amount;
+    save_account(&stake_position, stake_position_info)?;
+    
+    msg!("Unstaked {}", amount);
+    Ok(())
+}
+
+/// Mint accrued staking rewards into the player's vault once the lock period is over
+pub fn process_claim_staking_rewards(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    
+    // Get the required accounts
+    let owner_info = next_account_info(account_info_iter)?;
+    let player_wallet_info = next_account_info(account_info_iter)?;
+    let holdings_info = next_account_info(account_info_iter)?;
+    let currency_config_info = next_account_info(account_info_iter)?;
+    let mint_info = next_account_info(account_info_iter)?;
+    let staking_config_info = next_account_info(account_info_iter)?;
+    let stake_position_info = next_account_info(account_info_iter)?;
+    let vault_token_account_info = next_account_info(account_info_iter)?;
+    let token_program_info = next_account_info(account_info_iter)?;
+    let leaderboard_info = next_account_info(account_info_iter)?;
+    let season_clock_info = next_account_info(account_info_iter)?;
+    
+    if *token_program_info.key != spl_token::id() {
+        msg!("Invalid token program");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    
+    // Ensure the player wallet account belongs to this program
+    if player_wallet_info.owner != program_id {
+        msg!("Player wallet account does not belong to this program");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    let mut data = player_wallet_info.try_borrow_mut_data()?;
+    let player_wallet = PlayerWallet::load_mut(&mut data)?;
+    player_wallet.check_owner(owner_info)?;
+    
+    let mut currency_config = CurrencyConfig::load(program_id, currency_config_info)?;
+    if *mint_info.key != currency_config.mint {
+        msg!("Invalid currency mint account");
+        return Err(ProgramError::InvalidArgument);
+    }
+    let staking_config = StakingConfig::load(program_id, staking_config_info)?;
+    let mut stake_position = StakePosition::load(program_id, player_wallet_info.key, stake_position_info)?;
+    vault_balance(program_id, player_wallet_info, vault_token_account_info, mint_info.key)?;
+    
+    let clock = Clock::get()?;
+    let now = clock.unix_timestamp;
+    if !stake_position.is_unlocked(now) {
+        msg!("Stake locked until {}", stake_position.locked_until);
+        return Err(PlayerWalletError::StakeLocked.into());
+    }
+    
+    stake_position.accrue(now, staking_config.reward_rate)?;
+    let rewards = stake_position.pending_rewards;
+    stake_position.pending_rewards = 0;
+    save_account(&stake_position, stake_position_info)?;
+    
+    if *holdings_info.key != Holdings::find_address(player_wallet_info.key, program_id).0 {
+        msg!("Invalid holdings account");
+        return Err(ProgramError::InvalidArgument);
+    }
+    let mut holdings = load_account::<Holdings>(program_id, holdings_info)?;
+    let holding = holdings.get_mut(mint_info.key)?;
+    
+    // Staking rewards count towards the same per-epoch cap as minted rewards
+    currency_config.record_issuance(clock.epoch, rewards)?;
+    save_account(&currency_config, currency_config_info)?;
+    
+    invoke_signed(
+        &spl_token::instruction::mint_to(
+            token_program_info.key,
+            mint_info.key,
+            vault_token_account_info.key,
+            currency_config_info.key,
+            &[],
+            rewards,
+        )?,
+        &[
+            mint_info.clone(),
+            vault_token_account_info.clone(),
+            currency_config_info.clone(),
+            token_program_info.clone(),
+        ],
+        &[&[CURRENCY_CONFIG_SEED, &[currency_config.bump]]],
+    )?;
+    
+    holding.vault_amount = TokenAccount::unpack(&vault_token_account_info.data.borrow())?.amount;
+    player_wallet.roll_over_season(SeasonClock::seasons_closed(program_id, season_clock_info)?);
+    holdings.apply_aggregates(player_wallet)?;
+    save_account(&holdings, holdings_info)?;
+    let player_wallets = [(player_wallet_info.key, &*player_wallet)];
+    update_leaderboard(program_id, leaderboard_info, &player_wallets)?;
+    unlock_achievements(program_id, &player_wallets, account_info_iter.as_slice())?;
+    
+    msg!("Claimed {} staking rewards", rewards);
+    Ok(())
+}
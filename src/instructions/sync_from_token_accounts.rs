@@ -0,0 +1,65 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    msg,
+};
+
+use crate::state::{load_account, save_account, Holdings, PlayerWallet, SeasonClock};
+use super::achievement::unlock_achievements;
+use super::holdings::read_holding_amount;
+use super::leaderboard::update_leaderboard;
+
+/// Recompute holdings and wallet aggregates from the owner's SPL token accounts
+/// Anyone can sync a wallet. One token account is expected per holding, in
+/// holdings order, and each must be the owner's associated token account for
+/// that mint so no balance can be left out or counted twice. Token-2022
+/// holdings are read from the associated account under that program, and a
+/// closed account counts as an empty balance.
+pub fn process_sync_from_token_accounts(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    
+    // Get the required accounts
+    let player_wallet_info = next_account_info(account_info_iter)?;
+    let holdings_info = next_account_info(account_info_iter)?;
+    let leaderboard_info = next_account_info(account_info_iter)?;
+    let season_clock_info = next_account_info(account_info_iter)?;
+    
+    // Ensure the player wallet account belongs to this program
+    if player_wallet_info.owner != program_id {
+        msg!("Player wallet account does not belong to this program");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    let mut data = player_wallet_info.try_borrow_mut_data()?;
+    let player_wallet = PlayerWallet::load_mut(&mut data)?;
+    
+    if *holdings_info.key != Holdings::find_address(player_wallet_info.key, program_id).0 {
+        msg!("Invalid holdings account");
+        return Err(ProgramError::InvalidArgument);
+    }
+    let mut holdings = load_account::<Holdings>(program_id, holdings_info)?;
+    
+    if account_info_iter.as_slice().len() < holdings.entries.len() {
+        msg!("Expected {} token accounts", holdings.entries.len());
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+    let (token_account_infos, achievement_accounts) = account_info_iter.as_slice().split_at(holdings.entries.len());
+    
+    for (holding, token_account_info) in holdings.entries.iter_mut().zip(token_account_infos) {
+        holding.amount = read_holding_amount(holding, &player_wallet.wallet_address, token_account_info)?;
+    }
+    
+    player_wallet.roll_over_season(SeasonClock::seasons_closed(program_id, season_clock_info)?);
+    holdings.apply_aggregates(player_wallet)?;
+    save_account(&holdings, holdings_info)?;
+    let player_wallets = [(player_wallet_info.key, &*player_wallet)];
+    update_leaderboard(program_id, leaderboard_info, &player_wallets)?;
+    unlock_achievements(program_id, &player_wallets, achievement_accounts)?;
+    
+    msg!("Player wallet synced from {} token accounts", token_account_infos.len());
+    Ok(())
+}
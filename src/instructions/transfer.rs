@@ -0,0 +1,83 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::{rent::Rent, Sysvar},
+    msg,
+};
+
+use super::achievement::unlock_achievements;
+use super::leaderboard::update_leaderboard;
+use crate::state::{PlayerWallet, SeasonClock};
+use crate::error::PlayerWalletError;
+
+/// Transfer deposited SOL from one player wallet to another
+pub fn process_transfer(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: u64,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    
+    // Get the required accounts
+    let owner_info = next_account_info(account_info_iter)?;
+    let sender_info = next_account_info(account_info_iter)?;
+    let recipient_info = next_account_info(account_info_iter)?;
+    let leaderboard_info = next_account_info(account_info_iter)?;
+    let season_clock_info = next_account_info(account_info_iter)?;
+    
+    // Ensure both player wallet accounts belong to this program
+    if sender_info.owner != program_id || recipient_info.owner != program_id {
+        msg!("Player wallet account does not belong to this program");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    if sender_info.key == recipient_info.key {
+        msg!("Cannot transfer to the same player wallet");
+        return Err(ProgramError::InvalidArgument);
+    }
+    // Empty transfers would only inflate the transaction counts
+    if amount == 0 {
+        msg!("Transfer amount must be greater than zero");
+        return Err(ProgramError::InvalidArgument);
+    }
+    
+    let rent_exempt_minimum = Rent::get()?.minimum_balance(sender_info.data_len());
+    let mut sender_data = sender_info.try_borrow_mut_data()?;
+    let sender = PlayerWallet::load_mut(&mut sender_data)?;
+    sender.check_owner(owner_info)?;
+    
+    let mut recipient_data = recipient_info.try_borrow_mut_data()?;
+    let recipient = PlayerWallet::load_mut(&mut recipient_data)?;
+    if recipient.is_initialized == 0 {
+        return Err(PlayerWalletError::AccountNotInitialized.into());
+    }
+    if recipient.is_frozen() {
+        msg!("Recipient wallet is frozen");
+        return Err(PlayerWalletError::WalletFrozen.into());
+    }
+    
+    // The sender must stay rent-exempt
+    let available = sender_info.lamports().saturating_sub(rent_exempt_minimum);
+    if amount > sender.sol_balance || amount > available {
+        msg!("Requested {} lamports, {} deposited", amount, sender.sol_balance);
+        return Err(PlayerWalletError::InsufficientSolBalance.into());
+    }
+    
+    sender.sol_balance -= amount;
+    recipient.sol_balance = recipient.sol_balance
+        .checked_add(amount)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    let seasons_closed = SeasonClock::seasons_closed(program_id, season_clock_info)?;
+    sender.record_transaction(seasons_closed)?;
+    recipient.record_transaction(seasons_closed)?;
+    
+    **sender_info.try_borrow_mut_lamports()? -= amount;
+    **recipient_info.try_borrow_mut_lamports()? += amount;
+    let player_wallets = [(sender_info.key, &*sender), (recipient_info.key, &*recipient)];
+    update_leaderboard(program_id, leaderboard_info, &player_wallets)?;
+    unlock_achievements(program_id, &player_wallets, account_info_iter.as_slice())?;
+    
+    msg!("Transferred {} lamports to {}", amount, recipient_info.key);
+    Ok(())
+}
@@ -0,0 +1,63 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    pubkey::Pubkey,
+    sysvar::{rent::Rent, Sysvar},
+    msg,
+};
+
+use crate::state::{save_account, ProgramConfig, Treasury};
+use crate::error::PlayerWalletError;
+
+/// Set the fee charged on wallet creation
+pub fn process_set_creation_fee(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    creation_fee_lamports: u64,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    
+    // Get the required accounts
+    let authority_info = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+    
+    let mut config = ProgramConfig::load_with_authority(program_id, config_info, authority_info)?;
+    config.creation_fee_lamports = creation_fee_lamports;
+    save_account(&config, config_info)?;
+    
+    msg!("Creation fee set to {} lamports", creation_fee_lamports);
+    Ok(())
+}
+
+/// Withdraw collected fees from the treasury
+pub fn process_withdraw_treasury(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: u64,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    
+    // Get the required accounts
+    let authority_info = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+    let treasury_info = next_account_info(account_info_iter)?;
+    let destination_info = next_account_info(account_info_iter)?;
+    
+    ProgramConfig::load_with_authority(program_id, config_info, authority_info)?;
+    Treasury::load(program_id, treasury_info)?;
+    
+    // The treasury must stay rent-exempt
+    let rent = Rent::get()?;
+    let available = treasury_info.lamports()
+        .saturating_sub(rent.minimum_balance(treasury_info.data_len()));
+    if amount > available {
+        msg!("Requested {} lamports, {} available", amount, available);
+        return Err(PlayerWalletError::InsufficientTreasuryFunds.into());
+    }
+    
+    **treasury_info.try_borrow_mut_lamports()? -= amount;
+    **destination_info.try_borrow_mut_lamports()? += amount;
+    
+    msg!("Withdrew {} lamports from the treasury", amount);
+    Ok(())
+}
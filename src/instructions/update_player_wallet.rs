@@ -5,18 +5,14 @@ use solana_program::{
     pubkey::Pubkey,
     msg,
 };
-use borsh::{BorshDeserialize, BorshSerialize};
 
-use crate::state::PlayerWallet;
+use crate::state::{PlayerWallet, SeasonClock};
 use crate::error::PlayerWalletError;
 
 /// Update an existing player wallet account
 pub fn process_update_player_wallet(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
-    nb_tokens: u32,
-    amount_total_tokens: u32,
-    amount_total_value_stablecoin: u32,
     date_portfolio: u64,
 ) -> ProgramResult {
     let account_info_iter = &mut accounts.iter();
@@ -24,6 +20,7 @@ pub fn process_update_player_wallet(
     // Get the required accounts
     let owner_info = next_account_info(account_info_iter)?;
     let player_wallet_info = next_account_info(account_info_iter)?;
+    let season_clock_info = next_account_info(account_info_iter)?;
     
     // Ensure the owner is a signer
     if !owner_info.is_signer {
@@ -37,12 +34,12 @@ pub fn process_update_player_wallet(
         return Err(ProgramError::IncorrectProgramId);
     }
     
-    // Deserialize the player wallet data
-    let mut player_wallet = PlayerWallet::try_from_slice(&player_wallet_info.data.borrow())
-        .map_err(|_| PlayerWalletError::AccountNotInitialized)?;
+    // View the player wallet data in place
+    let mut data = player_wallet_info.try_borrow_mut_data()?;
+    let player_wallet = PlayerWallet::load_mut(&mut data)?;
     
     // Ensure the account is initialized
-    if !player_wallet.is_initialized {
+    if player_wallet.is_initialized == 0 {
         return Err(PlayerWalletError::AccountNotInitialized.into());
     }
     
@@ -51,15 +48,16 @@ pub fn process_update_player_wallet(
         return Err(PlayerWalletError::Unauthorized.into());
     }
     
-    // Update the player wallet data
-    player_wallet.nb_tokens = nb_tokens;
-    player_wallet.amount_total_tokens = amount_total_tokens;
-    player_wallet.amount_total_value_stablecoin = amount_total_value_stablecoin;
-    player_wallet.date_portfolio = date_portfolio;
-    player_wallet.nb_transactions += 1;
+    // Frozen wallets cannot be updated
+    if player_wallet.is_frozen() {
+        return Err(PlayerWalletError::WalletFrozen.into());
+    }
     
-    // Serialize the updated player wallet data to the account
-    player_wallet.serialize(&mut *player_wallet_info.data.borrow_mut())?;
+    player_wallet.roll_over_season(SeasonClock::seasons_closed(program_id, season_clock_info)?);
+    
+    // Update the player wallet data; token aggregates are only derived from the
+    // holdings and transactions only counted by trades and transfers
+    player_wallet.date_portfolio = date_portfolio;
     
     msg!("Player wallet updated successfully");
     Ok(())
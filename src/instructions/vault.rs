@@ -0,0 +1,250 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    msg,
+};
+use spl_associated_token_account::instruction::create_associated_token_account_idempotent;
+
+use super::achievement::unlock_achievements;
+use super::leaderboard::update_leaderboard;
+use crate::state::{
+    find_vault_authority_address, load_account, save_account, Holding, Holdings, PlayerWallet,
+    SeasonClock, StakePosition,
+};
+use crate::error::PlayerWalletError;
+use crate::constants::VAULT_SEED;
+use crate::token::{
+    check_mint_extensions, check_token_program, get_token_account_address, unpack_token_account,
+};
+
+// Ensure a token account is the associated token account of `wallet` for `mint`
+fn check_token_account(
+    token_account_info: &AccountInfo,
+    wallet: &Pubkey,
+    mint: &Pubkey,
+    token_program_id: &Pubkey,
+) -> ProgramResult {
+    if *token_account_info.key != get_token_account_address(wallet, mint, token_program_id) {
+        msg!("Invalid token account for mint {}", mint);
+        return Err(PlayerWalletError::InvalidTokenAccount.into());
+    }
+    Ok(())
+}
+
+// Refresh a holding from its token account and vault balances
+// Reading the balances back accounts for any transfer fee withheld by the mint
+fn refresh_holding(
+    holding: &mut Holding,
+    owner_token_account_info: &AccountInfo,
+    vault_token_account_info: &AccountInfo,
+) -> ProgramResult {
+    holding.amount = unpack_token_account(owner_token_account_info)?.amount;
+    holding.vault_amount = unpack_token_account(vault_token_account_info)?.amount;
+    Ok(())
+}
+
+/// Deposit SPL tokens from the owner's token account into the player's vault
+pub fn process_deposit(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: u64,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    
+    // Get the required accounts
+    let payer_info = next_account_info(account_info_iter)?;
+    let owner_info = next_account_info(account_info_iter)?;
+    let player_wallet_info = next_account_info(account_info_iter)?;
+    let holdings_info = next_account_info(account_info_iter)?;
+    let mint_info = next_account_info(account_info_iter)?;
+    let owner_token_account_info = next_account_info(account_info_iter)?;
+    let vault_authority_info = next_account_info(account_info_iter)?;
+    let vault_token_account_info = next_account_info(account_info_iter)?;
+    let token_program_info = next_account_info(account_info_iter)?;
+    let associated_token_program_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+    let leaderboard_info = next_account_info(account_info_iter)?;
+    let season_clock_info = next_account_info(account_info_iter)?;
+    
+    // Ensure the payer is a signer
+    if !payer_info.is_signer {
+        msg!("Payer must be a signer");
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    
+    check_token_program(token_program_info)?;
+    
+    // Ensure the player wallet account belongs to this program
+    if player_wallet_info.owner != program_id {
+        msg!("Player wallet account does not belong to this program");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    let mut data = player_wallet_info.try_borrow_mut_data()?;
+    let player_wallet = PlayerWallet::load_mut(&mut data)?;
+    player_wallet.check_owner(owner_info)?;
+    
+    if *holdings_info.key != Holdings::find_address(player_wallet_info.key, program_id).0 {
+        msg!("Invalid holdings account");
+        return Err(ProgramError::InvalidArgument);
+    }
+    let mut holdings = load_account::<Holdings>(program_id, holdings_info)?;
+    let holding = holdings.get_mut(mint_info.key)?;
+    
+    let vault_authority = find_vault_authority_address(player_wallet_info.key, program_id).0;
+    if *vault_authority_info.key != vault_authority {
+        msg!("Invalid vault authority");
+        return Err(ProgramError::InvalidArgument);
+    }
+    check_token_account(owner_token_account_info, owner_info.key, mint_info.key, token_program_info.key)?;
+    check_token_account(vault_token_account_info, &vault_authority, mint_info.key, token_program_info.key)?;
+    check_mint_extensions(mint_info)?;
+    
+    // Create the vault token account on the first deposit of this mint
+    invoke(
+        &create_associated_token_account_idempotent(
+            payer_info.key,
+            &vault_authority,
+            mint_info.key,
+            token_program_info.key,
+        ),
+        &[
+            payer_info.clone(),
+            vault_token_account_info.clone(),
+            vault_authority_info.clone(),
+            mint_info.clone(),
+            system_program_info.clone(),
+            token_program_info.clone(),
+            associated_token_program_info.clone(),
+        ],
+    )?;
+    
+    invoke(
+        &spl_token_2022::instruction::transfer_checked(
+            token_program_info.key,
+            owner_token_account_info.key,
+            mint_info.key,
+            vault_token_account_info.key,
+            owner_info.key,
+            &[],
+            amount,
+            holding.decimals,
+        )?,
+        &[
+            owner_token_account_info.clone(),
+            mint_info.clone(),
+            vault_token_account_info.clone(),
+            owner_info.clone(),
+            token_program_info.clone(),
+        ],
+    )?;
+    
+    refresh_holding(holding, owner_token_account_info, vault_token_account_info)?;
+    player_wallet.roll_over_season(SeasonClock::seasons_closed(program_id, season_clock_info)?);
+    holdings.apply_aggregates(player_wallet)?;
+    save_account(&holdings, holdings_info)?;
+    let player_wallets = [(player_wallet_info.key, &*player_wallet)];
+    update_leaderboard(program_id, leaderboard_info, &player_wallets)?;
+    unlock_achievements(program_id, &player_wallets, account_info_iter.as_slice())?;
+    
+    msg!("Deposited {} of mint {}", amount, mint_info.key);
+    Ok(())
+}
+
+/// Withdraw SPL tokens from the player's vault to the owner's token account
+pub fn process_withdraw(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: u64,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    
+    // Get the required accounts
+    let owner_info = next_account_info(account_info_iter)?;
+    let player_wallet_info = next_account_info(account_info_iter)?;
+    let holdings_info = next_account_info(account_info_iter)?;
+    let mint_info = next_account_info(account_info_iter)?;
+    let owner_token_account_info = next_account_info(account_info_iter)?;
+    let vault_authority_info = next_account_info(account_info_iter)?;
+    let vault_token_account_info = next_account_info(account_info_iter)?;
+    let token_program_info = next_account_info(account_info_iter)?;
+    let stake_position_info = next_account_info(account_info_iter)?;
+    let leaderboard_info = next_account_info(account_info_iter)?;
+    let season_clock_info = next_account_info(account_info_iter)?;
+    
+    check_token_program(token_program_info)?;
+    
+    // Staked tokens stay in the vault but cannot be withdrawn
+    let staked = if stake_position_info.owner == program_id {
+        let stake_position = StakePosition::load(program_id, player_wallet_info.key, stake_position_info)?;
+        if stake_position.mint == *mint_info.key { stake_position.amount } else { 0 }
+    } else if *stake_position_info.key != StakePosition::find_address(player_wallet_info.key, program_id).0 {
+        msg!("Invalid stake position account");
+        return Err(ProgramError::InvalidArgument);
+    } else {
+        0
+    };
+    
+    // Ensure the player wallet account belongs to this program
+    if player_wallet_info.owner != program_id {
+        msg!("Player wallet account does not belong to this program");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    let mut data = player_wallet_info.try_borrow_mut_data()?;
+    let player_wallet = PlayerWallet::load_mut(&mut data)?;
+    player_wallet.check_owner(owner_info)?;
+    
+    if *holdings_info.key != Holdings::find_address(player_wallet_info.key, program_id).0 {
+        msg!("Invalid holdings account");
+        return Err(ProgramError::InvalidArgument);
+    }
+    let mut holdings = load_account::<Holdings>(program_id, holdings_info)?;
+    let holding = holdings.get_mut(mint_info.key)?;
+    
+    let (vault_authority, vault_bump) = find_vault_authority_address(player_wallet_info.key, program_id);
+    if *vault_authority_info.key != vault_authority {
+        msg!("Invalid vault authority");
+        return Err(ProgramError::InvalidArgument);
+    }
+    check_token_account(owner_token_account_info, owner_info.key, mint_info.key, token_program_info.key)?;
+    check_token_account(vault_token_account_info, &vault_authority, mint_info.key, token_program_info.key)?;
+    
+    // The vault authority PDA signs for the vault token account
+    invoke_signed(
+        &spl_token_2022::instruction::transfer_checked(
+            token_program_info.key,
+            vault_token_account_info.key,
+            mint_info.key,
+            owner_token_account_info.key,
+            &vault_authority,
+            &[],
+            amount,
+            holding.decimals,
+        )?,
+        &[
+            vault_token_account_info.clone(),
+            mint_info.clone(),
+            owner_token_account_info.clone(),
+            vault_authority_info.clone(),
+            token_program_info.clone(),
+        ],
+        &[&[VAULT_SEED, player_wallet_info.key.as_ref(), &[vault_bump]]],
+    )?;
+    
+    refresh_holding(holding, owner_token_account_info, vault_token_account_info)?;
+    if holding.vault_amount < staked {
+        msg!("{} staked tokens must stay in the vault", staked);
+        return Err(PlayerWalletError::StakeLocked.into());
+    }
+    player_wallet.roll_over_season(SeasonClock::seasons_closed(program_id, season_clock_info)?);
+    holdings.apply_aggregates(player_wallet)?;
+    save_account(&holdings, holdings_info)?;
+    let player_wallets = [(player_wallet_info.key, &*player_wallet)];
+    update_leaderboard(program_id, leaderboard_info, &player_wallets)?;
+    unlock_achievements(program_id, &player_wallets, account_info_iter.as_slice())?;
+    
+    msg!("Withdrew {} of mint {}", amount, mint_info.key);
+    Ok(())
+}
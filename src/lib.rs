@@ -2,6 +2,11 @@ pub mod instructions;
 pub mod state;
 pub mod error;
 pub mod constants;
+pub mod utils;
+pub mod filters;
+pub mod merkle;
+pub mod token;
+pub mod events;
 
 #[cfg(test)]
 pub mod test;
@@ -42,21 +47,193 @@ pub fn process_instruction(
             msg!("Instruction: CreatePlayerWallet");
             process_create_player_wallet(program_id, accounts, name)
         },
-        PlayerWalletInstruction::UpdatePlayerWallet { 
-            nb_tokens, 
-            amount_total_tokens,
-            amount_total_value_stablecoin,
-            date_portfolio,
-        } => {
+        PlayerWalletInstruction::UpdatePlayerWallet { date_portfolio } => {
             msg!("Instruction: UpdatePlayerWallet");
-            process_update_player_wallet(
-                program_id, 
-                accounts, 
-                nb_tokens,
-                amount_total_tokens,
-                amount_total_value_stablecoin,
-                date_portfolio,
-            )
+            process_update_player_wallet(program_id, accounts, date_portfolio)
+        },
+        PlayerWalletInstruction::AddBlockedName { rule } => {
+            msg!("Instruction: AddBlockedName");
+            process_add_blocked_name(program_id, accounts, rule)
+        },
+        PlayerWalletInstruction::RemoveBlockedName { rule } => {
+            msg!("Instruction: RemoveBlockedName");
+            process_remove_blocked_name(program_id, accounts, rule)
+        },
+        PlayerWalletInstruction::SetCreationFee { creation_fee_lamports } => {
+            msg!("Instruction: SetCreationFee");
+            process_set_creation_fee(program_id, accounts, creation_fee_lamports)
+        },
+        PlayerWalletInstruction::WithdrawTreasury { amount } => {
+            msg!("Instruction: WithdrawTreasury");
+            process_withdraw_treasury(program_id, accounts, amount)
+        },
+        PlayerWalletInstruction::MigrateWallet => {
+            msg!("Instruction: MigrateWallet");
+            process_migrate_wallet(program_id, accounts)
+        },
+        PlayerWalletInstruction::SetWalletStatus { status } => {
+            msg!("Instruction: SetWalletStatus");
+            process_set_wallet_status(program_id, accounts, status)
+        },
+        PlayerWalletInstruction::AddHolding { mint } => {
+            msg!("Instruction: AddHolding");
+            process_add_holding(program_id, accounts, mint)
+        },
+        PlayerWalletInstruction::UpdateHolding { mint } => {
+            msg!("Instruction: UpdateHolding");
+            process_update_holding(program_id, accounts, mint)
+        },
+        PlayerWalletInstruction::RemoveHolding { mint } => {
+            msg!("Instruction: RemoveHolding");
+            process_remove_holding(program_id, accounts, mint)
+        },
+        PlayerWalletInstruction::SyncFromTokenAccounts => {
+            msg!("Instruction: SyncFromTokenAccounts");
+            process_sync_from_token_accounts(program_id, accounts)
+        },
+        PlayerWalletInstruction::CreatePriceFeed { mint, publisher } => {
+            msg!("Instruction: CreatePriceFeed");
+            process_create_price_feed(program_id, accounts, mint, publisher)
+        },
+        PlayerWalletInstruction::PublishPrice { price, expo, conf } => {
+            msg!("Instruction: PublishPrice");
+            process_publish_price(program_id, accounts, price, expo, conf)
+        },
+        PlayerWalletInstruction::Revalue => {
+            msg!("Instruction: Revalue");
+            process_revalue(program_id, accounts)
+        },
+        PlayerWalletInstruction::Deposit { amount } => {
+            msg!("Instruction: Deposit");
+            process_deposit(program_id, accounts, amount)
+        },
+        PlayerWalletInstruction::Withdraw { amount } => {
+            msg!("Instruction: Withdraw");
+            process_withdraw(program_id, accounts, amount)
+        },
+        PlayerWalletInstruction::DepositSol { amount } => {
+            msg!("Instruction: DepositSol");
+            process_deposit_sol(program_id, accounts, amount)
+        },
+        PlayerWalletInstruction::WithdrawSol { amount } => {
+            msg!("Instruction: WithdrawSol");
+            process_withdraw_sol(program_id, accounts, amount)
+        },
+        PlayerWalletInstruction::Transfer { amount } => {
+            msg!("Instruction: Transfer");
+            process_transfer(program_id, accounts, amount)
+        },
+        PlayerWalletInstruction::InitializeCurrency { decimals, updater, epoch_issuance_cap } => {
+            msg!("Instruction: InitializeCurrency");
+            process_initialize_currency(program_id, accounts, decimals, updater, epoch_issuance_cap)
+        },
+        PlayerWalletInstruction::SetCurrencyConfig { updater, epoch_issuance_cap } => {
+            msg!("Instruction: SetCurrencyConfig");
+            process_set_currency_config(program_id, accounts, updater, epoch_issuance_cap)
+        },
+        PlayerWalletInstruction::MintReward { amount } => {
+            msg!("Instruction: MintReward");
+            process_mint_reward(program_id, accounts, amount)
+        },
+        PlayerWalletInstruction::CreateDistributor { id, merkle_root, num_claims } => {
+            msg!("Instruction: CreateDistributor");
+            process_create_distributor(program_id, accounts, id, merkle_root, num_claims)
+        },
+        PlayerWalletInstruction::Claim { index, amount, proof } => {
+            msg!("Instruction: Claim");
+            process_claim(program_id, accounts, index, amount, proof)
+        },
+        PlayerWalletInstruction::SetStakingConfig { reward_rate, lock_period_seconds } => {
+            msg!("Instruction: SetStakingConfig");
+            process_set_staking_config(program_id, accounts, reward_rate, lock_period_seconds)
+        },
+        PlayerWalletInstruction::Stake { amount } => {
+            msg!("Instruction: Stake");
+            process_stake(program_id, accounts, amount)
+        },
+        PlayerWalletInstruction::Unstake { amount } => {
+            msg!("Instruction: Unstake");
+            process_unstake(program_id, accounts, amount)
+        },
+        PlayerWalletInstruction::ClaimStakingRewards => {
+            msg!("Instruction: ClaimStakingRewards");
+            process_claim_staking_rewards(program_id, accounts)
+        },
+        PlayerWalletInstruction::RegisterItem { slot } => {
+            msg!("Instruction: RegisterItem");
+            process_register_item(program_id, accounts, slot)
+        },
+        PlayerWalletInstruction::EquipItem { mint } => {
+            msg!("Instruction: EquipItem");
+            process_equip_item(program_id, accounts, mint)
+        },
+        PlayerWalletInstruction::UnequipItem { mint } => {
+            msg!("Instruction: UnequipItem");
+            process_unequip_item(program_id, accounts, mint)
+        },
+        PlayerWalletInstruction::SetMarketplaceConfig { fee_bps, fee_recipient } => {
+            msg!("Instruction: SetMarketplaceConfig");
+            process_set_marketplace_config(program_id, accounts, fee_bps, fee_recipient)
+        },
+        PlayerWalletInstruction::ListItem { price } => {
+            msg!("Instruction: ListItem");
+            process_list_item(program_id, accounts, price)
+        },
+        PlayerWalletInstruction::CancelListing => {
+            msg!("Instruction: CancelListing");
+            process_cancel_listing(program_id, accounts)
+        },
+        PlayerWalletInstruction::BuyItem { price } => {
+            msg!("Instruction: BuyItem");
+            process_buy_item(program_id, accounts, price)
+        },
+        PlayerWalletInstruction::CreateAuction { terms } => {
+            msg!("Instruction: CreateAuction");
+            process_create_auction(program_id, accounts, terms)
+        },
+        PlayerWalletInstruction::PlaceBid { amount } => {
+            msg!("Instruction: PlaceBid");
+            process_place_bid(program_id, accounts, amount)
+        },
+        PlayerWalletInstruction::SettleAuction => {
+            msg!("Instruction: SettleAuction");
+            process_settle_auction(program_id, accounts)
+        },
+        PlayerWalletInstruction::CreateRental { price, duration_seconds } => {
+            msg!("Instruction: CreateRental");
+            process_create_rental(program_id, accounts, price, duration_seconds)
+        },
+        PlayerWalletInstruction::RentItem { price, slot } => {
+            msg!("Instruction: RentItem");
+            process_rent_item(program_id, accounts, price, slot)
+        },
+        PlayerWalletInstruction::ReclaimRental => {
+            msg!("Instruction: ReclaimRental");
+            process_reclaim_rental(program_id, accounts)
+        },
+        PlayerWalletInstruction::SetLeaderboardConfig { metric, size } => {
+            msg!("Instruction: SetLeaderboardConfig");
+            process_set_leaderboard_config(program_id, accounts, metric, size)
+        },
+        PlayerWalletInstruction::CreateSeason { id, start_time, end_time } => {
+            msg!("Instruction: CreateSeason");
+            process_create_season(program_id, accounts, id, start_time, end_time)
+        },
+        PlayerWalletInstruction::CloseSeason { id } => {
+            msg!("Instruction: CloseSeason");
+            process_close_season(program_id, accounts, id)
+        },
+        PlayerWalletInstruction::SnapshotSeasonStats { season_id } => {
+            msg!("Instruction: SnapshotSeasonStats");
+            process_snapshot_season_stats(program_id, accounts, season_id)
+        },
+        PlayerWalletInstruction::CreateAchievement { id, name, metric, threshold } => {
+            msg!("Instruction: CreateAchievement");
+            process_create_achievement(program_id, accounts, id, name, metric, threshold)
+        },
+        PlayerWalletInstruction::CreatePlayerAchievements => {
+            msg!("Instruction: CreatePlayerAchievements");
+            process_create_player_achievements(program_id, accounts)
         },
     }
 }
@@ -0,0 +1,99 @@
+// Merkle trees for reward distributions
+// The on-chain `Claim` instruction only needs `hash_leaf` and `verify`; the
+// `MerkleTree` builder is meant for the off-chain tooling publishing a
+// distributor root and handing each player their proof. Leaves and inner
+// nodes are hashed with distinct prefixes, and sibling pairs are sorted
+// before hashing so proofs carry no left/right flags.
+use solana_program::{hash::hashv, pubkey::Pubkey};
+
+const LEAF_PREFIX: &[u8] = &[0];
+const NODE_PREFIX: &[u8] = &[1];
+
+/// A single claim in a distribution
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ClaimLeaf {
+    /// Position of the claim, also its bit in the distributor's claimed bitmap
+    pub index: u64,
+    /// Player wallet entitled to the claim
+    pub player_wallet: Pubkey,
+    /// Amount claimable, in base units
+    pub amount: u64,
+}
+
+/// Hash of a claim leaf
+pub fn hash_leaf(index: u64, player_wallet: &Pubkey, amount: u64) -> [u8; 32] {
+    hashv(&[
+        LEAF_PREFIX,
+        &index.to_le_bytes(),
+        player_wallet.as_ref(),
+        &amount.to_le_bytes(),
+    ])
+    .to_bytes()
+}
+
+/// Hash of an inner node from its two children, in either order
+pub fn hash_pair(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+    let (first, second) = if a <= b { (a, b) } else { (b, a) };
+    hashv(&[NODE_PREFIX, first, second]).to_bytes()
+}
+
+/// Check that `proof` links `leaf` to `root`
+pub fn verify(proof: &[[u8; 32]], root: &[u8; 32], leaf: [u8; 32]) -> bool {
+    proof.iter().fold(leaf, |node, sibling| hash_pair(&node, sibling)) == *root
+}
+
+/// Merkle tree over a list of claims
+pub struct MerkleTree {
+    // Hashes of each level, from the leaves up to the root
+    layers: Vec<Vec<[u8; 32]>>,
+}
+
+impl MerkleTree {
+    /// Build the tree of `claims`, whose indexes must be `0..claims.len()` in order
+    pub fn new(claims: &[ClaimLeaf]) -> Self {
+        let leaves: Vec<[u8; 32]> = claims.iter()
+            .map(|claim| hash_leaf(claim.index, &claim.player_wallet, claim.amount))
+            .collect();
+
+        // An odd node out is carried up to the next level unchanged
+        let mut layers = vec![leaves];
+        while layers.last().is_some_and(|layer| layer.len() > 1) {
+            let next = layers.last().unwrap()
+                .chunks(2)
+                .map(|pair| match pair {
+                    [a, b] => hash_pair(a, b),
+                    [a] => *a,
+                    _ => unreachable!(),
+                })
+                .collect();
+            layers.push(next);
+        }
+
+        Self { layers }
+    }
+
+    /// Root to store in the distributor, all zeros for an empty tree
+    pub fn root(&self) -> [u8; 32] {
+        self.layers.last()
+            .and_then(|layer| layer.first())
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Proof for the claim at `index`, or `None` if there is no such claim
+    pub fn proof(&self, index: usize) -> Option<Vec<[u8; 32]>> {
+        if index >= self.layers[0].len() {
+            return None;
+        }
+
+        let mut proof = Vec::new();
+        let mut position = index;
+        for layer in &self.layers[..self.layers.len() - 1] {
+            if let Some(sibling) = layer.get(position ^ 1) {
+                proof.push(*sibling);
+            }
+            position /= 2;
+        }
+        Some(proof)
+    }
+}
@@ -0,0 +1,75 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
+    hash::hash,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    msg,
+};
+use crate::error::PlayerWalletError;
+
+// Length of the type discriminator prefixed to every state account
+pub const DISCRIMINATOR_LEN: usize = 8;
+
+/// A state account type whose data starts with a type discriminator
+/// The discriminator is the first 8 bytes of `sha256("account:<TypeName>")`,
+/// so an account of one type can never be parsed as another. Borsh accounts
+/// are stored as `[discriminator][borsh data]` through the helpers below.
+pub trait AccountType {
+    const DISCRIMINATOR: [u8; DISCRIMINATOR_LEN];
+}
+
+// Compute the discriminator of an account type from its name
+pub fn account_discriminator(type_name: &str) -> [u8; DISCRIMINATOR_LEN] {
+    let mut discriminator = [0u8; DISCRIMINATOR_LEN];
+    discriminator.copy_from_slice(
+        &hash(format!("account:{}", type_name).as_bytes()).to_bytes()[..DISCRIMINATOR_LEN],
+    );
+    discriminator
+}
+
+// Decode account data, ensuring it holds the expected account type
+pub fn unpack_account<T: AccountType + BorshDeserialize>(data: &[u8]) -> Result<T, ProgramError> {
+    if data.len() < DISCRIMINATOR_LEN || data[..DISCRIMINATOR_LEN] == [0u8; DISCRIMINATOR_LEN] {
+        return Err(PlayerWalletError::AccountNotInitialized.into());
+    }
+    if data[..DISCRIMINATOR_LEN] != T::DISCRIMINATOR {
+        return Err(PlayerWalletError::InvalidAccountType.into());
+    }
+    
+    T::deserialize(&mut &data[DISCRIMINATOR_LEN..])
+        .map_err(|_| PlayerWalletError::InvalidAccountType.into())
+}
+
+// Encode an account with its discriminator
+pub fn pack_account<T: AccountType + BorshSerialize>(account: &T) -> Result<Vec<u8>, ProgramError> {
+    let mut data = T::DISCRIMINATOR.to_vec();
+    account.serialize(&mut data)?;
+    Ok(data)
+}
+
+// Load a state account, ensuring it is owned by the program and holds the expected type
+pub fn load_account<T: AccountType + BorshDeserialize>(
+    program_id: &Pubkey,
+    account_info: &AccountInfo,
+) -> Result<T, ProgramError> {
+    if account_info.owner != program_id {
+        msg!("Account {} does not belong to this program", account_info.key);
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    
+    unpack_account(&account_info.data.borrow())
+}
+
+// Write a state account with its discriminator
+pub fn save_account<T: AccountType + BorshSerialize>(account: &T, account_info: &AccountInfo) -> ProgramResult {
+    let mut data = account_info.try_borrow_mut_data()?;
+    if data.len() < DISCRIMINATOR_LEN {
+        return Err(ProgramError::AccountDataTooSmall);
+    }
+    
+    data[..DISCRIMINATOR_LEN].copy_from_slice(&T::DISCRIMINATOR);
+    account.serialize(&mut &mut data[DISCRIMINATOR_LEN..])?;
+    Ok(())
+}
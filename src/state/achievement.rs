@@ -0,0 +1,128 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::AccountInfo,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    msg,
+};
+use crate::constants::{ACHIEVEMENT_SEED, MAX_ACHIEVEMENT_NAME_LENGTH, PLAYER_ACHIEVEMENTS_SEED};
+use crate::state::{load_account, AccountType, PlayerWallet, WalletMetric, DISCRIMINATOR_LEN};
+
+/// An achievement players unlock by taking a wallet metric to a threshold
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct Achievement {
+    // Flag to check if the account is initialized
+    pub is_initialized: bool,
+    
+    // Achievement number, also its bit in each player's achievements bitmap
+    pub id: u8,
+    
+    // Display name (max `MAX_ACHIEVEMENT_NAME_LENGTH` bytes)
+    pub name: String,
+    
+    // Wallet metric the achievement tracks
+    pub metric: WalletMetric,
+    
+    // Metric value that unlocks the achievement
+    pub threshold: u64,
+    
+    // Bump seed of the achievement PDA
+    pub bump: u8,
+}
+
+impl AccountType for Achievement {
+    const DISCRIMINATOR: [u8; DISCRIMINATOR_LEN] = [30, 253, 162, 142, 30, 160, 66, 62];
+}
+
+impl Achievement {
+    // Size of the Achievement struct for account allocation
+    // - discriminator: 8 bytes
+    // - is_initialized: 1 byte (bool)
+    // - id: 1 byte (u8)
+    // - name: 4 bytes (String length) + max name bytes
+    // - metric: 1 byte (u8)
+    // - threshold: 8 bytes (u64)
+    // - bump: 1 byte (u8)
+    pub const LEN: usize = DISCRIMINATOR_LEN + 1 + 1 + 4 + MAX_ACHIEVEMENT_NAME_LENGTH + 1 + 8 + 1;
+    
+    // Derive the address of an achievement PDA
+    pub fn find_address(id: u8, program_id: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[ACHIEVEMENT_SEED, &[id]], program_id)
+    }
+    
+    // Load an achievement, ensuring it is the achievement PDA of its id
+    pub fn load(program_id: &Pubkey, achievement_info: &AccountInfo) -> Result<Self, ProgramError> {
+        let achievement: Self = load_account(program_id, achievement_info)?;
+        if *achievement_info.key != Self::find_address(achievement.id, program_id).0 {
+            msg!("Invalid achievement account");
+            return Err(ProgramError::InvalidArgument);
+        }
+        Ok(achievement)
+    }
+    
+    // Check whether a player wallet has reached the threshold
+    pub fn is_reached(&self, player_wallet: &PlayerWallet) -> bool {
+        self.metric.value(player_wallet) >= self.threshold
+    }
+}
+
+/// The achievements a player has unlocked
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct PlayerAchievements {
+    // Flag to check if the account is initialized
+    pub is_initialized: bool,
+    
+    // Player wallet the achievements belong to
+    pub player_wallet: Pubkey,
+    
+    // Bump seed of the player achievements PDA
+    pub bump: u8,
+    
+    // One bit per achievement id, set once unlocked
+    pub unlocked: [u8; 32],
+}
+
+impl AccountType for PlayerAchievements {
+    const DISCRIMINATOR: [u8; DISCRIMINATOR_LEN] = [108, 185, 169, 217, 73, 77, 75, 170];
+}
+
+impl PlayerAchievements {
+    // Size of the PlayerAchievements struct for account allocation
+    // - discriminator: 8 bytes
+    // - is_initialized: 1 byte (bool)
+    // - player_wallet: 32 bytes (Pubkey)
+    // - bump: 1 byte (u8)
+    // - unlocked: 32 bytes (one bit for each of the 256 achievement ids)
+    pub const LEN: usize = DISCRIMINATOR_LEN + 1 + 32 + 1 + 32;
+    
+    // Derive the address of a player wallet's achievements PDA
+    pub fn find_address(player_wallet: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[PLAYER_ACHIEVEMENTS_SEED, player_wallet.as_ref()], program_id)
+    }
+    
+    // Load a player's achievements, ensuring it is the achievements PDA of `player_wallet`
+    pub fn load(
+        program_id: &Pubkey,
+        player_wallet: &Pubkey,
+        player_achievements_info: &AccountInfo,
+    ) -> Result<Self, ProgramError> {
+        if *player_achievements_info.key != Self::find_address(player_wallet, program_id).0 {
+            msg!("Invalid player achievements account");
+            return Err(ProgramError::InvalidArgument);
+        }
+        
+        load_account(program_id, player_achievements_info)
+    }
+    
+    // Check whether an achievement is unlocked
+    pub fn is_unlocked(&self, id: u8) -> bool {
+        self.unlocked[id as usize / 8] & (1 << (id % 8)) != 0
+    }
+    
+    // Unlock an achievement, returning whether it was locked until now
+    pub fn unlock(&mut self, id: u8) -> bool {
+        let newly_unlocked = !self.is_unlocked(id);
+        self.unlocked[id as usize / 8] |= 1 << (id % 8);
+        newly_unlocked
+    }
+}
@@ -0,0 +1,153 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::AccountInfo,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    msg,
+};
+use crate::constants::AUCTION_SEED;
+use crate::state::{load_account, AccountType, DISCRIMINATOR_LEN};
+
+/// How an auction finds its winner, stored as a single byte
+#[repr(u8)]
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AuctionKind {
+    // Highest bid at the end time wins, outbid bidders are refunded
+    English = 0,
+    // Price falls linearly from the start price to the end price, the first bid wins
+    Dutch = 1,
+}
+
+/// Terms chosen by the seller when creating an auction
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct AuctionTerms {
+    /// Auction mode
+    pub kind: AuctionKind,
+    /// Unix timestamp bidding opens
+    pub start_time: i64,
+    /// Unix timestamp bidding closes
+    pub end_time: i64,
+    /// Reserve price (English) or opening price (Dutch), in game currency base units
+    pub start_price: u64,
+    /// Closing price (Dutch), in game currency base units
+    pub end_price: u64,
+    /// Minimum raise over the highest bid (English), in game currency base units
+    pub min_bid_increment: u64,
+}
+
+/// An item escrowed for a time-boxed auction in game currency
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct Auction {
+    // Flag to check if the account is initialized
+    pub is_initialized: bool,
+    
+    // Auction mode
+    pub kind: AuctionKind,
+    
+    // Player wallet selling the item
+    pub seller: Pubkey,
+    
+    // Item mint, held by the auction's associated token account
+    pub mint: Pubkey,
+    
+    // Unix timestamp bidding opens
+    pub start_time: i64,
+    
+    // Unix timestamp bidding closes
+    pub end_time: i64,
+    
+    // Reserve price (English) or opening price (Dutch), in game currency base units
+    pub start_price: u64,
+    
+    // Closing price (Dutch), in game currency base units
+    pub end_price: u64,
+    
+    // Minimum raise over the highest bid (English), in game currency base units
+    pub min_bid_increment: u64,
+    
+    // Highest bid so far, held by the auction's game currency token account
+    pub highest_bid: u64,
+    
+    // Player wallet of the highest bidder, default if there is no bid yet
+    pub highest_bidder: Pubkey,
+    
+    // Owner of the highest bidder's wallet, refunded when outbid
+    pub highest_bidder_owner: Pubkey,
+    
+    // Bump seed of the auction PDA
+    pub bump: u8,
+}
+
+impl AccountType for Auction {
+    const DISCRIMINATOR: [u8; DISCRIMINATOR_LEN] = [218, 94, 247, 242, 126, 233, 131, 81];
+}
+
+impl Auction {
+    // Size of the Auction struct for account allocation
+    // - discriminator: 8 bytes
+    // - is_initialized: 1 byte (bool)
+    // - kind: 1 byte (u8)
+    // - seller: 32 bytes (Pubkey)
+    // - mint: 32 bytes (Pubkey)
+    // - start_time: 8 bytes (i64)
+    // - end_time: 8 bytes (i64)
+    // - start_price: 8 bytes (u64)
+    // - end_price: 8 bytes (u64)
+    // - min_bid_increment: 8 bytes (u64)
+    // - highest_bid: 8 bytes (u64)
+    // - highest_bidder: 32 bytes (Pubkey)
+    // - highest_bidder_owner: 32 bytes (Pubkey)
+    // - bump: 1 byte (u8)
+    pub const LEN: usize = DISCRIMINATOR_LEN + 1 + 1 + 32 + 32 + 8 + 8 + 8 + 8 + 8 + 8 + 32 + 32 + 1;
+    
+    // Derive the address of an item's auction PDA
+    pub fn find_address(mint: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[AUCTION_SEED, mint.as_ref()], program_id)
+    }
+    
+    // Load an auction, ensuring it is the auction PDA of `mint`
+    pub fn load(program_id: &Pubkey, mint: &Pubkey, auction_info: &AccountInfo) -> Result<Self, ProgramError> {
+        if *auction_info.key != Self::find_address(mint, program_id).0 {
+            msg!("Invalid auction account");
+            return Err(ProgramError::InvalidArgument);
+        }
+        
+        load_account(program_id, auction_info)
+    }
+    
+    // Check whether anyone has bid yet
+    pub fn has_bid(&self) -> bool {
+        self.highest_bidder != Pubkey::default()
+    }
+    
+    // Check whether bids are accepted at `now`
+    pub fn is_open(&self, now: i64) -> bool {
+        now >= self.start_time
+            && now < self.end_time
+            && !(self.kind == AuctionKind::Dutch && self.has_bid())
+    }
+    
+    // Check whether the auction can be settled at `now`
+    pub fn is_over(&self, now: i64) -> bool {
+        now >= self.end_time || (self.kind == AuctionKind::Dutch && self.has_bid())
+    }
+    
+    // Lowest bid accepted at `now`
+    // English auctions need the reserve price, then a raise of at least
+    // `min_bid_increment` (and at least one unit) over the highest bid. Dutch
+    // auctions charge the current price, falling linearly over the auction.
+    pub fn min_bid(&self, now: i64) -> Result<u64, ProgramError> {
+        match self.kind {
+            AuctionKind::English if self.has_bid() => self.highest_bid
+                .checked_add(self.min_bid_increment.max(1))
+                .ok_or(ProgramError::ArithmeticOverflow),
+            AuctionKind::English => Ok(self.start_price),
+            AuctionKind::Dutch => {
+                let duration = (self.end_time - self.start_time) as u128;
+                let elapsed = (now.clamp(self.start_time, self.end_time) - self.start_time) as u128;
+                let drop = (self.start_price - self.end_price) as u128 * elapsed / duration;
+                Ok(self.start_price - drop as u64)
+            },
+        }
+    }
+}
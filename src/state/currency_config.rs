@@ -0,0 +1,90 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::AccountInfo,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    msg,
+};
+use crate::error::PlayerWalletError;
+use crate::constants::{CURRENCY_CONFIG_SEED, CURRENCY_MINT_SEED};
+use crate::state::{load_account, AccountType, DISCRIMINATOR_LEN};
+
+/// Settings of the in-game currency, whose mint authority is this PDA
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct CurrencyConfig {
+    // Flag to check if the account is initialized
+    pub is_initialized: bool,
+    
+    // Game currency mint PDA
+    pub mint: Pubkey,
+    
+    // Key allowed to mint rewards
+    pub updater: Pubkey,
+    
+    // Maximum amount mintable per epoch, in base units
+    pub epoch_issuance_cap: u64,
+    
+    // Epoch `issued_this_epoch` applies to
+    pub current_epoch: u64,
+    
+    // Amount minted during `current_epoch`, in base units
+    pub issued_this_epoch: u64,
+    
+    // Bump seed of the currency config PDA
+    pub bump: u8,
+}
+
+impl AccountType for CurrencyConfig {
+    const DISCRIMINATOR: [u8; DISCRIMINATOR_LEN] = [47, 186, 34, 214, 80, 76, 249, 57];
+}
+
+impl CurrencyConfig {
+    // Size of the CurrencyConfig struct for account allocation
+    // - discriminator: 8 bytes
+    // - is_initialized: 1 byte (bool)
+    // - mint: 32 bytes (Pubkey)
+    // - updater: 32 bytes (Pubkey)
+    // - epoch_issuance_cap: 8 bytes (u64)
+    // - current_epoch: 8 bytes (u64)
+    // - issued_this_epoch: 8 bytes (u64)
+    // - bump: 1 byte (u8)
+    pub const LEN: usize = DISCRIMINATOR_LEN + 1 + 32 + 32 + 8 + 8 + 8 + 1;
+    
+    // Derive the address of the currency config PDA
+    pub fn find_address(program_id: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[CURRENCY_CONFIG_SEED], program_id)
+    }
+    
+    // Derive the address of the game currency mint PDA
+    pub fn find_mint_address(program_id: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[CURRENCY_MINT_SEED], program_id)
+    }
+    
+    // Load the currency config, ensuring it is the currency config PDA
+    pub fn load(program_id: &Pubkey, currency_config_info: &AccountInfo) -> Result<Self, ProgramError> {
+        if *currency_config_info.key != Self::find_address(program_id).0 {
+            msg!("Invalid currency config account");
+            return Err(ProgramError::InvalidAccountData);
+        }
+        
+        load_account(program_id, currency_config_info)
+    }
+    
+    // Record `amount` as issued in `epoch`, starting a new tally on each epoch
+    pub fn record_issuance(&mut self, epoch: u64, amount: u64) -> Result<(), ProgramError> {
+        if epoch != self.current_epoch {
+            self.current_epoch = epoch;
+            self.issued_this_epoch = 0;
+        }
+        
+        let issued = self.issued_this_epoch
+            .checked_add(amount)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        if issued > self.epoch_issuance_cap {
+            msg!("Issuance cap reached: {} of {} issued this epoch", self.issued_this_epoch, self.epoch_issuance_cap);
+            return Err(PlayerWalletError::IssuanceCapExceeded.into());
+        }
+        self.issued_this_epoch = issued;
+        Ok(())
+    }
+}
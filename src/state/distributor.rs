@@ -0,0 +1,86 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    msg,
+};
+use crate::error::PlayerWalletError;
+use crate::constants::DISTRIBUTOR_SEED;
+use crate::state::{AccountType, DISCRIMINATOR_LEN};
+
+/// A merkle reward distribution, paying claims out of its token vault
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct Distributor {
+    // Flag to check if the account is initialized
+    pub is_initialized: bool,
+    
+    // Identifier of the distribution, part of the PDA seeds
+    pub id: u64,
+    
+    // Mint of the distributed token
+    pub mint: Pubkey,
+    
+    // Root of the claims merkle tree, see `crate::merkle`
+    pub merkle_root: [u8; 32],
+    
+    // Number of claims in the tree
+    pub num_claims: u64,
+    
+    // Total amount claimed so far, in base units
+    pub total_claimed: u64,
+    
+    // Bump seed of the distributor PDA
+    pub bump: u8,
+    
+    // One bit per claim index, set once claimed
+    pub claimed_bitmap: Vec<u8>,
+}
+
+impl AccountType for Distributor {
+    const DISCRIMINATOR: [u8; DISCRIMINATOR_LEN] = [90, 90, 217, 147, 6, 32, 135, 4];
+}
+
+impl Distributor {
+    // Get the size of the Distributor struct for account allocation
+    pub fn get_account_size(num_claims: u64) -> usize {
+        // Size calculation:
+        // - discriminator: 8 bytes
+        // - is_initialized: 1 byte (bool)
+        // - id: 8 bytes (u64)
+        // - mint: 32 bytes (Pubkey)
+        // - merkle_root: 32 bytes
+        // - num_claims: 8 bytes (u64)
+        // - total_claimed: 8 bytes (u64)
+        // - bump: 1 byte (u8)
+        // - claimed_bitmap: 4 bytes (Vec length) + one bit per claim
+        DISCRIMINATOR_LEN + 1 + 8 + 32 + 32 + 8 + 8 + 1 + 4 + Self::bitmap_len(num_claims)
+    }
+    
+    // Number of bitmap bytes needed for `num_claims` claims
+    pub fn bitmap_len(num_claims: u64) -> usize {
+        num_claims.div_ceil(8) as usize
+    }
+    
+    // Derive the address of a distributor PDA
+    pub fn find_address(id: u64, program_id: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[DISTRIBUTOR_SEED, &id.to_le_bytes()], program_id)
+    }
+    
+    // Check whether the claim at `index` has been paid out
+    pub fn is_claimed(&self, index: u64) -> bool {
+        index < self.num_claims && self.claimed_bitmap[(index / 8) as usize] & (1 << (index % 8)) != 0
+    }
+    
+    // Mark the claim at `index` as paid out
+    pub fn set_claimed(&mut self, index: u64) -> Result<(), ProgramError> {
+        if index >= self.num_claims {
+            msg!("Claim index {} out of range", index);
+            return Err(ProgramError::InvalidArgument);
+        }
+        if self.is_claimed(index) {
+            return Err(PlayerWalletError::AlreadyClaimed.into());
+        }
+        self.claimed_bitmap[(index / 8) as usize] |= 1 << (index % 8);
+        Ok(())
+    }
+}
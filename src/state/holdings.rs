@@ -0,0 +1,125 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+use crate::error::PlayerWalletError;
+use crate::constants::{HOLDINGS_SEED, MAX_HOLDINGS, VAULT_SEED};
+use crate::state::{AccountType, PlayerWallet, DISCRIMINATOR_LEN};
+
+/// A token held by the player
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Holding {
+    /// Token mint
+    pub mint: Pubkey,
+    /// Amount held in the owner's token account, in base units
+    pub amount: u64,
+    /// Decimals of the mint
+    pub decimals: u8,
+    /// Last price used to value the holding
+    pub last_price: u64,
+    /// Amount deposited in the player's vault, in base units
+    pub vault_amount: u64,
+    /// Token program owning the mint, and so the owner's token account
+    pub token_program: Pubkey,
+}
+
+impl Holding {
+    // Total amount held, in the owner's token account and the vault
+    pub fn total_amount(&self) -> Result<u64, ProgramError> {
+        self.amount
+            .checked_add(self.vault_amount)
+            .ok_or(ProgramError::ArithmeticOverflow)
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct Holdings {
+    // Flag to check if the account is initialized
+    pub is_initialized: bool,
+    
+    // Player wallet the holdings belong to
+    pub player_wallet: Pubkey,
+    
+    // Bump seed of the holdings PDA
+    pub bump: u8,
+    
+    // Holdings, one per mint (max `MAX_HOLDINGS`)
+    pub entries: Vec<Holding>,
+}
+
+impl AccountType for Holdings {
+    const DISCRIMINATOR: [u8; DISCRIMINATOR_LEN] = [57, 217, 12, 192, 169, 250, 151, 188];
+}
+
+impl Holdings {
+    // Size of a single Holding entry
+    // - mint: 32 bytes (Pubkey)
+    // - amount: 8 bytes (u64)
+    // - decimals: 1 byte (u8)
+    // - last_price: 8 bytes (u64)
+    // - vault_amount: 8 bytes (u64)
+    // - token_program: 32 bytes (Pubkey)
+    pub const HOLDING_LEN: usize = 32 + 8 + 1 + 8 + 8 + 32;
+    
+    // Derive the address of a player wallet's holdings PDA
+    pub fn find_address(player_wallet: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[HOLDINGS_SEED, player_wallet.as_ref()], program_id)
+    }
+    
+    // Get the size of the Holdings struct for account allocation
+    pub fn get_account_size(nb_entries: usize) -> usize {
+        // Size calculation:
+        // - discriminator: 8 bytes
+        // - is_initialized: 1 byte (bool)
+        // - player_wallet: 32 bytes (Pubkey)
+        // - bump: 1 byte (u8)
+        // - entries: 4 bytes (Vec length) + each entry
+        DISCRIMINATOR_LEN + 1 + 32 + 1 + 4 + nb_entries * Self::HOLDING_LEN
+    }
+    
+    // Find the holding for a mint
+    pub fn get_mut(&mut self, mint: &Pubkey) -> Result<&mut Holding, ProgramError> {
+        self.entries.iter_mut()
+            .find(|holding| holding.mint == *mint)
+            .ok_or_else(|| PlayerWalletError::HoldingNotFound.into())
+    }
+    
+    // Add a holding for a mint not held yet
+    pub fn add(&mut self, holding: Holding) -> Result<(), ProgramError> {
+        if self.entries.iter().any(|h| h.mint == holding.mint) {
+            return Err(PlayerWalletError::HoldingAlreadyExists.into());
+        }
+        if self.entries.len() >= MAX_HOLDINGS {
+            return Err(PlayerWalletError::TooManyHoldings.into());
+        }
+        self.entries.push(holding);
+        Ok(())
+    }
+    
+    // Remove the holding for a mint, unless tokens are still in the vault
+    pub fn remove(&mut self, mint: &Pubkey) -> Result<Holding, ProgramError> {
+        let position = self.entries.iter()
+            .position(|holding| holding.mint == *mint)
+            .ok_or(PlayerWalletError::HoldingNotFound)?;
+        if self.entries[position].vault_amount > 0 {
+            return Err(PlayerWalletError::VaultNotEmpty.into());
+        }
+        Ok(self.entries.remove(position))
+    }
+    
+    // Recompute the player wallet aggregates from the holdings
+    pub fn apply_aggregates(&self, player_wallet: &mut PlayerWallet) -> Result<(), ProgramError> {
+        player_wallet.nb_tokens = self.entries.len() as u32;
+        player_wallet.amount_total_tokens = self.entries.iter()
+            .try_fold(0u64, |total, holding| {
+                total.checked_add(holding.total_amount()?).ok_or(ProgramError::ArithmeticOverflow)
+            })?;
+        Ok(())
+    }
+}
+
+// Derive the address of the PDA owning a player wallet's vault token accounts
+pub fn find_vault_authority_address(player_wallet: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[VAULT_SEED, player_wallet.as_ref()], program_id)
+}
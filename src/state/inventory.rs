@@ -0,0 +1,178 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::AccountInfo,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    msg,
+};
+use crate::error::PlayerWalletError;
+use crate::constants::{
+    INVENTORY_CAPACITY_INCREMENT, INVENTORY_SEED, ITEM_REGISTRATION_SEED, MAX_INVENTORY_ITEMS,
+};
+use crate::state::{load_account, AccountType, DISCRIMINATOR_LEN};
+
+/// A game item NFT registered by the player
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct InventoryItem {
+    /// Item mint
+    pub mint: Pubkey,
+    /// Equipment slot the item goes in, defined by the game
+    pub slot: u8,
+    /// Whether the item is currently equipped in its slot
+    pub equipped: bool,
+    /// Unix timestamp a rented item can be used until, 0 for an item the player holds
+    pub usable_until: i64,
+}
+
+impl InventoryItem {
+    // Check whether the item is rented rather than held
+    pub fn is_rented(&self) -> bool {
+        self.usable_until != 0
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct Inventory {
+    // Flag to check if the account is initialized
+    pub is_initialized: bool,
+    
+    // Player wallet the inventory belongs to
+    pub player_wallet: Pubkey,
+    
+    // Bump seed of the inventory PDA
+    pub bump: u8,
+    
+    // Number of items the account is currently sized for
+    pub capacity: u32,
+    
+    // Registered items, one per mint (max `MAX_INVENTORY_ITEMS`)
+    pub items: Vec<InventoryItem>,
+}
+
+impl AccountType for Inventory {
+    const DISCRIMINATOR: [u8; DISCRIMINATOR_LEN] = [97, 218, 167, 233, 216, 170, 49, 27];
+}
+
+impl Inventory {
+    // Size of a single InventoryItem entry
+    // - mint: 32 bytes (Pubkey)
+    // - slot: 1 byte (u8)
+    // - equipped: 1 byte (bool)
+    // - usable_until: 8 bytes (i64)
+    pub const ITEM_LEN: usize = 32 + 1 + 1 + 8;
+    
+    // Derive the address of a player wallet's inventory PDA
+    pub fn find_address(player_wallet: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[INVENTORY_SEED, player_wallet.as_ref()], program_id)
+    }
+    
+    // Get the size of the Inventory struct for account allocation
+    pub fn get_account_size(capacity: usize) -> usize {
+        // Size calculation:
+        // - discriminator: 8 bytes
+        // - is_initialized: 1 byte (bool)
+        // - player_wallet: 32 bytes (Pubkey)
+        // - bump: 1 byte (u8)
+        // - capacity: 4 bytes (u32)
+        // - items: 4 bytes (Vec length) + each item
+        DISCRIMINATOR_LEN + 1 + 32 + 1 + 4 + 4 + capacity * Self::ITEM_LEN
+    }
+    
+    // Load an inventory, ensuring it is the inventory PDA of `player_wallet`
+    pub fn load(
+        program_id: &Pubkey,
+        player_wallet: &Pubkey,
+        inventory_info: &AccountInfo,
+    ) -> Result<Self, ProgramError> {
+        if *inventory_info.key != Self::find_address(player_wallet, program_id).0 {
+            msg!("Invalid inventory account");
+            return Err(ProgramError::InvalidArgument);
+        }
+        
+        load_account(program_id, inventory_info)
+    }
+    
+    // Find the item for a mint
+    pub fn get_mut(&mut self, mint: &Pubkey) -> Result<&mut InventoryItem, ProgramError> {
+        self.items.iter_mut()
+            .find(|item| item.mint == *mint)
+            .ok_or_else(|| PlayerWalletError::ItemNotFound.into())
+    }
+    
+    // Register an item not in the inventory yet, growing the capacity when full
+    // Returns whether the capacity grew, in which case the account must be resized
+    pub fn add(&mut self, item: InventoryItem) -> Result<bool, ProgramError> {
+        if self.items.iter().any(|i| i.mint == item.mint) {
+            return Err(PlayerWalletError::ItemAlreadyRegistered.into());
+        }
+        if self.items.len() >= MAX_INVENTORY_ITEMS {
+            return Err(PlayerWalletError::InventoryFull.into());
+        }
+        self.items.push(item);
+        
+        let grew = self.items.len() > self.capacity as usize;
+        if grew {
+            self.capacity = (self.capacity as usize + INVENTORY_CAPACITY_INCREMENT).min(MAX_INVENTORY_ITEMS) as u32;
+        }
+        Ok(grew)
+    }
+    
+    // Equip an item, unequipping whatever item was in the same slot
+    pub fn equip(&mut self, mint: &Pubkey) -> Result<(), ProgramError> {
+        let slot = self.get_mut(mint)?.slot;
+        for item in self.items.iter_mut().filter(|item| item.slot == slot) {
+            item.equipped = item.mint == *mint;
+        }
+        Ok(())
+    }
+    
+    // Unequip an item
+    pub fn unequip(&mut self, mint: &Pubkey) -> Result<(), ProgramError> {
+        self.get_mut(mint)?.equipped = false;
+        Ok(())
+    }
+    
+    // Remove an item, keeping the capacity for later items
+    pub fn remove(&mut self, mint: &Pubkey) -> Result<InventoryItem, ProgramError> {
+        let position = self.items.iter()
+            .position(|item| item.mint == *mint)
+            .ok_or(PlayerWalletError::ItemNotFound)?;
+        Ok(self.items.remove(position))
+    }
+}
+
+/// The player wallet an item is registered to, so one item is only ever in
+/// one inventory
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct ItemRegistration {
+    // Flag to check if the account is initialized
+    pub is_initialized: bool,
+    
+    // Item mint
+    pub mint: Pubkey,
+    
+    // Player wallet whose inventory holds the item
+    pub player_wallet: Pubkey,
+    
+    // Bump seed of the item registration PDA
+    pub bump: u8,
+}
+
+impl AccountType for ItemRegistration {
+    const DISCRIMINATOR: [u8; DISCRIMINATOR_LEN] = [1, 112, 84, 67, 161, 153, 171, 160];
+}
+
+impl ItemRegistration {
+    // Size of the ItemRegistration struct for account allocation
+    // - discriminator: 8 bytes
+    // - is_initialized: 1 byte (bool)
+    // - mint: 32 bytes (Pubkey)
+    // - player_wallet: 32 bytes (Pubkey)
+    // - bump: 1 byte (u8)
+    pub const LEN: usize = DISCRIMINATOR_LEN + 1 + 32 + 32 + 1;
+    
+    // Derive the address of an item's registration PDA
+    pub fn find_address(mint: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[ITEM_REGISTRATION_SEED, mint.as_ref()], program_id)
+    }
+}
@@ -0,0 +1,105 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::AccountInfo,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    msg,
+};
+use crate::constants::{LEADERBOARD_SEED, MAX_LEADERBOARD_ENTRIES};
+use crate::state::{load_account, AccountType, WalletMetric, DISCRIMINATOR_LEN};
+
+/// A ranked player wallet
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct LeaderboardEntry {
+    /// Player wallet account
+    pub player_wallet: Pubkey,
+    /// Score under the leaderboard metric
+    pub score: u64,
+    /// Unix timestamp the player reached the score, earlier ranks higher on ties
+    pub updated_at: i64,
+}
+
+/// Top players by a configured metric, kept up to date by every instruction changing wallet metrics
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct Leaderboard {
+    // Flag to check if the account is initialized
+    pub is_initialized: bool,
+    
+    // Metric players are ranked by
+    pub metric: WalletMetric,
+    
+    // Number of players ranked (max `MAX_LEADERBOARD_ENTRIES`)
+    pub size: u16,
+    
+    // Bump seed of the leaderboard PDA
+    pub bump: u8,
+    
+    // Ranked players, best first
+    pub entries: Vec<LeaderboardEntry>,
+}
+
+impl AccountType for Leaderboard {
+    const DISCRIMINATOR: [u8; DISCRIMINATOR_LEN] = [247, 186, 238, 243, 194, 30, 9, 36];
+}
+
+impl Leaderboard {
+    // Size of a single LeaderboardEntry
+    // - player_wallet: 32 bytes (Pubkey)
+    // - score: 8 bytes (u64)
+    // - updated_at: 8 bytes (i64)
+    pub const ENTRY_LEN: usize = 32 + 8 + 8;
+    
+    // Size of the Leaderboard struct for account allocation, at the maximum size
+    // - discriminator: 8 bytes
+    // - is_initialized: 1 byte (bool)
+    // - metric: 1 byte (u8)
+    // - size: 2 bytes (u16)
+    // - bump: 1 byte (u8)
+    // - entries: 4 bytes (Vec length) + each entry
+    pub const LEN: usize = DISCRIMINATOR_LEN + 1 + 1 + 2 + 1 + 4 + MAX_LEADERBOARD_ENTRIES * Self::ENTRY_LEN;
+    
+    // Derive the address of the leaderboard PDA
+    pub fn find_address(program_id: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[LEADERBOARD_SEED], program_id)
+    }
+    
+    // Load the leaderboard, ensuring it is the leaderboard PDA
+    pub fn load(program_id: &Pubkey, leaderboard_info: &AccountInfo) -> Result<Self, ProgramError> {
+        if *leaderboard_info.key != Self::find_address(program_id).0 {
+            msg!("Invalid leaderboard account");
+            return Err(ProgramError::InvalidAccountData);
+        }
+        
+        load_account(program_id, leaderboard_info)
+    }
+    
+    // Record a player's score at `now`, returning whether they are ranked
+    // A ranked player moves with their score and keeps their timestamp while it
+    // is unchanged. An unranked player joins when the board has room or when
+    // they beat the lowest score, since the lowest player reached a tie first.
+    pub fn record(&mut self, player_wallet: &Pubkey, score: u64, now: i64) -> bool {
+        match self.entries.iter().position(|entry| entry.player_wallet == *player_wallet) {
+            Some(index) if self.entries[index].score == score => return true,
+            Some(index) => {
+                self.entries.remove(index);
+            },
+            None if self.entries.len() < self.size as usize => {},
+            None => match self.entries.last() {
+                Some(lowest) if score > lowest.score => {
+                    self.entries.pop();
+                },
+                _ => return false,
+            },
+        }
+        
+        let index = self.entries.partition_point(|entry| {
+            entry.score > score || (entry.score == score && entry.updated_at <= now)
+        });
+        self.entries.insert(index, LeaderboardEntry {
+            player_wallet: *player_wallet,
+            score,
+            updated_at: now,
+        });
+        true
+    }
+}
@@ -0,0 +1,108 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::AccountInfo,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    msg,
+};
+use crate::constants::{LISTING_SEED, MARKETPLACE_CONFIG_SEED};
+use crate::state::{load_account, AccountType, DISCRIMINATOR_LEN};
+
+/// Marketplace settings, shared by every listing
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct MarketplaceConfig {
+    // Flag to check if the account is initialized
+    pub is_initialized: bool,
+    
+    // Fee charged on each sale, in basis points of the price
+    pub fee_bps: u16,
+    
+    // Wallet whose game currency token account receives the fees
+    pub fee_recipient: Pubkey,
+    
+    // Bump seed of the marketplace config PDA
+    pub bump: u8,
+}
+
+impl AccountType for MarketplaceConfig {
+    const DISCRIMINATOR: [u8; DISCRIMINATOR_LEN] = [169, 22, 247, 131, 182, 200, 81, 124];
+}
+
+impl MarketplaceConfig {
+    // Size of the MarketplaceConfig struct for account allocation
+    // - discriminator: 8 bytes
+    // - is_initialized: 1 byte (bool)
+    // - fee_bps: 2 bytes (u16)
+    // - fee_recipient: 32 bytes (Pubkey)
+    // - bump: 1 byte (u8)
+    pub const LEN: usize = DISCRIMINATOR_LEN + 1 + 2 + 32 + 1;
+    
+    // Derive the address of the marketplace config PDA
+    pub fn find_address(program_id: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[MARKETPLACE_CONFIG_SEED], program_id)
+    }
+    
+    // Load the marketplace config, ensuring it is the marketplace config PDA
+    pub fn load(program_id: &Pubkey, marketplace_config_info: &AccountInfo) -> Result<Self, ProgramError> {
+        if *marketplace_config_info.key != Self::find_address(program_id).0 {
+            msg!("Invalid marketplace config account");
+            return Err(ProgramError::InvalidAccountData);
+        }
+        
+        load_account(program_id, marketplace_config_info)
+    }
+    
+    // Fee owed on a sale at `price`, rounding down
+    pub fn fee_on(&self, price: u64) -> u64 {
+        (price as u128 * self.fee_bps as u128 / 10_000) as u64
+    }
+}
+
+/// An item escrowed for sale at a fixed price in game currency
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct Listing {
+    // Flag to check if the account is initialized
+    pub is_initialized: bool,
+    
+    // Player wallet selling the item
+    pub seller: Pubkey,
+    
+    // Item mint, held by the listing's associated token account
+    pub mint: Pubkey,
+    
+    // Price in game currency base units
+    pub price: u64,
+    
+    // Bump seed of the listing PDA
+    pub bump: u8,
+}
+
+impl AccountType for Listing {
+    const DISCRIMINATOR: [u8; DISCRIMINATOR_LEN] = [218, 32, 50, 73, 43, 134, 26, 58];
+}
+
+impl Listing {
+    // Size of the Listing struct for account allocation
+    // - discriminator: 8 bytes
+    // - is_initialized: 1 byte (bool)
+    // - seller: 32 bytes (Pubkey)
+    // - mint: 32 bytes (Pubkey)
+    // - price: 8 bytes (u64)
+    // - bump: 1 byte (u8)
+    pub const LEN: usize = DISCRIMINATOR_LEN + 1 + 32 + 32 + 8 + 1;
+    
+    // Derive the address of an item's listing PDA
+    pub fn find_address(mint: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[LISTING_SEED, mint.as_ref()], program_id)
+    }
+    
+    // Load a listing, ensuring it is the listing PDA of `mint`
+    pub fn load(program_id: &Pubkey, mint: &Pubkey, listing_info: &AccountInfo) -> Result<Self, ProgramError> {
+        if *listing_info.key != Self::find_address(mint, program_id).0 {
+            msg!("Invalid listing account");
+            return Err(ProgramError::InvalidArgument);
+        }
+        
+        load_account(program_id, listing_info)
+    }
+}
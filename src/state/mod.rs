@@ -1,3 +1,39 @@
+pub mod account_type;
 pub mod player_wallet;
+pub mod player_wallet_legacy;
+pub mod program_config;
+pub mod name_blocklist;
+pub mod treasury;
+pub mod profile_index;
+pub mod holdings;
+pub mod price_feed;
+pub mod currency_config;
+pub mod distributor;
+pub mod staking;
+pub mod inventory;
+pub mod marketplace;
+pub mod auction;
+pub mod rental;
+pub mod leaderboard;
+pub mod season;
+pub mod achievement;
 
+pub use account_type::*;
 pub use player_wallet::*;
+pub use player_wallet_legacy::*;
+pub use program_config::*;
+pub use name_blocklist::*;
+pub use treasury::*;
+pub use profile_index::*;
+pub use holdings::*;
+pub use price_feed::*;
+pub use currency_config::*;
+pub use distributor::*;
+pub use staking::*;
+pub use inventory::*;
+pub use marketplace::*;
+pub use auction::*;
+pub use rental::*;
+pub use leaderboard::*;
+pub use season::*;
+pub use achievement::*;
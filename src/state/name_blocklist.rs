@@ -0,0 +1,128 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::AccountInfo,
+    hash::hash,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    msg,
+};
+use crate::error::PlayerWalletError;
+use crate::constants::NAME_BLOCKLIST_SEED;
+use crate::state::{unpack_account, AccountType, DISCRIMINATOR_LEN};
+
+/// A single rule of the name blocklist, matched against canonical names
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub enum BlockedNameRule {
+    /// SHA-256 hash of a canonical name that is blocked exactly
+    Exact([u8; 32]),
+    /// Canonical prefix no name may start with
+    Prefix(String),
+    /// Canonical fragment no name may contain
+    Substring(String),
+}
+
+impl BlockedNameRule {
+    // Build an exact-match rule from a plain name
+    pub fn exact(name: &str) -> Self {
+        Self::Exact(hash_canonical_name(name))
+    }
+    
+    // Bring the rule into canonical form so it matches canonical names
+    pub fn canonicalize(self) -> Self {
+        match self {
+            Self::Exact(name_hash) => Self::Exact(name_hash),
+            Self::Prefix(prefix) => Self::Prefix(canonicalize_name(&prefix)),
+            Self::Substring(fragment) => Self::Substring(canonicalize_name(&fragment)),
+        }
+    }
+    
+    // Check whether the rule blocks the given canonical name
+    pub fn matches(&self, canonical_name: &str) -> bool {
+        match self {
+            Self::Exact(name_hash) => hash(canonical_name.as_bytes()).to_bytes() == *name_hash,
+            Self::Prefix(prefix) => canonical_name.starts_with(prefix.as_str()),
+            Self::Substring(fragment) => canonical_name.contains(fragment.as_str()),
+        }
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Default)]
+pub struct NameBlocklist {
+    // Flag to check if the account is initialized
+    pub is_initialized: bool,
+    
+    // Rules checked against every new player name
+    pub rules: Vec<BlockedNameRule>,
+}
+
+impl AccountType for NameBlocklist {
+    const DISCRIMINATOR: [u8; DISCRIMINATOR_LEN] = [3, 121, 150, 26, 110, 153, 43, 103];
+}
+
+impl NameBlocklist {
+    // Derive the address of the name blocklist PDA
+    pub fn find_address(program_id: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[NAME_BLOCKLIST_SEED], program_id)
+    }
+    
+    // Load the name blocklist, treating a not yet created account as empty
+    pub fn load(program_id: &Pubkey, blocklist_info: &AccountInfo) -> Result<Self, ProgramError> {
+        if *blocklist_info.key != Self::find_address(program_id).0 {
+            msg!("Invalid name blocklist account");
+            return Err(ProgramError::InvalidArgument);
+        }
+        
+        if blocklist_info.owner != program_id || blocklist_info.data_is_empty() {
+            return Ok(Self::default());
+        }
+        
+        unpack_account(&blocklist_info.data.borrow())
+    }
+    
+    // Ensure no rule blocks the given name
+    // Names cannot be changed once a wallet is created, so only wallet
+    // creation checks them; a future rename path must call this as well.
+    pub fn check_name(&self, name: &str) -> Result<(), ProgramError> {
+        let canonical_name = canonicalize_name(name);
+        if self.rules.iter().any(|rule| rule.matches(&canonical_name)) {
+            msg!("Name is not allowed");
+            return Err(PlayerWalletError::NameNotAllowed.into());
+        }
+        Ok(())
+    }
+    
+    // Get the size of the NameBlocklist struct for account allocation
+    pub fn get_account_size(&self) -> usize {
+        // Size calculation:
+        // - discriminator: 8 bytes
+        // - is_initialized: 1 byte (bool)
+        // - rules: 4 bytes (Vec length) + each rule
+        //   - Exact: 1 byte (variant) + 32 bytes (hash)
+        //   - Prefix / Substring: 1 byte (variant) + 4 bytes (String length) + text
+        DISCRIMINATOR_LEN + 1 + 4 + self.rules.iter().map(|rule| match rule {
+            BlockedNameRule::Exact(_) => 1 + 32,
+            BlockedNameRule::Prefix(text) | BlockedNameRule::Substring(text) => 1 + 4 + text.len(),
+        }).sum::<usize>()
+    }
+}
+
+// Reduce a name to the form rules are matched against: lowercase, with common
+// digit look-alikes mapped back to letters so "Adm1n" is caught like "admin"
+pub fn canonicalize_name(name: &str) -> String {
+    name.chars()
+        .map(|c| match c.to_ascii_lowercase() {
+            '0' => 'o',
+            '1' => 'i',
+            '3' => 'e',
+            '4' => 'a',
+            '5' => 's',
+            '7' => 't',
+            other => other,
+        })
+        .collect()
+}
+
+// Hash a name in canonical form, as stored by `BlockedNameRule::Exact`
+pub fn hash_canonical_name(name: &str) -> [u8; 32] {
+    hash(canonicalize_name(name).as_bytes()).to_bytes()
+}
@@ -1,21 +1,57 @@
+use bytemuck::{Pod, Zeroable};
 use borsh::{BorshDeserialize, BorshSerialize};
 use solana_program::{
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
     program_error::ProgramError,
     pubkey::Pubkey,
+    msg,
 };
 use crate::error::PlayerWalletError;
-use crate::constants::MAX_NAME_LENGTH;
+use crate::constants::{MAX_NAME_LENGTH, PLAYER_WALLET_VERSION};
+use crate::state::{AccountType, PlayerWalletV1, DISCRIMINATOR_LEN};
 
-#[derive(BorshSerialize, BorshDeserialize)]
+/// Fixed-size player wallet, read and written in place without deserializing
+/// Offsets (bytes):
+/// -   0 discriminator
+/// -   8 version
+/// -   9 is_initialized
+/// -  10 name_len
+/// -  11 status
+/// -  12 nb_tokens
+/// -  16 nb_transactions
+/// -  20 wallet_address
+/// -  52 name
+/// - 104 amount_total_tokens
+/// - 112 amount_total_value_stablecoin
+/// - 120 date_portfolio
+/// - 128 sol_balance
+/// - 136 season_transactions
+/// - 140 season_index
+/// - 144 closed_season_transactions
+/// - 148 closed_season_index
+/// - 152 closed_season_total_tokens
+/// - 160 closed_season_value_stablecoin
+/// - 168 closed_season_date_portfolio
+/// - 176 closed_season_nb_tokens
+/// - 180 reserved for future fields
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
 pub struct PlayerWallet {
-    // Flag to check if the account is initialized
-    pub is_initialized: bool,
+    // Type discriminator, see `AccountType`
+    pub discriminator: [u8; DISCRIMINATOR_LEN],
     
-    // Player name (max 50 characters, letters and numbers only)
-    pub name: String,
+    // Layout version, always `PLAYER_WALLET_VERSION` once written
+    pub version: u8,
     
-    // Player wallet address (Solana public key)
-    pub wallet_address: Pubkey,
+    // Flag to check if the account is initialized (0 or 1)
+    pub is_initialized: u8,
+    
+    // Length in bytes of the player name
+    pub name_len: u8,
+    
+    // Wallet status, see `WalletStatus`
+    pub status: u8,
     
     // Number of tokens owned
     pub nb_tokens: u32,
@@ -23,18 +59,117 @@ pub struct PlayerWallet {
     // Number of transactions made
     pub nb_transactions: u32,
     
+    // Player wallet address (Solana public key)
+    pub wallet_address: Pubkey,
+    
+    // Player name (max 50 characters, letters and numbers only), zero padded
+    pub name: [u8; MAX_NAME_LENGTH],
+    
+    pub _padding1: [u8; 2],
+    
     // Total amount of tokens
-    pub amount_total_tokens: u32,
+    pub amount_total_tokens: u64,
     
     // Total value in stablecoin
-    pub amount_total_value_stablecoin: u32,
+    pub amount_total_value_stablecoin: u64,
     
     // Portfolio date (timestamp)
     pub date_portfolio: u64,
+    
+    // Lamports deposited by the owner, held on top of the rent-exempt minimum
+    pub sol_balance: u64,
+    
+    // Number of transactions made since the season counter started
+    pub season_transactions: u32,
+    
+    // Number of seasons closed when the season counter started
+    pub season_index: u32,
+    
+    // Transactions of the last season whose counter was rolled over
+    pub closed_season_transactions: u32,
+    
+    // Close index of that season, 0 if none
+    pub closed_season_index: u32,
+    
+    // Total amount of tokens when that season closed
+    pub closed_season_total_tokens: u64,
+    
+    // Total value in stablecoin when that season closed
+    pub closed_season_value_stablecoin: u64,
+    
+    // Portfolio date when that season closed
+    pub closed_season_date_portfolio: u64,
+    
+    // Number of tokens owned when that season closed
+    pub closed_season_nb_tokens: u32,
+    
+    // Zeroed space for future fields, so they can be added without a migration
+    pub _reserved: [u8; 12],
+}
+
+/// A player wallet's metrics as they were when a season closed
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SeasonMetrics {
+    /// Number of tokens owned
+    pub nb_tokens: u32,
+    /// Number of transactions made during the season
+    pub nb_transactions: u32,
+    /// Total amount of tokens
+    pub amount_total_tokens: u64,
+    /// Total value in stablecoin
+    pub amount_total_value_stablecoin: u64,
+    /// Portfolio date (timestamp)
+    pub date_portfolio: u64,
+}
+
+/// Status of a player wallet, stored as a single byte
+#[repr(u8)]
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WalletStatus {
+    Active = 0,
+    Frozen = 1,
+}
+
+/// Player wallet field that leaderboards and achievements track, stored as a single byte
+#[repr(u8)]
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WalletMetric {
+    // `amount_total_value_stablecoin`
+    PortfolioValue = 0,
+    // `amount_total_tokens`
+    TotalTokens = 1,
+    // `nb_transactions`
+    Transactions = 2,
+}
+
+impl WalletMetric {
+    // Value of this metric for a player wallet
+    pub fn value(&self, player_wallet: &PlayerWallet) -> u64 {
+        match self {
+            Self::PortfolioValue => player_wallet.amount_total_value_stablecoin,
+            Self::TotalTokens => player_wallet.amount_total_tokens,
+            Self::Transactions => player_wallet.nb_transactions as u64,
+        }
+    }
+}
+
+impl AccountType for PlayerWallet {
+    const DISCRIMINATOR: [u8; DISCRIMINATOR_LEN] = [70, 208, 240, 254, 222, 47, 4, 36];
 }
 
 impl PlayerWallet {
-    pub fn new(name: String, wallet_address: Pubkey) -> Result<Self, ProgramError> {
+    // Size of the PlayerWallet struct for account allocation
+    pub const LEN: usize = std::mem::size_of::<Self>();
+    
+    // Stable field offsets, usable in `getProgramAccounts` memcmp filters
+    pub const DISCRIMINATOR_OFFSET: usize = 0;
+    pub const VERSION_OFFSET: usize = 8;
+    pub const NAME_LEN_OFFSET: usize = 10;
+    pub const STATUS_OFFSET: usize = 11;
+    pub const WALLET_ADDRESS_OFFSET: usize = 20;
+    pub const NAME_OFFSET: usize = 52;
+    
+    pub fn new(name: &str, wallet_address: Pubkey) -> Result<Self, ProgramError> {
         // Validate name length
         if name.len() > MAX_NAME_LENGTH {
             return Err(PlayerWalletError::NameTooLong.into());
@@ -45,31 +180,167 @@ impl PlayerWallet {
             return Err(PlayerWalletError::InvalidNameFormat.into());
         }
         
-        Ok(Self {
-            is_initialized: true,
-            name,
-            wallet_address,
-            nb_tokens: 0,
-            nb_transactions: 0,
-            amount_total_tokens: 0,
-            amount_total_value_stablecoin: 0,
-            date_portfolio: 0,
-        })
+        let mut player_wallet = Self::zeroed();
+        player_wallet.discriminator = Self::DISCRIMINATOR;
+        player_wallet.version = PLAYER_WALLET_VERSION;
+        player_wallet.is_initialized = 1;
+        player_wallet.wallet_address = wallet_address;
+        player_wallet.set_name_unchecked(name);
+        Ok(player_wallet)
     }
     
-    // Get the size of the PlayerWallet struct for account allocation
-    pub fn get_account_size(name_len: usize) -> usize {
-        // Size calculation:
-        // - is_initialized: 1 byte (bool)
-        // - name: variable length string
-        // - wallet_address: 32 bytes (Pubkey)
-        // - nb_tokens: 4 bytes (u32)
-        // - nb_transactions: 4 bytes (u32)
-        // - amount_total_tokens: 4 bytes (u32)
-        // - amount_total_value_stablecoin: 4 bytes (u32)
-        // - date_portfolio: 8 bytes (u64)
+    // Player name as a string slice
+    pub fn name(&self) -> &str {
+        std::str::from_utf8(&self.name[..self.name_len as usize]).unwrap_or_default()
+    }
+    
+    // Check whether the wallet has been frozen by the program authority
+    pub fn is_frozen(&self) -> bool {
+        self.status == WalletStatus::Frozen as u8
+    }
+    
+    // Ensure `owner_info` signed, owns this wallet, and the wallet is not frozen
+    pub fn check_owner(&self, owner_info: &AccountInfo) -> ProgramResult {
+        if !owner_info.is_signer {
+            msg!("Owner must be a signer");
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        if self.is_initialized == 0 {
+            return Err(PlayerWalletError::AccountNotInitialized.into());
+        }
+        if self.wallet_address != *owner_info.key {
+            return Err(PlayerWalletError::Unauthorized.into());
+        }
+        if self.is_frozen() {
+            return Err(PlayerWalletError::WalletFrozen.into());
+        }
+        Ok(())
+    }
+    
+    // Roll the season counter over if a season closed since it started, keeping
+    // the metrics the wallet had at the close
+    // Every instruction changing the metrics calls this first, so nothing has
+    // changed since the close when it rolls over.
+    pub fn roll_over_season(&mut self, seasons_closed: u32) {
+        if self.season_index < seasons_closed {
+            self.closed_season_transactions = self.season_transactions;
+            self.closed_season_index = self.season_index + 1;
+            self.closed_season_total_tokens = self.amount_total_tokens;
+            self.closed_season_value_stablecoin = self.amount_total_value_stablecoin;
+            self.closed_season_date_portfolio = self.date_portfolio;
+            self.closed_season_nb_tokens = self.nb_tokens;
+            self.season_transactions = 0;
+            self.season_index = seasons_closed;
+        }
+    }
+    
+    // Count a transaction in the current season
+    // A season's transactions are those made after the previous season closed.
+    pub fn record_transaction(&mut self, seasons_closed: u32) -> ProgramResult {
+        self.roll_over_season(seasons_closed);
         
-        // Note: Borsh serialization adds 4 bytes for String length
-        1 + 4 + name_len + 32 + 4 + 4 + 4 + 4 + 8
+        self.nb_transactions = self.nb_transactions
+            .checked_add(1)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        self.season_transactions = self.season_transactions
+            .checked_add(1)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        Ok(())
+    }
+    
+    // Metrics the wallet had when the season with `close_index` closed
+    // Seasons closed since the last rollover have the current metrics, and
+    // those the last rollover covered have the kept ones. Older seasons are
+    // no longer known, so None.
+    pub fn season_metrics_for(&self, close_index: u32) -> Option<SeasonMetrics> {
+        if close_index > self.season_index {
+            Some(SeasonMetrics {
+                nb_tokens: self.nb_tokens,
+                nb_transactions: if close_index == self.season_index + 1 { self.season_transactions } else { 0 },
+                amount_total_tokens: self.amount_total_tokens,
+                amount_total_value_stablecoin: self.amount_total_value_stablecoin,
+                date_portfolio: self.date_portfolio,
+            })
+        } else if close_index >= self.closed_season_index {
+            Some(SeasonMetrics {
+                nb_tokens: self.closed_season_nb_tokens,
+                nb_transactions: if close_index == self.closed_season_index { self.closed_season_transactions } else { 0 },
+                amount_total_tokens: self.closed_season_total_tokens,
+                amount_total_value_stablecoin: self.closed_season_value_stablecoin,
+                date_portfolio: self.closed_season_date_portfolio,
+            })
+        } else {
+            None
+        }
+    }
+    
+    // Store a name already known to be valid, truncating it to the maximum length
+    pub(crate) fn set_name_unchecked(&mut self, name: &str) {
+        let len = name.len().min(MAX_NAME_LENGTH);
+        self.name = [0; MAX_NAME_LENGTH];
+        self.name[..len].copy_from_slice(&name.as_bytes()[..len]);
+        self.name_len = len as u8;
+    }
+    
+    // Check that data holds a player wallet in the latest layout
+    fn check_layout(data: &[u8]) -> ProgramResult {
+        if data.len() < DISCRIMINATOR_LEN + 1 || data[..DISCRIMINATOR_LEN] != Self::DISCRIMINATOR {
+            // Version 1 predates the discriminator and starts with its `is_initialized` flag
+            return match data.first() {
+                Some(1) => Err(PlayerWalletError::MigrationRequired.into()),
+                _ if data.len() >= DISCRIMINATOR_LEN && data[..DISCRIMINATOR_LEN] == [0; DISCRIMINATOR_LEN] => {
+                    Err(PlayerWalletError::AccountNotInitialized.into())
+                },
+                _ => Err(PlayerWalletError::InvalidAccountType.into()),
+            };
+        }
+        if data[DISCRIMINATOR_LEN] != PLAYER_WALLET_VERSION {
+            return Err(PlayerWalletError::MigrationRequired.into());
+        }
+        if data.len() < Self::LEN {
+            return Err(ProgramError::AccountDataTooSmall);
+        }
+        Ok(())
+    }
+    
+    // View account data as a player wallet, requiring the latest layout
+    pub fn load(data: &[u8]) -> Result<&Self, ProgramError> {
+        Self::check_layout(data)?;
+        bytemuck::try_from_bytes(&data[..Self::LEN])
+            .map_err(|_| ProgramError::InvalidAccountData)
+    }
+    
+    // Mutably view account data as a player wallet, requiring the latest layout
+    pub fn load_mut(data: &mut [u8]) -> Result<&mut Self, ProgramError> {
+        Self::check_layout(data)?;
+        bytemuck::try_from_bytes_mut(&mut data[..Self::LEN])
+            .map_err(|_| ProgramError::InvalidAccountData)
+    }
+    
+    // Write the player wallet into an account
+    pub fn save(&self, account_info: &AccountInfo) -> ProgramResult {
+        let mut data = account_info.try_borrow_mut_data()?;
+        if data.len() < Self::LEN {
+            return Err(ProgramError::AccountDataTooSmall);
+        }
+        data[..Self::LEN].copy_from_slice(bytemuck::bytes_of(self));
+        Ok(())
+    }
+    
+    // Decode account data written with any historical layout
+    // The latest layout starts with the type discriminator followed by the
+    // version byte. Version 1 predates both, and its leading `is_initialized`
+    // flag is always 1 once written.
+    pub fn unpack_any_version(data: &[u8]) -> Result<Self, ProgramError> {
+        if data.starts_with(&Self::DISCRIMINATOR) {
+            return Self::load(data).copied();
+        }
+        
+        match data.first() {
+            Some(1) => PlayerWalletV1::deserialize(&mut &data[..])
+                .map(Self::from)
+                .map_err(|_| PlayerWalletError::AccountNotInitialized.into()),
+            _ => Self::load(data).copied(),
+        }
     }
 }
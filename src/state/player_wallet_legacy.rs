@@ -0,0 +1,36 @@
+// Historical PlayerWallet layout, kept so accounts created before versioning
+// can still be read and migrated to the latest layout
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::pubkey::Pubkey;
+use crate::constants::PLAYER_WALLET_VERSION;
+use crate::state::{AccountType, PlayerWallet};
+
+// Version 1: the original layout, without a version field
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct PlayerWalletV1 {
+    pub is_initialized: bool,
+    pub name: String,
+    pub wallet_address: Pubkey,
+    pub nb_tokens: u32,
+    pub nb_transactions: u32,
+    pub amount_total_tokens: u32,
+    pub amount_total_value_stablecoin: u32,
+    pub date_portfolio: u64,
+}
+
+impl From<PlayerWalletV1> for PlayerWallet {
+    fn from(wallet: PlayerWalletV1) -> Self {
+        let mut player_wallet = <Self as bytemuck::Zeroable>::zeroed();
+        player_wallet.discriminator = Self::DISCRIMINATOR;
+        player_wallet.version = PLAYER_WALLET_VERSION;
+        player_wallet.is_initialized = wallet.is_initialized as u8;
+        player_wallet.wallet_address = wallet.wallet_address;
+        player_wallet.set_name_unchecked(&wallet.name);
+        player_wallet.nb_tokens = wallet.nb_tokens;
+        player_wallet.nb_transactions = wallet.nb_transactions;
+        player_wallet.amount_total_tokens = wallet.amount_total_tokens.into();
+        player_wallet.amount_total_value_stablecoin = wallet.amount_total_value_stablecoin.into();
+        player_wallet.date_portfolio = wallet.date_portfolio;
+        player_wallet
+    }
+}
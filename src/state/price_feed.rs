@@ -0,0 +1,105 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    msg,
+};
+use crate::error::PlayerWalletError;
+use crate::constants::{
+    MAX_PRICE_AGE_SECONDS, MAX_PRICE_CONFIDENCE_BPS, PRICE_FEED_SEED, STABLECOIN_DECIMALS,
+};
+use crate::state::{AccountType, DISCRIMINATOR_LEN};
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct PriceFeed {
+    // Flag to check if the account is initialized
+    pub is_initialized: bool,
+    
+    // Token mint priced by this feed
+    pub mint: Pubkey,
+    
+    // Key allowed to publish prices
+    pub publisher: Pubkey,
+    
+    // Price of one whole token in stablecoin, as `price * 10^expo`
+    pub price: i64,
+    
+    // Decimal exponent of `price` and `conf`
+    pub expo: i32,
+    
+    // Confidence interval around `price`, same scale as `price`
+    pub conf: u64,
+    
+    // Unix timestamp of the last published price
+    pub publish_time: i64,
+    
+    // Bump seed of the price feed PDA
+    pub bump: u8,
+}
+
+impl AccountType for PriceFeed {
+    const DISCRIMINATOR: [u8; DISCRIMINATOR_LEN] = [189, 103, 252, 23, 152, 35, 243, 156];
+}
+
+impl PriceFeed {
+    // Size of the PriceFeed struct for account allocation
+    // - discriminator: 8 bytes
+    // - is_initialized: 1 byte (bool)
+    // - mint: 32 bytes (Pubkey)
+    // - publisher: 32 bytes (Pubkey)
+    // - price: 8 bytes (i64)
+    // - expo: 4 bytes (i32)
+    // - conf: 8 bytes (u64)
+    // - publish_time: 8 bytes (i64)
+    // - bump: 1 byte (u8)
+    pub const LEN: usize = DISCRIMINATOR_LEN + 1 + 32 + 32 + 8 + 4 + 8 + 8 + 1;
+    
+    // Derive the address of a mint's price feed PDA
+    pub fn find_address(mint: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[PRICE_FEED_SEED, mint.as_ref()], program_id)
+    }
+    
+    // Get the price if it is fresh and precise enough to value holdings
+    pub fn get_checked_price(&self, now: i64) -> Result<u64, ProgramError> {
+        if self.price <= 0 {
+            msg!("Price feed for {} has no valid price", self.mint);
+            return Err(PlayerWalletError::InvalidPriceFeed.into());
+        }
+        if now.saturating_sub(self.publish_time) > MAX_PRICE_AGE_SECONDS {
+            msg!("Price feed for {} is stale", self.mint);
+            return Err(PlayerWalletError::StalePrice.into());
+        }
+        
+        let price = self.price as u64;
+        if (self.conf as u128) * 10_000 > (price as u128) * (MAX_PRICE_CONFIDENCE_BPS as u128) {
+            msg!("Price feed for {} is not confident enough", self.mint);
+            return Err(PlayerWalletError::PriceConfidenceTooWide.into());
+        }
+        
+        Ok(price)
+    }
+    
+    // Value `amount` base units of a token with `decimals` in stablecoin base units
+    pub fn value_of(&self, price: u64, amount: u64, decimals: u8) -> Result<u64, ProgramError> {
+        let exponent = STABLECOIN_DECIMALS as i32 + self.expo - decimals as i32;
+        scale(amount as u128 * price as u128, exponent)
+    }
+    
+    // Price of one whole token in stablecoin base units
+    pub fn unit_price(&self, price: u64) -> Result<u64, ProgramError> {
+        scale(price as u128, STABLECOIN_DECIMALS as i32 + self.expo)
+    }
+}
+
+// Multiply `value` by `10^exponent`, rounding down
+fn scale(value: u128, exponent: i32) -> Result<u64, ProgramError> {
+    let factor = 10u128
+        .checked_pow(exponent.unsigned_abs())
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    let scaled = if exponent >= 0 {
+        value.checked_mul(factor).ok_or(ProgramError::ArithmeticOverflow)?
+    } else {
+        value / factor
+    };
+    u64::try_from(scaled).map_err(|_| ProgramError::ArithmeticOverflow)
+}
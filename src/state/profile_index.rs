@@ -0,0 +1,53 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::pubkey::Pubkey;
+use crate::constants::{PLAYER_WALLET_SEED, PROFILE_INDEX_SEED};
+use crate::state::{AccountType, DISCRIMINATOR_LEN};
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct ProfileIndex {
+    // Flag to check if the account is initialized
+    pub is_initialized: bool,
+    
+    // Owner whose profiles are counted
+    pub owner: Pubkey,
+    
+    // Number of profiles created, also the index of the next profile
+    pub profile_count: u32,
+    
+    // Bump seed of the profile index PDA
+    pub bump: u8,
+}
+
+impl AccountType for ProfileIndex {
+    const DISCRIMINATOR: [u8; DISCRIMINATOR_LEN] = [245, 48, 171, 227, 244, 70, 121, 2];
+}
+
+impl ProfileIndex {
+    // Size of the ProfileIndex struct for account allocation
+    // - discriminator: 8 bytes
+    // - is_initialized: 1 byte (bool)
+    // - owner: 32 bytes (Pubkey)
+    // - profile_count: 4 bytes (u32)
+    // - bump: 1 byte (u8)
+    pub const LEN: usize = DISCRIMINATOR_LEN + 1 + 32 + 4 + 1;
+    
+    // Derive the address of an owner's profile index PDA
+    pub fn find_address(owner: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[PROFILE_INDEX_SEED, owner.as_ref()], program_id)
+    }
+    
+    // List the addresses of every player wallet created by this owner
+    pub fn player_wallet_addresses(&self, program_id: &Pubkey) -> Vec<Pubkey> {
+        (0..self.profile_count)
+            .map(|index| find_player_wallet_address(&self.owner, index, program_id).0)
+            .collect()
+    }
+}
+
+// Derive the address of an owner's player wallet PDA for a profile index
+pub fn find_player_wallet_address(owner: &Pubkey, index: u32, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[PLAYER_WALLET_SEED, owner.as_ref(), &index.to_le_bytes()],
+        program_id,
+    )
+}
@@ -0,0 +1,73 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::AccountInfo,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    msg,
+};
+use crate::error::PlayerWalletError;
+use crate::constants::CONFIG_SEED;
+use crate::state::{load_account, AccountType, DISCRIMINATOR_LEN};
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct ProgramConfig {
+    // Flag to check if the account is initialized
+    pub is_initialized: bool,
+    
+    // Authority allowed to manage program-wide settings
+    pub authority: Pubkey,
+    
+    // Bump seed of the config PDA
+    pub bump: u8,
+    
+    // Fee in lamports charged to the owner on wallet creation
+    pub creation_fee_lamports: u64,
+}
+
+impl AccountType for ProgramConfig {
+    const DISCRIMINATOR: [u8; DISCRIMINATOR_LEN] = [196, 210, 90, 231, 144, 149, 140, 63];
+}
+
+impl ProgramConfig {
+    // Size of the ProgramConfig struct for account allocation
+    // - discriminator: 8 bytes
+    // - is_initialized: 1 byte (bool)
+    // - authority: 32 bytes (Pubkey)
+    // - bump: 1 byte (u8)
+    // - creation_fee_lamports: 8 bytes (u64)
+    pub const LEN: usize = DISCRIMINATOR_LEN + 1 + 32 + 1 + 8;
+    
+    // Derive the address of the program config PDA
+    pub fn find_address(program_id: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[CONFIG_SEED], program_id)
+    }
+    
+    // Load the program config, ensuring it is the config PDA
+    pub fn load(program_id: &Pubkey, config_info: &AccountInfo) -> Result<Self, ProgramError> {
+        if *config_info.key != Self::find_address(program_id).0 {
+            msg!("Invalid program config account");
+            return Err(ProgramError::InvalidAccountData);
+        }
+        
+        load_account(program_id, config_info)
+    }
+    
+    // Load the program config and ensure `authority_info` is its signing authority
+    pub fn load_with_authority(
+        program_id: &Pubkey,
+        config_info: &AccountInfo,
+        authority_info: &AccountInfo,
+    ) -> Result<Self, ProgramError> {
+        let config = Self::load(program_id, config_info)?;
+        
+        if !authority_info.is_signer {
+            msg!("Authority must be a signer");
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        if config.authority != *authority_info.key {
+            return Err(PlayerWalletError::Unauthorized.into());
+        }
+        
+        Ok(config)
+    }
+}
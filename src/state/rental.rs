@@ -0,0 +1,85 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::AccountInfo,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    msg,
+};
+use crate::constants::RENTAL_SEED;
+use crate::state::{load_account, AccountType, DISCRIMINATOR_LEN};
+
+/// An item escrowed by its owner to be rented out for a fixed duration
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct Rental {
+    // Flag to check if the account is initialized
+    pub is_initialized: bool,
+    
+    // Player wallet lending the item
+    pub lender: Pubkey,
+    
+    // Item mint, held by the rental's associated token account
+    pub mint: Pubkey,
+    
+    // Upfront price of one rental period, in game currency base units
+    pub price: u64,
+    
+    // Length of a rental period in seconds
+    pub duration_seconds: i64,
+    
+    // Player wallet currently renting the item, default if not rented
+    pub renter: Pubkey,
+    
+    // Unix timestamp the current rental ends, 0 if not rented
+    pub expires_at: i64,
+    
+    // Slot the item had in the lender's inventory, restored on reclaim
+    // None if the lender had not registered the item
+    pub lender_slot: Option<u8>,
+    
+    // Bump seed of the rental PDA
+    pub bump: u8,
+}
+
+impl AccountType for Rental {
+    const DISCRIMINATOR: [u8; DISCRIMINATOR_LEN] = [121, 83, 229, 235, 73, 50, 143, 184];
+}
+
+impl Rental {
+    // Size of the Rental struct for account allocation
+    // - discriminator: 8 bytes
+    // - is_initialized: 1 byte (bool)
+    // - lender: 32 bytes (Pubkey)
+    // - mint: 32 bytes (Pubkey)
+    // - price: 8 bytes (u64)
+    // - duration_seconds: 8 bytes (i64)
+    // - renter: 32 bytes (Pubkey)
+    // - expires_at: 8 bytes (i64)
+    // - lender_slot: 2 bytes (Option<u8>)
+    // - bump: 1 byte (u8)
+    pub const LEN: usize = DISCRIMINATOR_LEN + 1 + 32 + 32 + 8 + 8 + 32 + 8 + 2 + 1;
+    
+    // Derive the address of an item's rental PDA
+    pub fn find_address(mint: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[RENTAL_SEED, mint.as_ref()], program_id)
+    }
+    
+    // Load a rental, ensuring it is the rental PDA of `mint`
+    pub fn load(program_id: &Pubkey, mint: &Pubkey, rental_info: &AccountInfo) -> Result<Self, ProgramError> {
+        if *rental_info.key != Self::find_address(mint, program_id).0 {
+            msg!("Invalid rental account");
+            return Err(ProgramError::InvalidArgument);
+        }
+        
+        load_account(program_id, rental_info)
+    }
+    
+    // Check whether someone is renting the item
+    pub fn is_rented(&self) -> bool {
+        self.renter != Pubkey::default()
+    }
+    
+    // Check whether the current rental has ended at `now`
+    pub fn is_expired(&self, now: i64) -> bool {
+        self.is_rented() && now >= self.expires_at
+    }
+}
@@ -0,0 +1,205 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::AccountInfo,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    msg,
+};
+use crate::error::PlayerWalletError;
+use crate::constants::{SEASON_CLOCK_SEED, SEASON_SEED, SEASON_STATS_SEED};
+use crate::state::{load_account, AccountType, PlayerWallet, DISCRIMINATOR_LEN};
+
+/// A game season, opened and closed by the program authority
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct Season {
+    // Flag to check if the account is initialized
+    pub is_initialized: bool,
+    
+    // Season number, starting at 1
+    pub id: u32,
+    
+    // Unix timestamp the season starts
+    pub start_time: i64,
+    
+    // Unix timestamp the season ends, moved earlier if it is closed early
+    pub end_time: i64,
+    
+    // Whether the season is closed and player stats can be snapshotted
+    pub is_closed: bool,
+    
+    // Number of seasons closed up to and including this one, 0 while open
+    pub close_index: u32,
+    
+    // Bump seed of the season PDA
+    pub bump: u8,
+}
+
+impl AccountType for Season {
+    const DISCRIMINATOR: [u8; DISCRIMINATOR_LEN] = [76, 67, 93, 156, 180, 157, 248, 47];
+}
+
+impl Season {
+    // Size of the Season struct for account allocation
+    // - discriminator: 8 bytes
+    // - is_initialized: 1 byte (bool)
+    // - id: 4 bytes (u32)
+    // - start_time: 8 bytes (i64)
+    // - end_time: 8 bytes (i64)
+    // - is_closed: 1 byte (bool)
+    // - close_index: 4 bytes (u32)
+    // - bump: 1 byte (u8)
+    pub const LEN: usize = DISCRIMINATOR_LEN + 1 + 4 + 8 + 8 + 1 + 4 + 1;
+    
+    // Derive the address of a season PDA
+    pub fn find_address(id: u32, program_id: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[SEASON_SEED, &id.to_le_bytes()], program_id)
+    }
+    
+    // Load a season, ensuring it is the season PDA of `id`
+    pub fn load(program_id: &Pubkey, id: u32, season_info: &AccountInfo) -> Result<Self, ProgramError> {
+        if *season_info.key != Self::find_address(id, program_id).0 {
+            msg!("Invalid season account");
+            return Err(ProgramError::InvalidArgument);
+        }
+        
+        load_account(program_id, season_info)
+    }
+}
+
+/// Number of seasons closed so far, telling player wallets when to roll their
+/// season counters over
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct SeasonClock {
+    // Flag to check if the account is initialized
+    pub is_initialized: bool,
+    
+    // Number of seasons closed
+    pub seasons_closed: u32,
+    
+    // Bump seed of the season clock PDA
+    pub bump: u8,
+}
+
+impl AccountType for SeasonClock {
+    const DISCRIMINATOR: [u8; DISCRIMINATOR_LEN] = [238, 40, 51, 101, 188, 200, 64, 118];
+}
+
+impl SeasonClock {
+    // Size of the SeasonClock struct for account allocation
+    // - discriminator: 8 bytes
+    // - is_initialized: 1 byte (bool)
+    // - seasons_closed: 4 bytes (u32)
+    // - bump: 1 byte (u8)
+    pub const LEN: usize = DISCRIMINATOR_LEN + 1 + 4 + 1;
+    
+    // Derive the address of the season clock PDA
+    pub fn find_address(program_id: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[SEASON_CLOCK_SEED], program_id)
+    }
+    
+    // Load the season clock, ensuring it is the season clock PDA
+    pub fn load(program_id: &Pubkey, season_clock_info: &AccountInfo) -> Result<Self, ProgramError> {
+        if *season_clock_info.key != Self::find_address(program_id).0 {
+            msg!("Invalid season clock account");
+            return Err(ProgramError::InvalidArgument);
+        }
+        
+        load_account(program_id, season_clock_info)
+    }
+    
+    // Number of seasons closed, 0 until the season clock exists
+    pub fn seasons_closed(program_id: &Pubkey, season_clock_info: &AccountInfo) -> Result<u32, ProgramError> {
+        if season_clock_info.owner != program_id && *season_clock_info.key == Self::find_address(program_id).0 {
+            return Ok(0);
+        }
+        Self::load(program_id, season_clock_info).map(|season_clock| season_clock.seasons_closed)
+    }
+}
+
+/// A player's wallet metrics at the close of a season
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct SeasonStats {
+    // Flag to check if the account is initialized
+    pub is_initialized: bool,
+    
+    // Season the stats belong to
+    pub season_id: u32,
+    
+    // Player wallet the stats belong to
+    pub player_wallet: Pubkey,
+    
+    // Number of tokens owned
+    pub nb_tokens: u32,
+    
+    // Number of transactions made during the season
+    pub nb_transactions: u32,
+    
+    // Total amount of tokens
+    pub amount_total_tokens: u64,
+    
+    // Total value in stablecoin
+    pub amount_total_value_stablecoin: u64,
+    
+    // Portfolio date (timestamp)
+    pub date_portfolio: u64,
+    
+    // Unix timestamp the snapshot was taken
+    pub snapshot_at: i64,
+    
+    // Bump seed of the season stats PDA
+    pub bump: u8,
+}
+
+impl AccountType for SeasonStats {
+    const DISCRIMINATOR: [u8; DISCRIMINATOR_LEN] = [23, 137, 84, 131, 148, 235, 209, 2];
+}
+
+impl SeasonStats {
+    // Size of the SeasonStats struct for account allocation
+    // - discriminator: 8 bytes
+    // - is_initialized: 1 byte (bool)
+    // - season_id: 4 bytes (u32)
+    // - player_wallet: 32 bytes (Pubkey)
+    // - nb_tokens: 4 bytes (u32)
+    // - nb_transactions: 4 bytes (u32)
+    // - amount_total_tokens: 8 bytes (u64)
+    // - amount_total_value_stablecoin: 8 bytes (u64)
+    // - date_portfolio: 8 bytes (u64)
+    // - snapshot_at: 8 bytes (i64)
+    // - bump: 1 byte (u8)
+    pub const LEN: usize = DISCRIMINATOR_LEN + 1 + 4 + 32 + 4 + 4 + 8 + 8 + 8 + 8 + 1;
+    
+    // Derive the address of a player wallet's stats PDA for a season
+    pub fn find_address(season_id: u32, player_wallet: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[SEASON_STATS_SEED, &season_id.to_le_bytes(), player_wallet.as_ref()],
+            program_id,
+        )
+    }
+    
+    // Snapshot a player wallet's metrics as they were when a season closed
+    pub fn snapshot(
+        season: &Season,
+        player_wallet_address: &Pubkey,
+        player_wallet: &PlayerWallet,
+        now: i64,
+        bump: u8,
+    ) -> Result<Self, ProgramError> {
+        let metrics = player_wallet.season_metrics_for(season.close_index).ok_or_else(|| {
+            msg!("Season {} metrics were replaced by a later season", season.id);
+            PlayerWalletError::SeasonStatsUnavailable
+        })?;
+        Ok(Self {
+            is_initialized: true,
+            season_id: season.id,
+            player_wallet: *player_wallet_address,
+            nb_tokens: metrics.nb_tokens,
+            nb_transactions: metrics.nb_transactions,
+            amount_total_tokens: metrics.amount_total_tokens,
+            amount_total_value_stablecoin: metrics.amount_total_value_stablecoin,
+            date_portfolio: metrics.date_portfolio,
+            snapshot_at: now,
+            bump,
+        })
+    }
+}
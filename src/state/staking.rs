@@ -0,0 +1,141 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::AccountInfo,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    msg,
+};
+use crate::constants::{REWARD_RATE_SCALE, STAKE_POSITION_SEED, STAKING_CONFIG_SEED};
+use crate::state::{load_account, AccountType, DISCRIMINATOR_LEN};
+
+/// Staking settings, shared by every stake position
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct StakingConfig {
+    // Flag to check if the account is initialized
+    pub is_initialized: bool,
+    
+    // Rewards per second for each `REWARD_RATE_SCALE` staked base units
+    pub reward_rate: u64,
+    
+    // Seconds staked tokens stay locked after the last stake
+    pub lock_period_seconds: i64,
+    
+    // Bump seed of the staking config PDA
+    pub bump: u8,
+}
+
+impl AccountType for StakingConfig {
+    const DISCRIMINATOR: [u8; DISCRIMINATOR_LEN] = [45, 134, 252, 82, 37, 57, 84, 25];
+}
+
+impl StakingConfig {
+    // Size of the StakingConfig struct for account allocation
+    // - discriminator: 8 bytes
+    // - is_initialized: 1 byte (bool)
+    // - reward_rate: 8 bytes (u64)
+    // - lock_period_seconds: 8 bytes (i64)
+    // - bump: 1 byte (u8)
+    pub const LEN: usize = DISCRIMINATOR_LEN + 1 + 8 + 8 + 1;
+    
+    // Derive the address of the staking config PDA
+    pub fn find_address(program_id: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[STAKING_CONFIG_SEED], program_id)
+    }
+    
+    // Load the staking config, ensuring it is the staking config PDA
+    pub fn load(program_id: &Pubkey, staking_config_info: &AccountInfo) -> Result<Self, ProgramError> {
+        if *staking_config_info.key != Self::find_address(program_id).0 {
+            msg!("Invalid staking config account");
+            return Err(ProgramError::InvalidAccountData);
+        }
+        
+        load_account(program_id, staking_config_info)
+    }
+}
+
+/// Game currency a player has locked in their vault to earn rewards
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct StakePosition {
+    // Flag to check if the account is initialized
+    pub is_initialized: bool,
+    
+    // Player wallet the position belongs to
+    pub player_wallet: Pubkey,
+    
+    // Mint of the staked tokens
+    pub mint: Pubkey,
+    
+    // Amount staked, in base units, held in the player's vault
+    pub amount: u64,
+    
+    // Rewards accrued but not claimed yet, in base units
+    pub pending_rewards: u64,
+    
+    // Unix timestamp rewards have been accrued up to
+    pub last_update_time: i64,
+    
+    // Unix timestamp before which the position cannot be claimed or unstaked
+    pub locked_until: i64,
+    
+    // Bump seed of the stake position PDA
+    pub bump: u8,
+}
+
+impl AccountType for StakePosition {
+    const DISCRIMINATOR: [u8; DISCRIMINATOR_LEN] = [78, 165, 30, 111, 171, 125, 11, 220];
+}
+
+impl StakePosition {
+    // Size of the StakePosition struct for account allocation
+    // - discriminator: 8 bytes
+    // - is_initialized: 1 byte (bool)
+    // - player_wallet: 32 bytes (Pubkey)
+    // - mint: 32 bytes (Pubkey)
+    // - amount: 8 bytes (u64)
+    // - pending_rewards: 8 bytes (u64)
+    // - last_update_time: 8 bytes (i64)
+    // - locked_until: 8 bytes (i64)
+    // - bump: 1 byte (u8)
+    pub const LEN: usize = DISCRIMINATOR_LEN + 1 + 32 + 32 + 8 + 8 + 8 + 8 + 1;
+    
+    // Derive the address of a player wallet's stake position PDA
+    pub fn find_address(player_wallet: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[STAKE_POSITION_SEED, player_wallet.as_ref()], program_id)
+    }
+    
+    // Load a player wallet's stake position, ensuring it is the wallet's PDA
+    pub fn load(
+        program_id: &Pubkey,
+        player_wallet: &Pubkey,
+        stake_position_info: &AccountInfo,
+    ) -> Result<Self, ProgramError> {
+        if *stake_position_info.key != Self::find_address(player_wallet, program_id).0 {
+            msg!("Invalid stake position account");
+            return Err(ProgramError::InvalidAccountData);
+        }
+        
+        load_account(program_id, stake_position_info)
+    }
+    
+    // Accrue rewards on the staked amount up to `now` at `reward_rate`
+    // Rate changes apply to any time not accrued yet.
+    pub fn accrue(&mut self, now: i64, reward_rate: u64) -> Result<(), ProgramError> {
+        let elapsed = now.saturating_sub(self.last_update_time).max(0) as u128;
+        let rewards = elapsed
+            .checked_mul(reward_rate as u128)
+            .and_then(|value| value.checked_mul(self.amount as u128))
+            .map(|value| value / REWARD_RATE_SCALE as u128)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        self.pending_rewards = u64::try_from(rewards)
+            .ok()
+            .and_then(|rewards| self.pending_rewards.checked_add(rewards))
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        self.last_update_time = self.last_update_time.max(now);
+        Ok(())
+    }
+    
+    // Check whether the lock period is over at `now`
+    pub fn is_unlocked(&self, now: i64) -> bool {
+        now >= self.locked_until
+    }
+}
@@ -0,0 +1,49 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::AccountInfo,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    msg,
+};
+use crate::constants::TREASURY_SEED;
+use crate::state::{load_account, AccountType, DISCRIMINATOR_LEN};
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct Treasury {
+    // Flag to check if the account is initialized
+    pub is_initialized: bool,
+    
+    // Bump seed of the treasury PDA
+    pub bump: u8,
+    
+    // Total lamports collected in fees since initialization
+    pub total_collected: u64,
+}
+
+impl AccountType for Treasury {
+    const DISCRIMINATOR: [u8; DISCRIMINATOR_LEN] = [238, 239, 123, 238, 89, 1, 168, 253];
+}
+
+impl Treasury {
+    // Size of the Treasury struct for account allocation
+    // - discriminator: 8 bytes
+    // - is_initialized: 1 byte (bool)
+    // - bump: 1 byte (u8)
+    // - total_collected: 8 bytes (u64)
+    pub const LEN: usize = DISCRIMINATOR_LEN + 1 + 1 + 8;
+    
+    // Derive the address of the treasury PDA
+    pub fn find_address(program_id: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[TREASURY_SEED], program_id)
+    }
+    
+    // Load the treasury, ensuring it is the treasury PDA
+    pub fn load(program_id: &Pubkey, treasury_info: &AccountInfo) -> Result<Self, ProgramError> {
+        if *treasury_info.key != Self::find_address(program_id).0 {
+            msg!("Invalid treasury account");
+            return Err(ProgramError::InvalidAccountData);
+        }
+        
+        load_account(program_id, treasury_info)
+    }
+}
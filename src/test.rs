@@ -3,30 +3,47 @@
 mod tests {
     use solana_program::{
         account_info::{AccountInfo, next_account_info},
+        bpf_loader_upgradeable::{self, UpgradeableLoaderState},
         entrypoint::ProgramResult,
+        instruction::InstructionError,
         program_error::ProgramError,
         pubkey::Pubkey,
+        program_pack::Pack,
         rent::Rent,
         system_instruction,
         sysvar::Sysvar,
     };
     use solana_program_test::*;
     use solana_sdk::{
-        account::Account,
+        account::{Account, AccountSharedData},
         signature::{Keypair, Signer},
-        transaction::Transaction,
+        transaction::{Transaction, TransactionError},
     };
-    use borsh::{BorshDeserialize, BorshSerialize};
+    use borsh::BorshSerialize;
     use std::mem::size_of;
     
     use crate::{
+        events::{AchievementUnlocked, Event},
+        filters::{self, AccountFilter},
+        merkle::{self, ClaimLeaf, MerkleTree},
         instructions::{
             process_initialize,
             process_create_player_wallet,
             process_update_player_wallet,
         },
-        state::PlayerWallet,
-        constants::MAX_NAME_LENGTH,
+        state::{
+            account_discriminator, find_player_wallet_address, find_vault_authority_address,
+            pack_account, unpack_account, AccountType, Achievement, Auction, AuctionKind, AuctionTerms,
+            BlockedNameRule, CurrencyConfig, Distributor, Holding, Holdings, Inventory, InventoryItem,
+            ItemRegistration, Leaderboard, LeaderboardEntry, Listing, MarketplaceConfig, NameBlocklist,
+            PlayerAchievements, PlayerWallet, PlayerWalletV1, PriceFeed, ProfileIndex, ProgramConfig,
+            Rental, Season, SeasonClock, SeasonStats, StakePosition, StakingConfig, Treasury,
+            WalletMetric, WalletStatus,
+        },
+        constants::{
+            INVENTORY_CAPACITY_INCREMENT, MAX_HOLDINGS, MAX_NAME_LENGTH, MAX_PRICE_AGE_SECONDS,
+            PLAYER_WALLET_VERSION,
+        },
     };
 
     // Helper function to create a program test environment
@@ -38,103 +55,471 @@ mod tests {
         )
     }
 
-    #[tokio::test]
-    async fn test_initialize_program() {
-        let (mut banks_client, payer, recent_blockhash) = program_test().start().await;
+    // Helper function to add an existing player wallet to the test environment
+    fn add_player_wallet(program_test: &mut ProgramTest, player_wallet: &PlayerWallet) -> Pubkey {
+        let address = Pubkey::new_unique();
+        program_test.add_account(
+            address,
+            Account {
+                lamports: Rent::default().minimum_balance(PlayerWallet::LEN),
+                data: bytemuck::bytes_of(player_wallet).to_vec(),
+                owner: crate::id(),
+                ..Account::default()
+            },
+        );
+        address
+    }
+    
+    // Helper function to add an existing Borsh state account to the test environment
+    fn add_state_account<T: AccountType + BorshSerialize>(
+        program_test: &mut ProgramTest,
+        address: Pubkey,
+        state: &T,
+    ) {
+        let data = pack_account(state).unwrap();
+        program_test.add_account(
+            address,
+            Account {
+                lamports: Rent::default().minimum_balance(data.len()),
+                data,
+                owner: crate::id(),
+                ..Account::default()
+            },
+        );
+    }
+    
+    // Helper function to compute the discriminator of an event type from its name
+    fn event_discriminator(type_name: &str) -> [u8; 8] {
+        let mut discriminator = [0u8; 8];
+        discriminator.copy_from_slice(
+            &solana_program::hash::hash(format!("event:{}", type_name).as_bytes()).to_bytes()[..8],
+        );
+        discriminator
+    }
+    
+    // Helper function to add an SPL token account to the test environment
+    fn add_token_account(
+        program_test: &mut ProgramTest,
+        address: Pubkey,
+        mint: Pubkey,
+        owner: Pubkey,
+        amount: u64,
+    ) {
+        let mut data = vec![0; spl_token::state::Account::LEN];
+        spl_token::state::Account::pack(
+            spl_token::state::Account {
+                mint,
+                owner,
+                amount,
+                state: spl_token::state::AccountState::Initialized,
+                ..spl_token::state::Account::default()
+            },
+            &mut data,
+        )
+        .unwrap();
+        program_test.add_account(
+            address,
+            Account {
+                lamports: Rent::default().minimum_balance(data.len()),
+                data,
+                owner: spl_token::id(),
+                ..Account::default()
+            },
+        );
+    }
+    
+    // Helper function to add an SPL token mint to the test environment
+    fn add_mint(
+        program_test: &mut ProgramTest,
+        address: Pubkey,
+        mint_authority: Option<Pubkey>,
+        decimals: u8,
+        supply: u64,
+    ) {
+        let mut data = vec![0; spl_token::state::Mint::LEN];
+        spl_token::state::Mint::pack(
+            spl_token::state::Mint {
+                mint_authority: mint_authority.into(),
+                supply,
+                decimals,
+                is_initialized: true,
+                freeze_authority: None.into(),
+            },
+            &mut data,
+        )
+        .unwrap();
+        program_test.add_account(
+            address,
+            Account {
+                lamports: Rent::default().minimum_balance(data.len()),
+                data,
+                owner: spl_token::id(),
+                ..Account::default()
+            },
+        );
+    }
+    
+    // Helper function to add the game currency, with no decimals, to the test environment
+    fn add_currency(program_test: &mut ProgramTest) -> (Pubkey, Pubkey) {
+        let currency_mint = CurrencyConfig::find_mint_address(&crate::id()).0;
+        let (currency_config_address, currency_config_bump) = CurrencyConfig::find_address(&crate::id());
+        add_state_account(
+            program_test,
+            currency_config_address,
+            &CurrencyConfig {
+                is_initialized: true,
+                mint: currency_mint,
+                updater: Pubkey::new_unique(),
+                epoch_issuance_cap: 0,
+                current_epoch: 0,
+                issued_this_epoch: 0,
+                bump: currency_config_bump,
+            },
+        );
+        add_mint(program_test, currency_mint, Some(currency_config_address), 0, 10_000);
+        (currency_config_address, currency_mint)
+    }
+    
+    // Helper function to add the marketplace config and its fee token account to the test environment
+    fn add_marketplace_config(program_test: &mut ProgramTest, fee_bps: u16) -> (Pubkey, Pubkey) {
+        let currency_mint = CurrencyConfig::find_mint_address(&crate::id()).0;
+        let fee_recipient = Pubkey::new_unique();
+        let fee_account = spl_associated_token_account::get_associated_token_address(&fee_recipient, &currency_mint);
+        add_token_account(program_test, fee_account, currency_mint, fee_recipient, 0);
+        let (marketplace_config_address, marketplace_config_bump) = MarketplaceConfig::find_address(&crate::id());
+        add_state_account(
+            program_test,
+            marketplace_config_address,
+            &MarketplaceConfig {
+                is_initialized: true,
+                fee_bps,
+                fee_recipient,
+                bump: marketplace_config_bump,
+            },
+        );
+        (marketplace_config_address, fee_account)
+    }
+    
+    // Helper function to build Token-2022 mint data, optionally with a transfer fee
+    // or non-transferable
+    fn token_2022_mint_data(supply: u64, transfer_fee_bps: Option<u16>, non_transferable: bool) -> Vec<u8> {
+        use spl_token_2022::extension::{
+            non_transferable::NonTransferable,
+            transfer_fee::{TransferFee, TransferFeeConfig},
+            ExtensionType, StateWithExtensionsMut,
+        };
+        
+        let mut extensions = Vec::new();
+        if transfer_fee_bps.is_some() {
+            extensions.push(ExtensionType::TransferFeeConfig);
+        }
+        if non_transferable {
+            extensions.push(ExtensionType::NonTransferable);
+        }
+        let space = ExtensionType::try_calculate_account_len::<spl_token_2022::state::Mint>(&extensions).unwrap();
+        let mut data = vec![0; space];
+        let mut state = StateWithExtensionsMut::<spl_token_2022::state::Mint>::unpack_uninitialized(&mut data).unwrap();
+        if let Some(transfer_fee_bps) = transfer_fee_bps {
+            let transfer_fee = TransferFee {
+                epoch: 0.into(),
+                maximum_fee: u64::MAX.into(),
+                transfer_fee_basis_points: transfer_fee_bps.into(),
+            };
+            let config = state.init_extension::<TransferFeeConfig>(true).unwrap();
+            config.older_transfer_fee = transfer_fee;
+            config.newer_transfer_fee = transfer_fee;
+        }
+        if non_transferable {
+            state.init_extension::<NonTransferable>(true).unwrap();
+        }
+        state.base = spl_token_2022::state::Mint {
+            mint_authority: Some(Pubkey::new_unique()).into(),
+            supply,
+            decimals: 0,
+            is_initialized: true,
+            freeze_authority: None.into(),
+        };
+        state.pack_base();
+        state.init_account_type().unwrap();
+        data
+    }
+    
+    // Helper function to add a Token-2022 account able to receive fee-bearing transfers
+    fn add_token_2022_account(
+        program_test: &mut ProgramTest,
+        address: Pubkey,
+        mint: Pubkey,
+        owner: Pubkey,
+        amount: u64,
+    ) {
+        use spl_token_2022::extension::{
+            transfer_fee::TransferFeeAmount, ExtensionType, StateWithExtensionsMut,
+        };
         
-        // Create program authority
-        let program_authority = Keypair::new();
+        let space = ExtensionType::try_calculate_account_len::<spl_token_2022::state::Account>(
+            &[ExtensionType::TransferFeeAmount],
+        )
+        .unwrap();
+        let mut data = vec![0; space];
+        let mut state = StateWithExtensionsMut::<spl_token_2022::state::Account>::unpack_uninitialized(&mut data).unwrap();
+        state.init_extension::<TransferFeeAmount>(true).unwrap();
+        state.base = spl_token_2022::state::Account {
+            mint,
+            owner,
+            amount,
+            state: spl_token_2022::state::AccountState::Initialized,
+            ..spl_token_2022::state::Account::default()
+        };
+        state.pack_base();
+        state.init_account_type().unwrap();
+        program_test.add_account(
+            address,
+            Account {
+                lamports: Rent::default().minimum_balance(data.len()),
+                data,
+                owner: spl_token_2022::id(),
+                ..Account::default()
+            },
+        );
+    }
+    
+    // Helper function to store this program's data account with the given upgrade authority
+    fn set_upgrade_authority(context: &mut ProgramTestContext, upgrade_authority: Option<Pubkey>) {
+        // ProgramData state: variant 3, deployment slot, then the optional upgrade authority
+        let mut data = vec![3, 0, 0, 0];
+        data.extend_from_slice(&0u64.to_le_bytes());
+        data.extend(upgrade_authority.try_to_vec().unwrap());
+        data.resize(UpgradeableLoaderState::size_of_programdata_metadata(), 0);
         
-        // Create instruction data
-        let instruction_data = [0]; // 0 = Initialize instruction
+        context.set_account(
+            &program_data_address(),
+            &AccountSharedData::from(Account {
+                lamports: Rent::default().minimum_balance(data.len()),
+                data,
+                owner: bpf_loader_upgradeable::id(),
+                ..Account::default()
+            }),
+        );
+    }
+    
+    // Derive the program data account of this program
+    fn program_data_address() -> Pubkey {
+        Pubkey::find_program_address(&[crate::id().as_ref()], &bpf_loader_upgradeable::id()).0
+    }
+    
+    // Helper function to build an Initialize instruction for the given authority
+    fn initialize_instruction(authority: &Pubkey) -> solana_program::instruction::Instruction {
+        solana_program::instruction::Instruction::new_with_bytes(
+            crate::id(),
+            &[0], // 0 = Initialize instruction
+            vec![
+                solana_program::instruction::AccountMeta::new(*authority, true),
+                solana_program::instruction::AccountMeta::new(ProgramConfig::find_address(&crate::id()).0, false),
+                solana_program::instruction::AccountMeta::new(Treasury::find_address(&crate::id()).0, false),
+                solana_program::instruction::AccountMeta::new_readonly(solana_program::system_program::id(), false),
+                solana_program::instruction::AccountMeta::new_readonly(program_data_address(), false),
+            ],
+        )
+    }
+    
+    // Helper function to initialize the program with the payer as upgrade authority
+    async fn initialize_program(context: &mut ProgramTestContext) {
+        let payer = context.payer.pubkey();
+        set_upgrade_authority(context, Some(payer));
         
         // Create transaction
         let mut transaction = Transaction::new_with_payer(
-            &[solana_program::instruction::Instruction::new_with_bincode(
-                crate::id(),
-                &instruction_data,
-                vec![
-                    solana_program::instruction::AccountMeta::new(program_authority.pubkey(), true),
-                ],
-            )],
-            Some(&payer.pubkey()),
+            &[initialize_instruction(&payer)],
+            Some(&payer),
         );
-        transaction.sign(&[&payer, &program_authority], recent_blockhash);
+        transaction.sign(&[&context.payer], context.last_blockhash);
         
         // Process transaction
-        banks_client.process_transaction(transaction).await.unwrap();
-        
-        // No need to check anything, if it doesn't error, it's successful
+        context.banks_client.process_transaction(transaction).await.unwrap();
     }
     
     #[tokio::test]
-    async fn test_create_player_wallet() {
-        let (mut banks_client, payer, recent_blockhash) = program_test().start().await;
-        
-        // Create player wallet account
-        let player_wallet_keypair = Keypair::new();
-        let player_name = "TestPlayer123".to_string();
+    async fn test_initialize_program() {
+        let mut context = program_test().start_with_context().await;
+        let (config_address, _) = ProgramConfig::find_address(&crate::id());
         
-        // Calculate account size
-        let account_size = PlayerWallet::get_account_size(player_name.len());
+        // Only the upgrade authority may initialize the program
+        set_upgrade_authority(&mut context, Some(Pubkey::new_unique()));
+        let mut transaction = Transaction::new_with_payer(
+            &[initialize_instruction(&context.payer.pubkey())],
+            Some(&context.payer.pubkey()),
+        );
+        transaction.sign(&[&context.payer], context.last_blockhash);
+        assert!(context.banks_client.process_transaction(transaction).await.is_err());
         
-        // Get minimum rent
-        let rent = banks_client.get_rent().await.unwrap();
-        let rent_lamports = rent.minimum_balance(account_size);
+        // The payer acts as upgrade authority and funds the config account
+        initialize_program(&mut context).await;
         
-        // Create instruction data
+        // Verify the authority was recorded
+        let config_account = context.banks_client
+            .get_account(config_address)
+            .await
+            .unwrap()
+            .unwrap();
+        let config = unpack_account::<ProgramConfig>(&config_account.data).unwrap();
+        assert_eq!(config.is_initialized, true);
+        assert_eq!(config.authority, context.payer.pubkey());
+        assert_eq!(config.creation_fee_lamports, 0);
+    }
+    
+    // Helper function to build a CreatePlayerWallet instruction for an owner's profile
+    fn create_player_wallet_instruction(
+        payer: &Pubkey,
+        owner: &Pubkey,
+        index: u32,
+        name: &str,
+    ) -> solana_program::instruction::Instruction {
         // 1 = CreatePlayerWallet instruction
         let mut instruction_data = vec![1];
-        instruction_data.extend_from_slice(&(player_name.try_to_vec().unwrap()));
+        instruction_data.extend_from_slice(&name.to_string().try_to_vec().unwrap());
+        
+        solana_program::instruction::Instruction::new_with_bytes(
+            crate::id(),
+            &instruction_data,
+            vec![
+                solana_program::instruction::AccountMeta::new(*payer, true),
+                solana_program::instruction::AccountMeta::new_readonly(*owner, true),
+                solana_program::instruction::AccountMeta::new(ProfileIndex::find_address(owner, &crate::id()).0, false),
+                solana_program::instruction::AccountMeta::new(find_player_wallet_address(owner, index, &crate::id()).0, false),
+                solana_program::instruction::AccountMeta::new_readonly(solana_program::system_program::id(), false),
+                solana_program::instruction::AccountMeta::new_readonly(NameBlocklist::find_address(&crate::id()).0, false),
+                solana_program::instruction::AccountMeta::new_readonly(ProgramConfig::find_address(&crate::id()).0, false),
+                solana_program::instruction::AccountMeta::new(Treasury::find_address(&crate::id()).0, false),
+            ],
+        )
+    }
+    
+    #[tokio::test]
+    async fn test_create_player_wallet() {
+        let mut context = program_test().start_with_context().await;
+        initialize_program(&mut context).await;
+        
+        // Create two player profiles for an owner holding no SOL,
+        // sponsored by the payer
+        let owner = Keypair::new();
+        let player_names = ["TestPlayer123".to_string(), "SecondCharacter".to_string()];
         
         // Create transaction
         let mut transaction = Transaction::new_with_payer(
             &[
-                // Create account
-                solana_program::system_instruction::create_account(
-                    &payer.pubkey(),
-                    &player_wallet_keypair.pubkey(),
-                    rent_lamports,
-                    account_size as u64,
-                    &crate::id(),
-                ),
-                // Initialize player wallet
-                solana_program::instruction::Instruction::new_with_bincode(
-                    crate::id(),
-                    &instruction_data,
-                    vec![
-                        solana_program::instruction::AccountMeta::new(payer.pubkey(), true),
-                        solana_program::instruction::AccountMeta::new(player_wallet_keypair.pubkey(), false),
-                        solana_program::instruction::AccountMeta::new_readonly(solana_program::system_program::id(), false),
-                    ],
-                ),
+                create_player_wallet_instruction(&context.payer.pubkey(), &owner.pubkey(), 0, &player_names[0]),
+                create_player_wallet_instruction(&context.payer.pubkey(), &owner.pubkey(), 1, &player_names[1]),
             ],
-            Some(&payer.pubkey()),
+            Some(&context.payer.pubkey()),
         );
-        transaction.sign(&[&payer, &player_wallet_keypair], recent_blockhash);
+        transaction.sign(&[&context.payer, &owner], context.last_blockhash);
         
         // Process transaction
-        banks_client.process_transaction(transaction).await.unwrap();
+        context.banks_client.process_transaction(transaction).await.unwrap();
         
-        // Fetch the account to verify it was created correctly
-        let player_wallet_account = banks_client
-            .get_account(player_wallet_keypair.pubkey())
+        // The profile index lists both wallets
+        let profile_index_account = context.banks_client
+            .get_account(ProfileIndex::find_address(&owner.pubkey(), &crate::id()).0)
             .await
             .unwrap()
             .unwrap();
+        let profile_index = unpack_account::<ProfileIndex>(&profile_index_account.data).unwrap();
+        assert_eq!(profile_index.owner, owner.pubkey());
+        assert_eq!(profile_index.profile_count, 2);
+        let player_wallet_addresses = profile_index.player_wallet_addresses(&crate::id());
         
-        // Deserialize the account data
-        let player_wallet = PlayerWallet::try_from_slice(&player_wallet_account.data).unwrap();
-        
-        // Verify the account data
-        assert_eq!(player_wallet.is_initialized, true);
-        assert_eq!(player_wallet.name, player_name);
-        assert_eq!(player_wallet.wallet_address, payer.pubkey());
-        assert_eq!(player_wallet.nb_tokens, 0);
-        assert_eq!(player_wallet.nb_transactions, 0);
-        assert_eq!(player_wallet.amount_total_tokens, 0);
-        assert_eq!(player_wallet.amount_total_value_stablecoin, 0);
-        assert_eq!(player_wallet.date_portfolio, 0);
+        for (player_wallet_address, player_name) in player_wallet_addresses.iter().zip(player_names.iter()) {
+            // Fetch the account to verify it was created correctly
+            let player_wallet_account = context.banks_client
+                .get_account(*player_wallet_address)
+                .await
+                .unwrap()
+                .unwrap();
+            
+            // Deserialize the account data
+            let player_wallet = PlayerWallet::load(&player_wallet_account.data).unwrap();
+            
+            // Verify the account data
+            assert_eq!(player_wallet_account.data.len(), PlayerWallet::LEN);
+            assert_eq!(player_wallet.is_initialized, 1);
+            assert_eq!(player_wallet.name(), player_name.as_str());
+            assert_eq!(player_wallet.wallet_address, owner.pubkey());
+            assert_eq!(player_wallet.nb_tokens, 0);
+            assert_eq!(player_wallet.nb_transactions, 0);
+            assert_eq!(player_wallet.amount_total_tokens, 0);
+            assert_eq!(player_wallet.amount_total_value_stablecoin, 0);
+            assert_eq!(player_wallet.date_portfolio, 0);
+        }
+    }
+    
+    #[tokio::test]
+    async fn test_name_blocklist() {
+        let mut context = program_test().start_with_context().await;
+        initialize_program(&mut context).await;
+        let owner = Keypair::new();
+        
+        // 3 = AddBlockedName and 4 = RemoveBlockedName instructions
+        let blocklist_instruction = |tag: u8, rule: BlockedNameRule| {
+            let mut instruction_data = vec![tag];
+            instruction_data.extend(rule.try_to_vec().unwrap());
+            solana_program::instruction::Instruction::new_with_bytes(
+                crate::id(),
+                &instruction_data,
+                vec![
+                    solana_program::instruction::AccountMeta::new(context.payer.pubkey(), true),
+                    solana_program::instruction::AccountMeta::new_readonly(ProgramConfig::find_address(&crate::id()).0, false),
+                    solana_program::instruction::AccountMeta::new(NameBlocklist::find_address(&crate::id()).0, false),
+                    solana_program::instruction::AccountMeta::new_readonly(solana_program::system_program::id(), false),
+                ],
+            )
+        };
+        let add_rule = blocklist_instruction(3, BlockedNameRule::Prefix("moderator".to_string()));
+        let remove_rule = blocklist_instruction(4, BlockedNameRule::Prefix("moderator".to_string()));
+        
+        let mut transaction = Transaction::new_with_payer(&[add_rule], Some(&context.payer.pubkey()));
+        transaction.sign(&[&context.payer], context.last_blockhash);
+        context.banks_client.process_transaction(transaction).await.unwrap();
+        
+        let blocklist_account = context.banks_client
+            .get_account(NameBlocklist::find_address(&crate::id()).0)
+            .await
+            .unwrap()
+            .unwrap();
+        let blocklist = unpack_account::<NameBlocklist>(&blocklist_account.data).unwrap();
+        assert_eq!(blocklist.rules, vec![BlockedNameRule::Prefix("moderator".to_string())]);
+        
+        // A blocked name is rejected on wallet creation
+        let mut transaction = Transaction::new_with_payer(
+            &[create_player_wallet_instruction(&context.payer.pubkey(), &owner.pubkey(), 0, "Moderator42")],
+            Some(&context.payer.pubkey()),
+        );
+        transaction.sign(&[&context.payer, &owner], context.last_blockhash);
+        assert_eq!(
+            context.banks_client.process_transaction(transaction).await.unwrap_err().unwrap(),
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(crate::error::PlayerWalletError::NameNotAllowed as u32),
+            ),
+        );
+        
+        // Once the rule is removed, the name can be used
+        let mut transaction = Transaction::new_with_payer(
+            &[
+                remove_rule,
+                create_player_wallet_instruction(&context.payer.pubkey(), &owner.pubkey(), 0, "Moderator42"),
+            ],
+            Some(&context.payer.pubkey()),
+        );
+        transaction.sign(&[&context.payer, &owner], context.last_blockhash);
+        context.banks_client.process_transaction(transaction).await.unwrap();
+        
+        let blocklist_account = context.banks_client
+            .get_account(NameBlocklist::find_address(&crate::id()).0)
+            .await
+            .unwrap()
+            .unwrap();
+        let blocklist = unpack_account::<NameBlocklist>(&blocklist_account.data).unwrap();
+        assert!(blocklist.rules.is_empty());
     }
     
     #[tokio::test]
@@ -146,23 +531,14 @@ mod tests {
         let player_name = "TestPlayer123".to_string();
         
         // Calculate account size
-        let account_size = PlayerWallet::get_account_size(player_name.len());
+        let account_size = PlayerWallet::LEN;
         
         // Get minimum rent
         let rent = banks_client.get_rent().await.unwrap();
         let rent_lamports = rent.minimum_balance(account_size);
         
         // Create player wallet
-        let player_wallet = PlayerWallet {
-            is_initialized: true,
-            name: player_name.clone(),
-            wallet_address: payer.pubkey(),
-            nb_tokens: 0,
-            nb_transactions: 0,
-            amount_total_tokens: 0,
-            amount_total_value_stablecoin: 0,
-            date_portfolio: 0,
-        };
+        let player_wallet = PlayerWallet::new(&player_name, payer.pubkey()).unwrap();
         
         // Create account with initial data
         let mut transaction = Transaction::new_with_payer(
@@ -186,46 +562,27 @@ mod tests {
             .await
             .unwrap()
             .unwrap();
-        player_wallet_account.data = player_wallet.try_to_vec().unwrap();
+        player_wallet_account.data = bytemuck::bytes_of(&player_wallet).to_vec();
         banks_client.set_account(&player_wallet_keypair.pubkey(), &player_wallet_account);
         
-        // Update values
-        let new_nb_tokens = 100;
-        let new_amount_total_tokens = 500;
-        let new_amount_total_value_stablecoin = 1000;
+        // Only the portfolio date can be set; token aggregates come from the holdings
         let new_date_portfolio = 1620000000;
         
         // Create instruction data for update
         // 2 = UpdatePlayerWallet instruction
         let mut instruction_data = vec![2];
-        
-        // Create update payload
-        #[derive(BorshSerialize)]
-        struct UpdatePayload {
-            nb_tokens: u32,
-            amount_total_tokens: u32,
-            amount_total_value_stablecoin: u32,
-            date_portfolio: u64,
-        }
-        
-        let update_payload = UpdatePayload {
-            nb_tokens: new_nb_tokens,
-            amount_total_tokens: new_amount_total_tokens,
-            amount_total_value_stablecoin: new_amount_total_value_stablecoin,
-            date_portfolio: new_date_portfolio,
-        };
-        
-        instruction_data.extend_from_slice(&update_payload.try_to_vec().unwrap());
+        instruction_data.extend_from_slice(&new_date_portfolio.try_to_vec().unwrap());
         
         // Create transaction for update
         let mut transaction = Transaction::new_with_payer(
             &[
-                solana_program::instruction::Instruction::new_with_bincode(
+                solana_program::instruction::Instruction::new_with_bytes(
                     crate::id(),
                     &instruction_data,
                     vec![
                         solana_program::instruction::AccountMeta::new(payer.pubkey(), true),
                         solana_program::instruction::AccountMeta::new(player_wallet_keypair.pubkey(), false),
+                        solana_program::instruction::AccountMeta::new_readonly(SeasonClock::find_address(&crate::id()).0, false),
                     ],
                 ),
             ],
@@ -244,37 +601,2947 @@ mod tests {
             .unwrap();
         
         // Deserialize the account data
-        let updated_wallet = PlayerWallet::try_from_slice(&updated_account.data).unwrap();
+        let updated_wallet = PlayerWallet::load(&updated_account.data).unwrap();
         
         // Verify the account data was updated
-        assert_eq!(updated_wallet.is_initialized, true);
-        assert_eq!(updated_wallet.name, player_name);
+        assert_eq!(updated_wallet.is_initialized, 1);
+        assert_eq!(updated_wallet.name(), player_name);
         assert_eq!(updated_wallet.wallet_address, payer.pubkey());
-        assert_eq!(updated_wallet.nb_tokens, new_nb_tokens);
-        assert_eq!(updated_wallet.nb_transactions, 1); // Should be incremented
-        assert_eq!(updated_wallet.amount_total_tokens, new_amount_total_tokens);
-        assert_eq!(updated_wallet.amount_total_value_stablecoin, new_amount_total_value_stablecoin);
+        assert_eq!(updated_wallet.nb_tokens, 0);
+        assert_eq!(updated_wallet.nb_transactions, 0); // Only trades and transfers count
+        assert_eq!(updated_wallet.amount_total_tokens, 0);
+        assert_eq!(updated_wallet.amount_total_value_stablecoin, 0);
         assert_eq!(updated_wallet.date_portfolio, new_date_portfolio);
     }
     
-    #[test]
-    fn test_player_wallet_validation() {
-        // Test name length validation
-        let wallet_address = Pubkey::new_unique();
+    #[tokio::test]
+    async fn test_creation_fee_and_withdraw_treasury() {
+        let mut context = program_test().start_with_context().await;
+        initialize_program(&mut context).await;
         
-        // Test valid name
-        let valid_name = "ValidPlayerName123".to_string();
-        let valid_result = PlayerWallet::new(valid_name.clone(), wallet_address);
-        assert!(valid_result.is_ok());
+        let (config_address, _) = ProgramConfig::find_address(&crate::id());
+        let (treasury_address, _) = Treasury::find_address(&crate::id());
+        let creation_fee: u64 = 1_000_000;
         
-        // Test name too long
-        let long_name = "a".repeat(MAX_NAME_LENGTH + 1);
-        let long_result = PlayerWallet::new(long_name, wallet_address);
-        assert!(long_result.is_err());
+        // 5 = SetCreationFee instruction
+        let mut fee_data = vec![5];
+        fee_data.extend_from_slice(&creation_fee.try_to_vec().unwrap());
+        let mut transaction = Transaction::new_with_payer(
+            &[solana_program::instruction::Instruction::new_with_bytes(
+                crate::id(),
+                &fee_data,
+                vec![
+                    solana_program::instruction::AccountMeta::new_readonly(context.payer.pubkey(), true),
+                    solana_program::instruction::AccountMeta::new(config_address, false),
+                ],
+            )],
+            Some(&context.payer.pubkey()),
+        );
+        transaction.sign(&[&context.payer], context.last_blockhash);
+        context.banks_client.process_transaction(transaction).await.unwrap();
         
-        // Test invalid characters
-        let invalid_name = "Invalid-Name!".to_string();
-        let invalid_result = PlayerWallet::new(invalid_name, wallet_address);
-        assert!(invalid_result.is_err());
+        let treasury_lamports_before = context.banks_client.get_balance(treasury_address).await.unwrap();
+        
+        // Create a wallet, paying the fee
+        let mut transaction = Transaction::new_with_payer(
+            &[create_player_wallet_instruction(&context.payer.pubkey(), &context.payer.pubkey(), 0, "FeePayer")],
+            Some(&context.payer.pubkey()),
+        );
+        transaction.sign(&[&context.payer], context.last_blockhash);
+        context.banks_client.process_transaction(transaction).await.unwrap();
+        
+        // The fee landed in the treasury
+        let treasury_account = context.banks_client.get_account(treasury_address).await.unwrap().unwrap();
+        assert_eq!(treasury_account.lamports, treasury_lamports_before + creation_fee);
+        let treasury = unpack_account::<Treasury>(&treasury_account.data).unwrap();
+        assert_eq!(treasury.total_collected, creation_fee);
+        
+        // 6 = WithdrawTreasury instruction
+        let destination = Keypair::new();
+        let mut withdraw_data = vec![6];
+        withdraw_data.extend_from_slice(&creation_fee.try_to_vec().unwrap());
+        let withdraw_instruction = |amount_data: Vec<u8>| solana_program::instruction::Instruction::new_with_bytes(
+            crate::id(),
+            &amount_data,
+            vec![
+                solana_program::instruction::AccountMeta::new_readonly(context.payer.pubkey(), true),
+                solana_program::instruction::AccountMeta::new_readonly(config_address, false),
+                solana_program::instruction::AccountMeta::new(treasury_address, false),
+                solana_program::instruction::AccountMeta::new(destination.pubkey(), false),
+            ],
+        );
+        let mut transaction = Transaction::new_with_payer(
+            &[withdraw_instruction(withdraw_data)],
+            Some(&context.payer.pubkey()),
+        );
+        transaction.sign(&[&context.payer], context.last_blockhash);
+        context.banks_client.process_transaction(transaction).await.unwrap();
+        assert_eq!(context.banks_client.get_balance(destination.pubkey()).await.unwrap(), creation_fee);
+        
+        // The rent-exempt reserve cannot be withdrawn
+        let mut drain_data = vec![6];
+        drain_data.extend_from_slice(&1u64.try_to_vec().unwrap());
+        let mut transaction = Transaction::new_with_payer(
+            &[withdraw_instruction(drain_data)],
+            Some(&context.payer.pubkey()),
+        );
+        transaction.sign(&[&context.payer], context.last_blockhash);
+        assert!(context.banks_client.process_transaction(transaction).await.is_err());
+    }
+    
+    #[tokio::test]
+    async fn test_migrate_wallet() {
+        let mut program_test = program_test();
+        
+        // A wallet written with the original, unversioned layout
+        let owner = Pubkey::new_unique();
+        let legacy_wallet_address = Pubkey::new_unique();
+        let legacy_wallet = PlayerWalletV1 {
+            is_initialized: true,
+            name: "OldTimer".to_string(),
+            wallet_address: owner,
+            nb_tokens: 3,
+            nb_transactions: 7,
+            amount_total_tokens: 500,
+            amount_total_value_stablecoin: 1000,
+            date_portfolio: 1620000000,
+        };
+        let legacy_data = legacy_wallet.try_to_vec().unwrap();
+        program_test.add_account(
+            legacy_wallet_address,
+            Account {
+                lamports: Rent::default().minimum_balance(legacy_data.len()),
+                data: legacy_data,
+                owner: crate::id(),
+                ..Account::default()
+            },
+        );
+        
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+        
+        // 7 = MigrateWallet instruction
+        let mut transaction = Transaction::new_with_payer(
+            &[solana_program::instruction::Instruction::new_with_bytes(
+                crate::id(),
+                &[7],
+                vec![
+                    solana_program::instruction::AccountMeta::new(payer.pubkey(), true),
+                    solana_program::instruction::AccountMeta::new(legacy_wallet_address, false),
+                    solana_program::instruction::AccountMeta::new_readonly(solana_program::system_program::id(), false),
+                ],
+            )],
+            Some(&payer.pubkey()),
+        );
+        transaction.sign(&[&payer], recent_blockhash);
+        banks_client.process_transaction(transaction).await.unwrap();
+        
+        // The account now holds the latest layout with the same values
+        let migrated_account = banks_client
+            .get_account(legacy_wallet_address)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(migrated_account.data.len(), PlayerWallet::LEN);
+        let migrated_wallet = PlayerWallet::load(&migrated_account.data).unwrap();
+        assert_eq!(migrated_wallet.version, PLAYER_WALLET_VERSION);
+        assert_eq!(migrated_wallet.name(), "OldTimer");
+        assert_eq!(migrated_wallet.wallet_address, owner);
+        assert_eq!(migrated_wallet.nb_tokens, 3);
+        assert_eq!(migrated_wallet.nb_transactions, 7);
+        assert_eq!(migrated_wallet.amount_total_tokens, 500);
+        assert_eq!(migrated_wallet.amount_total_value_stablecoin, 1000);
+        assert_eq!(migrated_wallet.date_portfolio, 1620000000);
+    }
+    
+    #[tokio::test]
+    async fn test_add_holding() {
+        let mut program_test = program_test();
+        let owner = Keypair::new();
+        let player_wallet_address = add_player_wallet(&mut program_test, &PlayerWallet::new("Holder", owner.pubkey()).unwrap());
+        let holdings_address = Holdings::find_address(&player_wallet_address, &crate::id()).0;
+        let mint = Pubkey::new_unique();
+        add_mint(&mut program_test, mint, Some(Pubkey::new_unique()), 6, 1_000_000);
+        
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+        
+        // 9 = AddHolding instruction
+        let mut instruction_data = vec![9];
+        instruction_data.extend(mint.try_to_vec().unwrap());
+        let mut transaction = Transaction::new_with_payer(
+            &[solana_program::instruction::Instruction::new_with_bytes(
+                crate::id(),
+                &instruction_data,
+                vec![
+                    solana_program::instruction::AccountMeta::new(payer.pubkey(), true),
+                    solana_program::instruction::AccountMeta::new_readonly(owner.pubkey(), true),
+                    solana_program::instruction::AccountMeta::new(player_wallet_address, false),
+                    solana_program::instruction::AccountMeta::new(holdings_address, false),
+                    solana_program::instruction::AccountMeta::new_readonly(mint, false),
+                    solana_program::instruction::AccountMeta::new_readonly(solana_program::system_program::id(), false),
+                    solana_program::instruction::AccountMeta::new_readonly(SeasonClock::find_address(&crate::id()).0, false),
+                ],
+            )],
+            Some(&payer.pubkey()),
+        );
+        transaction.sign(&[&payer, &owner], recent_blockhash);
+        banks_client.process_transaction(transaction).await.unwrap();
+        
+        // Decimals come from the mint, the amount and price from sync and revalue
+        let holdings_account = banks_client.get_account(holdings_address).await.unwrap().unwrap();
+        let holdings = unpack_account::<Holdings>(&holdings_account.data).unwrap();
+        assert_eq!(holdings.entries, vec![Holding { mint, amount: 0, decimals: 6, last_price: 0, vault_amount: 0, token_program: spl_token::id() }]);
+        
+        let player_wallet_account = banks_client.get_account(player_wallet_address).await.unwrap().unwrap();
+        let player_wallet = PlayerWallet::load(&player_wallet_account.data).unwrap();
+        assert_eq!(player_wallet.nb_tokens, 1);
+        assert_eq!(player_wallet.amount_total_tokens, 0);
+    }
+    
+    #[tokio::test]
+    async fn test_sync_from_token_accounts() {
+        let mut program_test = program_test();
+        
+        // A wallet claiming balances that differ from its token accounts
+        let owner = Pubkey::new_unique();
+        let mut player_wallet = PlayerWallet::new("Syncer", owner).unwrap();
+        player_wallet.nb_tokens = 3;
+        player_wallet.amount_total_tokens = 1_000_000;
+        let player_wallet_address = add_player_wallet(&mut program_test, &player_wallet);
+        
+        let mints = [Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique()];
+        let holdings_address = Holdings::find_address(&player_wallet_address, &crate::id()).0;
+        add_state_account(
+            &mut program_test,
+            holdings_address,
+            &Holdings {
+                is_initialized: true,
+                player_wallet: player_wallet_address,
+                bump: Holdings::find_address(&player_wallet_address, &crate::id()).1,
+                entries: mints.iter()
+                    .map(|mint| Holding { mint: *mint, amount: 500_000, decimals: 6, last_price: 0, vault_amount: 0, token_program: spl_token::id() })
+                    .collect(),
+            },
+        );
+        
+        let token_accounts: Vec<Pubkey> = mints.iter()
+            .map(|mint| spl_associated_token_account::get_associated_token_address(&owner, mint))
+            .collect();
+        add_token_account(&mut program_test, token_accounts[0], mints[0], owner, 1_500);
+        add_token_account(&mut program_test, token_accounts[1], mints[1], owner, 2_500);
+        // The third token account has been closed
+        
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+        
+        // 12 = SyncFromTokenAccounts instruction, callable by anyone
+        let mut transaction = Transaction::new_with_payer(
+            &[solana_program::instruction::Instruction::new_with_bytes(
+                crate::id(),
+                &[12],
+                vec![
+                    solana_program::instruction::AccountMeta::new(player_wallet_address, false),
+                    solana_program::instruction::AccountMeta::new(holdings_address, false),
+                    solana_program::instruction::AccountMeta::new(Leaderboard::find_address(&crate::id()).0, false),
+                    solana_program::instruction::AccountMeta::new_readonly(SeasonClock::find_address(&crate::id()).0, false),
+                    solana_program::instruction::AccountMeta::new_readonly(token_accounts[0], false),
+                    solana_program::instruction::AccountMeta::new_readonly(token_accounts[1], false),
+                    solana_program::instruction::AccountMeta::new_readonly(token_accounts[2], false),
+                ],
+            )],
+            Some(&payer.pubkey()),
+        );
+        transaction.sign(&[&payer], recent_blockhash);
+        banks_client.process_transaction(transaction).await.unwrap();
+        
+        // Balances now reflect the token accounts, the closed one holding nothing
+        let synced_account = banks_client.get_account(player_wallet_address).await.unwrap().unwrap();
+        let synced_wallet = PlayerWallet::load(&synced_account.data).unwrap();
+        assert_eq!(synced_wallet.nb_tokens, 3);
+        assert_eq!(synced_wallet.amount_total_tokens, 4_000);
+        
+        let holdings_account = banks_client.get_account(holdings_address).await.unwrap().unwrap();
+        let holdings = unpack_account::<Holdings>(&holdings_account.data).unwrap();
+        assert_eq!(holdings.entries[0].amount, 1_500);
+        assert_eq!(holdings.entries[1].amount, 2_500);
+        assert_eq!(holdings.entries[2].amount, 0);
+    }
+    
+    #[tokio::test]
+    async fn test_update_holding() {
+        let mut program_test = program_test();
+        let owner = Keypair::new();
+        let mut player_wallet = PlayerWallet::new("Updater", owner.pubkey()).unwrap();
+        player_wallet.nb_tokens = 1;
+        let player_wallet_address = add_player_wallet(&mut program_test, &player_wallet);
+        
+        let mint = Pubkey::new_unique();
+        let holdings_address = Holdings::find_address(&player_wallet_address, &crate::id()).0;
+        add_state_account(
+            &mut program_test,
+            holdings_address,
+            &Holdings {
+                is_initialized: true,
+                player_wallet: player_wallet_address,
+                bump: Holdings::find_address(&player_wallet_address, &crate::id()).1,
+                entries: vec![Holding { mint, amount: 0, decimals: 6, last_price: 0, vault_amount: 0, token_program: spl_token::id() }],
+            },
+        );
+        
+        let token_account = spl_associated_token_account::get_associated_token_address(&owner.pubkey(), &mint);
+        add_token_account(&mut program_test, token_account, mint, owner.pubkey(), 7_000);
+        
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+        
+        // 10 = UpdateHolding instruction
+        let mut instruction_data = vec![10];
+        instruction_data.extend(mint.try_to_vec().unwrap());
+        let mut transaction = Transaction::new_with_payer(
+            &[solana_program::instruction::Instruction::new_with_bytes(
+                crate::id(),
+                &instruction_data,
+                vec![
+                    solana_program::instruction::AccountMeta::new_readonly(owner.pubkey(), true),
+                    solana_program::instruction::AccountMeta::new(player_wallet_address, false),
+                    solana_program::instruction::AccountMeta::new(holdings_address, false),
+                    solana_program::instruction::AccountMeta::new_readonly(token_account, false),
+                    solana_program::instruction::AccountMeta::new(Leaderboard::find_address(&crate::id()).0, false),
+                    solana_program::instruction::AccountMeta::new_readonly(SeasonClock::find_address(&crate::id()).0, false),
+                ],
+            )],
+            Some(&payer.pubkey()),
+        );
+        transaction.sign(&[&payer, &owner], recent_blockhash);
+        banks_client.process_transaction(transaction).await.unwrap();
+        
+        // The holding and the wallet total follow the token account
+        let holdings_account = banks_client.get_account(holdings_address).await.unwrap().unwrap();
+        let holdings = unpack_account::<Holdings>(&holdings_account.data).unwrap();
+        assert_eq!(holdings.entries[0].amount, 7_000);
+        
+        let player_wallet_account = banks_client.get_account(player_wallet_address).await.unwrap().unwrap();
+        let player_wallet = PlayerWallet::load(&player_wallet_account.data).unwrap();
+        assert_eq!(player_wallet.amount_total_tokens, 7_000);
+    }
+    
+    #[tokio::test]
+    async fn test_revalue_from_price_feeds() {
+        let mut program_test = program_test();
+        
+        let player_wallet = PlayerWallet::new("Valued", Pubkey::new_unique()).unwrap();
+        let player_wallet_address = add_player_wallet(&mut program_test, &player_wallet);
+        
+        // 2.5 tokens with 6 decimals, 3 tokens with 9 decimals and an unpriced token
+        let mints = [Pubkey::new_unique(), Pubkey::new_unique()];
+        let unpriced_mint = Pubkey::new_unique();
+        let (holdings_address, holdings_bump) = Holdings::find_address(&player_wallet_address, &crate::id());
+        add_state_account(
+            &mut program_test,
+            holdings_address,
+            &Holdings {
+                is_initialized: true,
+                player_wallet: player_wallet_address,
+                bump: holdings_bump,
+                entries: vec![
+                    Holding { mint: mints[0], amount: 2_500_000, decimals: 6, last_price: 0, vault_amount: 0, token_program: spl_token::id() },
+                    Holding { mint: mints[1], amount: 3_000_000_000, decimals: 9, last_price: 0, vault_amount: 0, token_program: spl_token::id() },
+                    Holding { mint: unpriced_mint, amount: 1_000, decimals: 0, last_price: 7, vault_amount: 0, token_program: spl_token::id() },
+                ],
+            },
+        );
+        
+        let mut context = program_test.start_with_context().await;
+        let clock: solana_program::clock::Clock = context.banks_client.get_sysvar().await.unwrap();
+        
+        // Prices of 1.50 and 20.00 published at the current time
+        let price_feeds: Vec<Pubkey> = mints.iter()
+            .zip([(150_000_000, -8), (2_000, -2)])
+            .map(|(mint, (price, expo))| {
+                let (address, bump) = PriceFeed::find_address(mint, &crate::id());
+                let data = pack_account(&PriceFeed {
+                    is_initialized: true,
+                    mint: *mint,
+                    publisher: Pubkey::new_unique(),
+                    price,
+                    expo,
+                    conf: 0,
+                    publish_time: clock.unix_timestamp,
+                    bump,
+                }).unwrap();
+                context.set_account(&address, &AccountSharedData::from(Account {
+                    lamports: Rent::default().minimum_balance(data.len()),
+                    data,
+                    owner: crate::id(),
+                    ..Account::default()
+                }));
+                address
+            })
+            .collect();
+        
+        // 15 = Revalue instruction, callable by anyone
+        let mut transaction = Transaction::new_with_payer(
+            &[solana_program::instruction::Instruction::new_with_bytes(
+                crate::id(),
+                &[15],
+                vec![
+                    solana_program::instruction::AccountMeta::new(player_wallet_address, false),
+                    solana_program::instruction::AccountMeta::new(holdings_address, false),
+                    solana_program::instruction::AccountMeta::new(Leaderboard::find_address(&crate::id()).0, false),
+                    solana_program::instruction::AccountMeta::new_readonly(SeasonClock::find_address(&crate::id()).0, false),
+                    solana_program::instruction::AccountMeta::new_readonly(price_feeds[0], false),
+                    solana_program::instruction::AccountMeta::new_readonly(price_feeds[1], false),
+                    solana_program::instruction::AccountMeta::new_readonly(PriceFeed::find_address(&unpriced_mint, &crate::id()).0, false),
+                ],
+            )],
+            Some(&context.payer.pubkey()),
+        );
+        transaction.sign(&[&context.payer], context.last_blockhash);
+        context.banks_client.process_transaction(transaction).await.unwrap();
+        
+        // 2.5 * 1.50 + 3 * 20.00 = 63.75 stablecoin, the unpriced token counting for nothing
+        let valued_account = context.banks_client.get_account(player_wallet_address).await.unwrap().unwrap();
+        let valued_wallet = PlayerWallet::load(&valued_account.data).unwrap();
+        assert_eq!(valued_wallet.amount_total_value_stablecoin, 63_750_000);
+        assert!(valued_wallet.date_portfolio >= clock.unix_timestamp as u64);
+        
+        let holdings_account = context.banks_client.get_account(holdings_address).await.unwrap().unwrap();
+        let holdings = unpack_account::<Holdings>(&holdings_account.data).unwrap();
+        assert_eq!(holdings.entries[0].last_price, 1_500_000);
+        assert_eq!(holdings.entries[1].last_price, 20_000_000);
+        assert_eq!(holdings.entries[2].last_price, 0);
+    }
+    
+    #[tokio::test]
+    async fn test_vault_deposit_and_withdraw() {
+        let mut program_test = program_test();
+        
+        let owner = Keypair::new();
+        let player_wallet = PlayerWallet::new("Depositor", owner.pubkey()).unwrap();
+        let player_wallet_address = add_player_wallet(&mut program_test, &player_wallet);
+        
+        let mint = Pubkey::new_unique();
+        add_mint(&mut program_test, mint, Some(Pubkey::new_unique()), 6, 1_000);
+        let owner_token_account = spl_associated_token_account::get_associated_token_address(&owner.pubkey(), &mint);
+        add_token_account(&mut program_test, owner_token_account, mint, owner.pubkey(), 1_000);
+        
+        let (holdings_address, holdings_bump) = Holdings::find_address(&player_wallet_address, &crate::id());
+        add_state_account(
+            &mut program_test,
+            holdings_address,
+            &Holdings {
+                is_initialized: true,
+                player_wallet: player_wallet_address,
+                bump: holdings_bump,
+                entries: vec![Holding { mint, amount: 1_000, decimals: 6, last_price: 0, vault_amount: 0, token_program: spl_token::id() }],
+            },
+        );
+        
+        let vault_authority = find_vault_authority_address(&player_wallet_address, &crate::id()).0;
+        let vault_token_account = spl_associated_token_account::get_associated_token_address(&vault_authority, &mint);
+        
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+        
+        // 16 = Deposit instruction
+        let mut instruction_data = vec![16];
+        instruction_data.extend(400u64.try_to_vec().unwrap());
+        let mut transaction = Transaction::new_with_payer(
+            &[solana_program::instruction::Instruction::new_with_bytes(
+                crate::id(),
+                &instruction_data,
+                vec![
+                    solana_program::instruction::AccountMeta::new(payer.pubkey(), true),
+                    solana_program::instruction::AccountMeta::new_readonly(owner.pubkey(), true),
+                    solana_program::instruction::AccountMeta::new(player_wallet_address, false),
+                    solana_program::instruction::AccountMeta::new(holdings_address, false),
+                    solana_program::instruction::AccountMeta::new_readonly(mint, false),
+                    solana_program::instruction::AccountMeta::new(owner_token_account, false),
+                    solana_program::instruction::AccountMeta::new_readonly(vault_authority, false),
+                    solana_program::instruction::AccountMeta::new(vault_token_account, false),
+                    solana_program::instruction::AccountMeta::new_readonly(spl_token::id(), false),
+                    solana_program::instruction::AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+                    solana_program::instruction::AccountMeta::new_readonly(solana_program::system_program::id(), false),
+                    solana_program::instruction::AccountMeta::new(Leaderboard::find_address(&crate::id()).0, false),
+                    solana_program::instruction::AccountMeta::new_readonly(SeasonClock::find_address(&crate::id()).0, false),
+                ],
+            )],
+            Some(&payer.pubkey()),
+        );
+        transaction.sign(&[&payer, &owner], recent_blockhash);
+        banks_client.process_transaction(transaction).await.unwrap();
+        
+        // The tokens moved to the vault without changing the wallet total
+        let vault_account = banks_client.get_account(vault_token_account).await.unwrap().unwrap();
+        assert_eq!(spl_token::state::Account::unpack(&vault_account.data).unwrap().amount, 400);
+        let holdings_account = banks_client.get_account(holdings_address).await.unwrap().unwrap();
+        let holdings = unpack_account::<Holdings>(&holdings_account.data).unwrap();
+        assert_eq!(holdings.entries[0].amount, 600);
+        assert_eq!(holdings.entries[0].vault_amount, 400);
+        let player_wallet_account = banks_client.get_account(player_wallet_address).await.unwrap().unwrap();
+        assert_eq!(PlayerWallet::load(&player_wallet_account.data).unwrap().amount_total_tokens, 1_000);
+        
+        // 17 = Withdraw instruction
+        let mut instruction_data = vec![17];
+        instruction_data.extend(150u64.try_to_vec().unwrap());
+        let mut transaction = Transaction::new_with_payer(
+            &[solana_program::instruction::Instruction::new_with_bytes(
+                crate::id(),
+                &instruction_data,
+                vec![
+                    solana_program::instruction::AccountMeta::new_readonly(owner.pubkey(), true),
+                    solana_program::instruction::AccountMeta::new(player_wallet_address, false),
+                    solana_program::instruction::AccountMeta::new(holdings_address, false),
+                    solana_program::instruction::AccountMeta::new_readonly(mint, false),
+                    solana_program::instruction::AccountMeta::new(owner_token_account, false),
+                    solana_program::instruction::AccountMeta::new_readonly(vault_authority, false),
+                    solana_program::instruction::AccountMeta::new(vault_token_account, false),
+                    solana_program::instruction::AccountMeta::new_readonly(spl_token::id(), false),
+                    solana_program::instruction::AccountMeta::new_readonly(StakePosition::find_address(&player_wallet_address, &crate::id()).0, false),
+                    solana_program::instruction::AccountMeta::new(Leaderboard::find_address(&crate::id()).0, false),
+                    solana_program::instruction::AccountMeta::new_readonly(SeasonClock::find_address(&crate::id()).0, false),
+                ],
+            )],
+            Some(&payer.pubkey()),
+        );
+        transaction.sign(&[&payer, &owner], recent_blockhash);
+        banks_client.process_transaction(transaction).await.unwrap();
+        
+        let owner_account = banks_client.get_account(owner_token_account).await.unwrap().unwrap();
+        assert_eq!(spl_token::state::Account::unpack(&owner_account.data).unwrap().amount, 750);
+        let holdings_account = banks_client.get_account(holdings_address).await.unwrap().unwrap();
+        let holdings = unpack_account::<Holdings>(&holdings_account.data).unwrap();
+        assert_eq!(holdings.entries[0].amount, 750);
+        assert_eq!(holdings.entries[0].vault_amount, 250);
+    }
+    
+    #[tokio::test]
+    async fn test_sol_deposit_and_withdraw() {
+        let mut program_test = program_test();
+        let rent_exempt_minimum = Rent::default().minimum_balance(PlayerWallet::LEN);
+        
+        let owner = Keypair::new();
+        program_test.add_account(
+            owner.pubkey(),
+            Account { lamports: 5_000_000_000, ..Account::default() },
+        );
+        let player_wallet = PlayerWallet::new("SolHolder", owner.pubkey()).unwrap();
+        let player_wallet_address = add_player_wallet(&mut program_test, &player_wallet);
+        
+        // A wallet recording more SOL than it holds above rent
+        let mut short_wallet = PlayerWallet::new("ShortWallet", owner.pubkey()).unwrap();
+        short_wallet.sol_balance = 1_000_000;
+        let short_wallet_address = Pubkey::new_unique();
+        program_test.add_account(
+            short_wallet_address,
+            Account {
+                lamports: rent_exempt_minimum + 10,
+                data: bytemuck::bytes_of(&short_wallet).to_vec(),
+                owner: crate::id(),
+                ..Account::default()
+            },
+        );
+        
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+        
+        let sol_instruction = |tag: u8, player_wallet: Pubkey, amount: u64| {
+            let mut instruction_data = vec![tag];
+            instruction_data.extend(amount.try_to_vec().unwrap());
+            let mut accounts = vec![
+                solana_program::instruction::AccountMeta::new(owner.pubkey(), true),
+                solana_program::instruction::AccountMeta::new(player_wallet, false),
+            ];
+            if tag == 18 {
+                accounts.push(solana_program::instruction::AccountMeta::new_readonly(
+                    solana_program::system_program::id(),
+                    false,
+                ));
+            }
+            solana_program::instruction::Instruction::new_with_bytes(crate::id(), &instruction_data, accounts)
+        };
+        
+        // 18 = DepositSol instruction
+        let mut transaction = Transaction::new_with_payer(
+            &[sol_instruction(18, player_wallet_address, 1_000_000_000)],
+            Some(&payer.pubkey()),
+        );
+        transaction.sign(&[&payer, &owner], recent_blockhash);
+        banks_client.process_transaction(transaction).await.unwrap();
+        
+        let player_wallet_account = banks_client.get_account(player_wallet_address).await.unwrap().unwrap();
+        assert_eq!(player_wallet_account.lamports, rent_exempt_minimum + 1_000_000_000);
+        assert_eq!(PlayerWallet::load(&player_wallet_account.data).unwrap().sol_balance, 1_000_000_000);
+        
+        // 19 = WithdrawSol instruction, limited to the deposited balance
+        let mut transaction = Transaction::new_with_payer(
+            &[sol_instruction(19, player_wallet_address, 1_000_000_001)],
+            Some(&payer.pubkey()),
+        );
+        transaction.sign(&[&payer, &owner], recent_blockhash);
+        assert!(banks_client.process_transaction(transaction).await.is_err());
+        
+        let mut transaction = Transaction::new_with_payer(
+            &[sol_instruction(19, player_wallet_address, 400_000_000)],
+            Some(&payer.pubkey()),
+        );
+        transaction.sign(&[&payer, &owner], recent_blockhash);
+        banks_client.process_transaction(transaction).await.unwrap();
+        
+        let player_wallet_account = banks_client.get_account(player_wallet_address).await.unwrap().unwrap();
+        assert_eq!(player_wallet_account.lamports, rent_exempt_minimum + 600_000_000);
+        assert_eq!(PlayerWallet::load(&player_wallet_account.data).unwrap().sol_balance, 600_000_000);
+        assert_eq!(banks_client.get_balance(owner.pubkey()).await.unwrap(), 4_400_000_000);
+        
+        // Even a recorded balance cannot be paid out of the rent-exempt minimum
+        let mut transaction = Transaction::new_with_payer(
+            &[sol_instruction(19, short_wallet_address, 11)],
+            Some(&payer.pubkey()),
+        );
+        transaction.sign(&[&payer, &owner], recent_blockhash);
+        assert!(banks_client.process_transaction(transaction).await.is_err());
+        
+        let mut transaction = Transaction::new_with_payer(
+            &[sol_instruction(19, short_wallet_address, 10)],
+            Some(&payer.pubkey()),
+        );
+        transaction.sign(&[&payer, &owner], recent_blockhash);
+        banks_client.process_transaction(transaction).await.unwrap();
+        
+        let short_wallet_account = banks_client.get_account(short_wallet_address).await.unwrap().unwrap();
+        assert_eq!(short_wallet_account.lamports, rent_exempt_minimum);
+    }
+    
+    #[tokio::test]
+    async fn test_transfer_between_players() {
+        let mut program_test = program_test();
+        let rent_exempt_minimum = Rent::default().minimum_balance(PlayerWallet::LEN);
+        
+        // Add a player wallet holding `sol_balance` lamports above rent
+        let mut add_wallet = |name: &str, owner: Pubkey, sol_balance: u64, status: WalletStatus| {
+            let mut player_wallet = PlayerWallet::new(name, owner).unwrap();
+            player_wallet.sol_balance = sol_balance;
+            player_wallet.status = status as u8;
+            let address = Pubkey::new_unique();
+            program_test.add_account(
+                address,
+                Account {
+                    lamports: rent_exempt_minimum + sol_balance,
+                    data: bytemuck::bytes_of(&player_wallet).to_vec(),
+                    owner: crate::id(),
+                    ..Account::default()
+                },
+            );
+            address
+        };
+        let sender_owner = Keypair::new();
+        let sender = add_wallet("Sender", sender_owner.pubkey(), 1_000, WalletStatus::Active);
+        let recipient = add_wallet("Recipient", Pubkey::new_unique(), 0, WalletStatus::Active);
+        let frozen = add_wallet("Frozen", Pubkey::new_unique(), 0, WalletStatus::Frozen);
+        
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+        
+        // 20 = Transfer instruction
+        let transfer_instruction = |recipient: Pubkey, amount: u64| {
+            let mut instruction_data = vec![20];
+            instruction_data.extend(amount.try_to_vec().unwrap());
+            solana_program::instruction::Instruction::new_with_bytes(
+                crate::id(),
+                &instruction_data,
+                vec![
+                    solana_program::instruction::AccountMeta::new_readonly(sender_owner.pubkey(), true),
+                    solana_program::instruction::AccountMeta::new(sender, false),
+                    solana_program::instruction::AccountMeta::new(recipient, false),
+                    solana_program::instruction::AccountMeta::new(Leaderboard::find_address(&crate::id()).0, false),
+                    solana_program::instruction::AccountMeta::new_readonly(SeasonClock::find_address(&crate::id()).0, false),
+                ],
+            )
+        };
+        
+        let mut transaction = Transaction::new_with_payer(
+            &[transfer_instruction(recipient, 300)],
+            Some(&payer.pubkey()),
+        );
+        transaction.sign(&[&payer, &sender_owner], recent_blockhash);
+        banks_client.process_transaction(transaction).await.unwrap();
+        
+        let sender_account = banks_client.get_account(sender).await.unwrap().unwrap();
+        let sender_wallet = PlayerWallet::load(&sender_account.data).unwrap();
+        assert_eq!(sender_account.lamports, rent_exempt_minimum + 700);
+        assert_eq!(sender_wallet.sol_balance, 700);
+        assert_eq!(sender_wallet.nb_transactions, 1);
+        
+        let recipient_account = banks_client.get_account(recipient).await.unwrap().unwrap();
+        let recipient_wallet = PlayerWallet::load(&recipient_account.data).unwrap();
+        assert_eq!(recipient_account.lamports, rent_exempt_minimum + 300);
+        assert_eq!(recipient_wallet.sol_balance, 300);
+        assert_eq!(recipient_wallet.nb_transactions, 1);
+        
+        // Empty transfers, overdrafts and frozen recipients are rejected
+        let mut transaction = Transaction::new_with_payer(
+            &[transfer_instruction(recipient, 0)],
+            Some(&payer.pubkey()),
+        );
+        transaction.sign(&[&payer, &sender_owner], recent_blockhash);
+        assert!(banks_client.process_transaction(transaction).await.is_err());
+        
+        let mut transaction = Transaction::new_with_payer(
+            &[transfer_instruction(recipient, 701)],
+            Some(&payer.pubkey()),
+        );
+        transaction.sign(&[&payer, &sender_owner], recent_blockhash);
+        assert!(banks_client.process_transaction(transaction).await.is_err());
+        
+        let mut transaction = Transaction::new_with_payer(
+            &[transfer_instruction(frozen, 100)],
+            Some(&payer.pubkey()),
+        );
+        transaction.sign(&[&payer, &sender_owner], recent_blockhash);
+        assert!(banks_client.process_transaction(transaction).await.is_err());
+    }
+    
+    #[tokio::test]
+    async fn test_mint_reward() {
+        let mut program_test = program_test();
+        let (mint, _) = CurrencyConfig::find_mint_address(&crate::id());
+        let currency_config_address = CurrencyConfig::find_address(&crate::id()).0;
+        
+        // A player already tracking the game currency
+        let owner = Pubkey::new_unique();
+        let player_wallet = PlayerWallet::new("Rewarded", owner).unwrap();
+        let player_wallet_address = add_player_wallet(&mut program_test, &player_wallet);
+        let (holdings_address, holdings_bump) = Holdings::find_address(&player_wallet_address, &crate::id());
+        add_state_account(
+            &mut program_test,
+            holdings_address,
+            &Holdings {
+                is_initialized: true,
+                player_wallet: player_wallet_address,
+                bump: holdings_bump,
+                entries: vec![Holding { mint, amount: 0, decimals: 2, last_price: 0, vault_amount: 0, token_program: spl_token::id() }],
+            },
+        );
+        let token_account = spl_associated_token_account::get_associated_token_address(&owner, &mint);
+        add_token_account(&mut program_test, token_account, mint, owner, 0);
+        
+        // A board by total tokens, ranking the player as rewards arrive
+        let leaderboard_address = Leaderboard::find_address(&crate::id()).0;
+        let mut data = pack_account(&Leaderboard {
+            is_initialized: true,
+            metric: WalletMetric::TotalTokens,
+            size: 2,
+            bump: 255,
+            entries: Vec::new(),
+        }).unwrap();
+        data.resize(Leaderboard::LEN, 0);
+        program_test.add_account(
+            leaderboard_address,
+            Account {
+                lamports: Rent::default().minimum_balance(data.len()),
+                data,
+                owner: crate::id(),
+                ..Account::default()
+            },
+        );
+        
+        let mut context = program_test.start_with_context().await;
+        initialize_program(&mut context).await;
+        
+        // 21 = InitializeCurrency instruction, capped at 1,000 per epoch
+        let updater = Keypair::new();
+        let mut instruction_data = vec![21];
+        instruction_data.extend((2u8, updater.pubkey(), 1_000u64).try_to_vec().unwrap());
+        let mut transaction = Transaction::new_with_payer(
+            &[solana_program::instruction::Instruction::new_with_bytes(
+                crate::id(),
+                &instruction_data,
+                vec![
+                    solana_program::instruction::AccountMeta::new(context.payer.pubkey(), true),
+                    solana_program::instruction::AccountMeta::new_readonly(ProgramConfig::find_address(&crate::id()).0, false),
+                    solana_program::instruction::AccountMeta::new(currency_config_address, false),
+                    solana_program::instruction::AccountMeta::new(mint, false),
+                    solana_program::instruction::AccountMeta::new_readonly(spl_token::id(), false),
+                    solana_program::instruction::AccountMeta::new_readonly(solana_program::system_program::id(), false),
+                ],
+            )],
+            Some(&context.payer.pubkey()),
+        );
+        transaction.sign(&[&context.payer], context.last_blockhash);
+        context.banks_client.process_transaction(transaction).await.unwrap();
+        
+        let mint_account = context.banks_client.get_account(mint).await.unwrap().unwrap();
+        let mint_state = spl_token::state::Mint::unpack(&mint_account.data).unwrap();
+        assert_eq!(mint_state.mint_authority, Some(currency_config_address).into());
+        assert_eq!(mint_state.decimals, 2);
+        
+        // 23 = MintReward instruction
+        let mint_reward_instruction = |amount: u64| {
+            let mut instruction_data = vec![23];
+            instruction_data.extend(amount.try_to_vec().unwrap());
+            solana_program::instruction::Instruction::new_with_bytes(
+                crate::id(),
+                &instruction_data,
+                vec![
+                    solana_program::instruction::AccountMeta::new_readonly(updater.pubkey(), true),
+                    solana_program::instruction::AccountMeta::new(currency_config_address, false),
+                    solana_program::instruction::AccountMeta::new(mint, false),
+                    solana_program::instruction::AccountMeta::new(player_wallet_address, false),
+                    solana_program::instruction::AccountMeta::new(holdings_address, false),
+                    solana_program::instruction::AccountMeta::new(token_account, false),
+                    solana_program::instruction::AccountMeta::new_readonly(spl_token::id(), false),
+                    solana_program::instruction::AccountMeta::new(leaderboard_address, false),
+                    solana_program::instruction::AccountMeta::new_readonly(SeasonClock::find_address(&crate::id()).0, false),
+                ],
+            )
+        };
+        let mut transaction = Transaction::new_with_payer(&[mint_reward_instruction(600)], Some(&context.payer.pubkey()));
+        transaction.sign(&[&context.payer, &updater], context.last_blockhash);
+        context.banks_client.process_transaction(transaction).await.unwrap();
+        
+        let token_account_data = context.banks_client.get_account(token_account).await.unwrap().unwrap();
+        assert_eq!(spl_token::state::Account::unpack(&token_account_data.data).unwrap().amount, 600);
+        let player_wallet_account = context.banks_client.get_account(player_wallet_address).await.unwrap().unwrap();
+        assert_eq!(PlayerWallet::load(&player_wallet_account.data).unwrap().amount_total_tokens, 600);
+        let leaderboard_account = context.banks_client.get_account(leaderboard_address).await.unwrap().unwrap();
+        let leaderboard = unpack_account::<Leaderboard>(&leaderboard_account.data).unwrap();
+        assert_eq!(leaderboard.entries[0].player_wallet, player_wallet_address);
+        assert_eq!(leaderboard.entries[0].score, 600);
+        
+        // The per-epoch cap stops further issuance
+        let mut transaction = Transaction::new_with_payer(&[mint_reward_instruction(401)], Some(&context.payer.pubkey()));
+        transaction.sign(&[&context.payer, &updater], context.last_blockhash);
+        assert!(context.banks_client.process_transaction(transaction).await.is_err());
+        
+        // Only the updater can mint
+        let mut transaction = Transaction::new_with_payer(
+            &[solana_program::instruction::Instruction::new_with_bytes(
+                crate::id(),
+                &[23, 1, 0, 0, 0, 0, 0, 0, 0],
+                vec![
+                    solana_program::instruction::AccountMeta::new_readonly(context.payer.pubkey(), true),
+                    solana_program::instruction::AccountMeta::new(currency_config_address, false),
+                    solana_program::instruction::AccountMeta::new(mint, false),
+                    solana_program::instruction::AccountMeta::new(player_wallet_address, false),
+                    solana_program::instruction::AccountMeta::new(holdings_address, false),
+                    solana_program::instruction::AccountMeta::new(token_account, false),
+                    solana_program::instruction::AccountMeta::new_readonly(spl_token::id(), false),
+                ],
+            )],
+            Some(&context.payer.pubkey()),
+        );
+        transaction.sign(&[&context.payer], context.last_blockhash);
+        assert!(context.banks_client.process_transaction(transaction).await.is_err());
+    }
+    
+    #[test]
+    fn test_currency_issuance_cap() {
+        let mut currency_config = CurrencyConfig {
+            is_initialized: true,
+            mint: Pubkey::new_unique(),
+            updater: Pubkey::new_unique(),
+            epoch_issuance_cap: 100,
+            current_epoch: 0,
+            issued_this_epoch: 0,
+            bump: 255,
+        };
+        
+        currency_config.record_issuance(5, 60).unwrap();
+        currency_config.record_issuance(5, 40).unwrap();
+        assert_eq!(
+            currency_config.record_issuance(5, 1).err(),
+            Some(crate::error::PlayerWalletError::IssuanceCapExceeded.into()),
+        );
+        
+        // A new epoch starts a new tally
+        currency_config.record_issuance(6, 100).unwrap();
+        assert_eq!(currency_config.current_epoch, 6);
+        assert_eq!(currency_config.issued_this_epoch, 100);
+    }
+    
+    #[tokio::test]
+    async fn test_claim_from_distributor() {
+        let mut program_test = program_test();
+        
+        let owner = Keypair::new();
+        let player_wallet = PlayerWallet::new("Claimer", owner.pubkey()).unwrap();
+        let player_wallet_address = add_player_wallet(&mut program_test, &player_wallet);
+        
+        let mint = Pubkey::new_unique();
+        add_mint(&mut program_test, mint, Some(Pubkey::new_unique()), 0, 10_000);
+        let (holdings_address, holdings_bump) = Holdings::find_address(&player_wallet_address, &crate::id());
+        add_state_account(
+            &mut program_test,
+            holdings_address,
+            &Holdings {
+                is_initialized: true,
+                player_wallet: player_wallet_address,
+                bump: holdings_bump,
+                entries: vec![Holding { mint, amount: 0, decimals: 0, last_price: 0, vault_amount: 0, token_program: spl_token::id() }],
+            },
+        );
+        let token_account = spl_associated_token_account::get_associated_token_address(&owner.pubkey(), &mint);
+        add_token_account(&mut program_test, token_account, mint, owner.pubkey(), 0);
+        
+        // A funded distribution where the player is the third of five claims
+        let claims: Vec<ClaimLeaf> = (0..5)
+            .map(|index| ClaimLeaf {
+                index,
+                player_wallet: if index == 2 { player_wallet_address } else { Pubkey::new_unique() },
+                amount: 100 * (index + 1),
+            })
+            .collect();
+        let tree = MerkleTree::new(&claims);
+        let (distributor_address, distributor_bump) = Distributor::find_address(7, &crate::id());
+        add_state_account(
+            &mut program_test,
+            distributor_address,
+            &Distributor {
+                is_initialized: true,
+                id: 7,
+                mint,
+                merkle_root: tree.root(),
+                num_claims: 5,
+                total_claimed: 0,
+                bump: distributor_bump,
+                claimed_bitmap: vec![0; Distributor::bitmap_len(5)],
+            },
+        );
+        let vault = spl_associated_token_account::get_associated_token_address(&distributor_address, &mint);
+        add_token_account(&mut program_test, vault, mint, distributor_address, 10_000);
+        
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+        
+        // 25 = Claim instruction
+        let claim_instruction = |amount: u64| {
+            let mut instruction_data = vec![25];
+            instruction_data.extend((2u64, amount, tree.proof(2).unwrap()).try_to_vec().unwrap());
+            solana_program::instruction::Instruction::new_with_bytes(
+                crate::id(),
+                &instruction_data,
+                vec![
+                    solana_program::instruction::AccountMeta::new_readonly(owner.pubkey(), true),
+                    solana_program::instruction::AccountMeta::new(player_wallet_address, false),
+                    solana_program::instruction::AccountMeta::new(holdings_address, false),
+                    solana_program::instruction::AccountMeta::new(distributor_address, false),
+                    solana_program::instruction::AccountMeta::new_readonly(mint, false),
+                    solana_program::instruction::AccountMeta::new(vault, false),
+                    solana_program::instruction::AccountMeta::new(token_account, false),
+                    solana_program::instruction::AccountMeta::new_readonly(spl_token::id(), false),
+                    solana_program::instruction::AccountMeta::new(Leaderboard::find_address(&crate::id()).0, false),
+                    solana_program::instruction::AccountMeta::new_readonly(SeasonClock::find_address(&crate::id()).0, false),
+                ],
+            )
+        };
+        
+        // A proof for a different amount is rejected
+        let mut transaction = Transaction::new_with_payer(&[claim_instruction(3_000)], Some(&payer.pubkey()));
+        transaction.sign(&[&payer, &owner], recent_blockhash);
+        assert!(banks_client.process_transaction(transaction).await.is_err());
+        
+        let mut transaction = Transaction::new_with_payer(&[claim_instruction(300)], Some(&payer.pubkey()));
+        transaction.sign(&[&payer, &owner], recent_blockhash);
+        banks_client.process_transaction(transaction).await.unwrap();
+        
+        let token_account_data = banks_client.get_account(token_account).await.unwrap().unwrap();
+        assert_eq!(spl_token::state::Account::unpack(&token_account_data.data).unwrap().amount, 300);
+        let player_wallet_account = banks_client.get_account(player_wallet_address).await.unwrap().unwrap();
+        assert_eq!(PlayerWallet::load(&player_wallet_account.data).unwrap().amount_total_tokens, 300);
+        let distributor_account = banks_client.get_account(distributor_address).await.unwrap().unwrap();
+        let distributor = unpack_account::<Distributor>(&distributor_account.data).unwrap();
+        assert!(distributor.is_claimed(2));
+        assert_eq!(distributor.total_claimed, 300);
+        
+        // The same claim cannot be paid twice
+        let recent_blockhash = banks_client.get_new_latest_blockhash(&recent_blockhash).await.unwrap();
+        let mut transaction = Transaction::new_with_payer(&[claim_instruction(300)], Some(&payer.pubkey()));
+        transaction.sign(&[&payer, &owner], recent_blockhash);
+        assert!(banks_client.process_transaction(transaction).await.is_err());
+    }
+    
+    #[test]
+    fn test_merkle_tree() {
+        let claims: Vec<ClaimLeaf> = (0..7)
+            .map(|index| ClaimLeaf { index, player_wallet: Pubkey::new_unique(), amount: index * 10 })
+            .collect();
+        let tree = MerkleTree::new(&claims);
+        let root = tree.root();
+        
+        // Every claim proves against the root, and only with its own data
+        for claim in &claims {
+            let proof = tree.proof(claim.index as usize).unwrap();
+            assert!(merkle::verify(&proof, &root, merkle::hash_leaf(claim.index, &claim.player_wallet, claim.amount)));
+            assert!(!merkle::verify(&proof, &root, merkle::hash_leaf(claim.index, &claim.player_wallet, claim.amount + 1)));
+            assert!(!merkle::verify(&proof, &root, merkle::hash_leaf(claim.index + 1, &claim.player_wallet, claim.amount)));
+        }
+        assert!(tree.proof(7).is_none());
+        
+        // A single claim is its own root, and an empty tree has a zero root
+        let single = MerkleTree::new(&claims[..1]);
+        assert_eq!(single.proof(0).unwrap(), Vec::<[u8; 32]>::new());
+        assert!(merkle::verify(&[], &single.root(), merkle::hash_leaf(0, &claims[0].player_wallet, 0)));
+        assert_eq!(MerkleTree::new(&[]).root(), [0; 32]);
+    }
+    
+    #[test]
+    fn test_distributor_claimed_bitmap() {
+        let mut distributor = Distributor {
+            is_initialized: true,
+            id: 0,
+            mint: Pubkey::new_unique(),
+            merkle_root: [0; 32],
+            num_claims: 10,
+            total_claimed: 0,
+            bump: 255,
+            claimed_bitmap: vec![0; Distributor::bitmap_len(10)],
+        };
+        assert_eq!(distributor.claimed_bitmap.len(), 2);
+        
+        distributor.set_claimed(9).unwrap();
+        assert!(distributor.is_claimed(9));
+        assert!(!distributor.is_claimed(8));
+        assert_eq!(
+            distributor.set_claimed(9).err(),
+            Some(crate::error::PlayerWalletError::AlreadyClaimed.into()),
+        );
+        assert!(distributor.set_claimed(10).is_err());
+        assert_eq!(
+            pack_account(&distributor).unwrap().len(),
+            Distributor::get_account_size(10),
+        );
+    }
+    
+    #[tokio::test]
+    async fn test_staking() {
+        let mut program_test = program_test();
+        let (mint, _) = CurrencyConfig::find_mint_address(&crate::id());
+        let (currency_config_address, currency_config_bump) = CurrencyConfig::find_address(&crate::id());
+        let (staking_config_address, staking_config_bump) = StakingConfig::find_address(&crate::id());
+        
+        // Game currency minted by the program, earning 1 per second per 1,000 staked,
+        // with 100 of the 250 issuable in epoch 0 already minted
+        add_mint(&mut program_test, mint, Some(currency_config_address), 0, 1_000);
+        add_state_account(
+            &mut program_test,
+            currency_config_address,
+            &CurrencyConfig {
+                is_initialized: true,
+                mint,
+                updater: Pubkey::new_unique(),
+                epoch_issuance_cap: 250,
+                current_epoch: 0,
+                issued_this_epoch: 100,
+                bump: currency_config_bump,
+            },
+        );
+        add_state_account(
+            &mut program_test,
+            staking_config_address,
+            &StakingConfig {
+                is_initialized: true,
+                reward_rate: 1_000_000,
+                lock_period_seconds: 100,
+                bump: staking_config_bump,
+            },
+        );
+        
+        // A player with 1,000 game currency in their vault
+        let owner = Keypair::new();
+        program_test.add_account(
+            owner.pubkey(),
+            Account { lamports: 1_000_000_000, ..Account::default() },
+        );
+        let player_wallet = PlayerWallet::new("Staker", owner.pubkey()).unwrap();
+        let player_wallet_address = add_player_wallet(&mut program_test, &player_wallet);
+        let (holdings_address, holdings_bump) = Holdings::find_address(&player_wallet_address, &crate::id());
+        add_state_account(
+            &mut program_test,
+            holdings_address,
+            &Holdings {
+                is_initialized: true,
+                player_wallet: player_wallet_address,
+                bump: holdings_bump,
+                entries: vec![Holding { mint, amount: 0, decimals: 0, last_price: 0, vault_amount: 1_000, token_program: spl_token::id() }],
+            },
+        );
+        let vault_authority = find_vault_authority_address(&player_wallet_address, &crate::id()).0;
+        let vault_token_account = spl_associated_token_account::get_associated_token_address(&vault_authority, &mint);
+        add_token_account(&mut program_test, vault_token_account, mint, vault_authority, 1_000);
+        let owner_token_account = spl_associated_token_account::get_associated_token_address(&owner.pubkey(), &mint);
+        add_token_account(&mut program_test, owner_token_account, mint, owner.pubkey(), 0);
+        let stake_position_address = StakePosition::find_address(&player_wallet_address, &crate::id()).0;
+
+        
+        let mut context = program_test.start_with_context().await;
+        
+        let stake_instruction = |tag: u8, amount: u64| {
+            let mut instruction_data = vec![tag];
+            instruction_data.extend(amount.try_to_vec().unwrap());
+            let accounts = if tag == 27 {
+                vec![
+                    solana_program::instruction::AccountMeta::new(owner.pubkey(), true),
+                    solana_program::instruction::AccountMeta::new_readonly(player_wallet_address, false),
+                    solana_program::instruction::AccountMeta::new_readonly(currency_config_address, false),
+                    solana_program::instruction::AccountMeta::new_readonly(staking_config_address, false),
+                    solana_program::instruction::AccountMeta::new(stake_position_address, false),
+                    solana_program::instruction::AccountMeta::new_readonly(vault_token_account, false),
+                    solana_program::instruction::AccountMeta::new_readonly(solana_program::system_program::id(), false),
+                ]
+            } else {
+                vec![
+                    solana_program::instruction::AccountMeta::new_readonly(owner.pubkey(), true),
+                    solana_program::instruction::AccountMeta::new_readonly(player_wallet_address, false),
+                    solana_program::instruction::AccountMeta::new_readonly(staking_config_address, false),
+                    solana_program::instruction::AccountMeta::new(stake_position_address, false),
+                ]
+            };
+            solana_program::instruction::Instruction::new_with_bytes(crate::id(), &instruction_data, accounts)
+        };
+        let withdraw_instruction = |amount: u64| {
+            let mut instruction_data = vec![17];
+            instruction_data.extend(amount.try_to_vec().unwrap());
+            solana_program::instruction::Instruction::new_with_bytes(
+                crate::id(),
+                &instruction_data,
+                vec![
+                    solana_program::instruction::AccountMeta::new_readonly(owner.pubkey(), true),
+                    solana_program::instruction::AccountMeta::new(player_wallet_address, false),
+                    solana_program::instruction::AccountMeta::new(holdings_address, false),
+                    solana_program::instruction::AccountMeta::new_readonly(mint, false),
+                    solana_program::instruction::AccountMeta::new(owner_token_account, false),
+                    solana_program::instruction::AccountMeta::new_readonly(vault_authority, false),
+                    solana_program::instruction::AccountMeta::new(vault_token_account, false),
+                    solana_program::instruction::AccountMeta::new_readonly(spl_token::id(), false),
+                    solana_program::instruction::AccountMeta::new_readonly(stake_position_address, false),
+                    solana_program::instruction::AccountMeta::new(Leaderboard::find_address(&crate::id()).0, false),
+                    solana_program::instruction::AccountMeta::new_readonly(SeasonClock::find_address(&crate::id()).0, false),
+                ],
+            )
+        };
+        // 29 = ClaimStakingRewards instruction
+        let claim_instruction = solana_program::instruction::Instruction::new_with_bytes(
+            crate::id(),
+            &[29],
+            vec![
+                solana_program::instruction::AccountMeta::new_readonly(owner.pubkey(), true),
+                solana_program::instruction::AccountMeta::new(player_wallet_address, false),
+                solana_program::instruction::AccountMeta::new(holdings_address, false),
+                solana_program::instruction::AccountMeta::new(currency_config_address, false),
+                solana_program::instruction::AccountMeta::new(mint, false),
+                solana_program::instruction::AccountMeta::new_readonly(staking_config_address, false),
+                solana_program::instruction::AccountMeta::new(stake_position_address, false),
+                solana_program::instruction::AccountMeta::new(vault_token_account, false),
+                solana_program::instruction::AccountMeta::new_readonly(spl_token::id(), false),
+                solana_program::instruction::AccountMeta::new(Leaderboard::find_address(&crate::id()).0, false),
+                solana_program::instruction::AccountMeta::new_readonly(SeasonClock::find_address(&crate::id()).0, false),
+            ],
+        );
+        
+        // 27 = Stake instruction, limited to the vault balance
+        for (instruction, succeeds) in [
+            (stake_instruction(27, 1_001), false),
+            (stake_instruction(27, 1_000), true),
+            (withdraw_instruction(1), false),
+            (stake_instruction(28, 1_000), false),
+            (claim_instruction.clone(), false),
+        ] {
+            let mut transaction = Transaction::new_with_payer(&[instruction], Some(&context.payer.pubkey()));
+            transaction.sign(&[&context.payer, &owner], context.last_blockhash);
+            assert_eq!(context.banks_client.process_transaction(transaction).await.is_ok(), succeeds);
+        }
+        
+        // Once the lock period is over, 200 seconds of rewards can be claimed,
+        // but not beyond the issuance cap of epoch 0
+        let stake_position_account = context.banks_client.get_account(stake_position_address).await.unwrap().unwrap();
+        let stake_position = unpack_account::<StakePosition>(&stake_position_account.data).unwrap();
+        let mut clock: solana_program::clock::Clock = context.banks_client.get_sysvar().await.unwrap();
+        clock.unix_timestamp = stake_position.last_update_time + 200;
+        for (epoch, succeeds) in [(0, false), (1, true)] {
+            clock.epoch = epoch;
+            context.last_blockhash = context.banks_client
+                .get_new_latest_blockhash(&context.last_blockhash)
+                .await
+                .unwrap();
+            context.set_sysvar(&clock);
+            let mut transaction = Transaction::new_with_payer(&[claim_instruction.clone()], Some(&context.payer.pubkey()));
+            transaction.sign(&[&context.payer, &owner], context.last_blockhash);
+            assert_eq!(context.banks_client.process_transaction(transaction).await.is_ok(), succeeds);
+        }
+        
+        // The claim was tallied in the new epoch
+        let currency_config_account = context.banks_client.get_account(currency_config_address).await.unwrap().unwrap();
+        let currency_config = unpack_account::<CurrencyConfig>(&currency_config_account.data).unwrap();
+        assert_eq!((currency_config.current_epoch, currency_config.issued_this_epoch), (1, 200));
+        
+        let vault_account = context.banks_client.get_account(vault_token_account).await.unwrap().unwrap();
+        assert_eq!(spl_token::state::Account::unpack(&vault_account.data).unwrap().amount, 1_200);
+        let player_wallet_account = context.banks_client.get_account(player_wallet_address).await.unwrap().unwrap();
+        assert_eq!(PlayerWallet::load(&player_wallet_account.data).unwrap().amount_total_tokens, 1_200);
+        
+        // Rewards are free to withdraw, and the stake once unstaked
+        for (instruction, succeeds) in [
+            (withdraw_instruction(201), false),
+            (withdraw_instruction(200), true),
+            (stake_instruction(28, 1_000), true),
+            (withdraw_instruction(1_000), true),
+        ] {
+            let mut transaction = Transaction::new_with_payer(&[instruction], Some(&context.payer.pubkey()));
+            transaction.sign(&[&context.payer, &owner], context.last_blockhash);
+            assert_eq!(context.banks_client.process_transaction(transaction).await.is_ok(), succeeds);
+        }
+        
+        let stake_position_account = context.banks_client.get_account(stake_position_address).await.unwrap().unwrap();
+        let stake_position = unpack_account::<StakePosition>(&stake_position_account.data).unwrap();
+        assert_eq!(stake_position.amount, 0);
+        assert_eq!(stake_position.pending_rewards, 0);
+    }
+    
+    #[test]
+    fn test_stake_position_accrual() {
+        let mut stake_position = StakePosition {
+            is_initialized: true,
+            player_wallet: Pubkey::new_unique(),
+            mint: Pubkey::new_unique(),
+            amount: 2_000_000_000,
+            pending_rewards: 5,
+            last_update_time: 1_000,
+            locked_until: 1_100,
+            bump: 255,
+        };
+        
+        // 0.5 per second per staked whole unit over 10 seconds
+        stake_position.accrue(1_010, 500_000_000).unwrap();
+        assert_eq!(stake_position.pending_rewards, 5 + 10_000_000_000);
+        assert_eq!(stake_position.last_update_time, 1_010);
+        
+        // Time never runs backwards
+        stake_position.accrue(1_000, 500_000_000).unwrap();
+        stake_position.accrue(1_010, 500_000_000).unwrap();
+        assert_eq!(stake_position.pending_rewards, 5 + 10_000_000_000);
+        
+        assert!(!stake_position.is_unlocked(1_099));
+        assert!(stake_position.is_unlocked(1_100));
+    }
+    
+    #[tokio::test]
+    async fn test_inventory() {
+        let mut program_test = program_test();
+        
+        let owner = Keypair::new();
+        let player_wallet = PlayerWallet::new("Collector", owner.pubkey()).unwrap();
+        let player_wallet_address = add_player_wallet(&mut program_test, &player_wallet);
+        let inventory_address = Inventory::find_address(&player_wallet_address, &crate::id()).0;
+        
+        // A second profile of the same owner
+        let second_wallet_address = add_player_wallet(&mut program_test, &PlayerWallet::new("Alt", owner.pubkey()).unwrap());
+        let second_inventory_address = Inventory::find_address(&second_wallet_address, &crate::id()).0;
+        
+        // Enough items to outgrow the first capacity increment, all held by the owner
+        let items: Vec<(Pubkey, Pubkey)> = (0..INVENTORY_CAPACITY_INCREMENT + 1)
+            .map(|_| (Pubkey::new_unique(), Pubkey::new_unique()))
+            .collect();
+        for (mint, token_account) in &items {
+            add_mint(&mut program_test, *mint, None, 0, 1);
+            add_token_account(&mut program_test, *token_account, *mint, owner.pubkey(), 1);
+        }
+        
+        // A fungible token is not an item
+        let coin_mint = Pubkey::new_unique();
+        let coin_account = Pubkey::new_unique();
+        add_mint(&mut program_test, coin_mint, Some(Pubkey::new_unique()), 6, 1_000);
+        add_token_account(&mut program_test, coin_account, coin_mint, owner.pubkey(), 1_000);
+        
+        // An item held by someone else
+        let other_mint = Pubkey::new_unique();
+        let other_account = Pubkey::new_unique();
+        add_mint(&mut program_test, other_mint, None, 0, 1);
+        add_token_account(&mut program_test, other_account, other_mint, Pubkey::new_unique(), 1);
+        
+        // A mint that can still issue more units is not an item
+        let open_mint = Pubkey::new_unique();
+        let open_account = Pubkey::new_unique();
+        add_mint(&mut program_test, open_mint, Some(Pubkey::new_unique()), 0, 1);
+        add_token_account(&mut program_test, open_account, open_mint, owner.pubkey(), 1);
+        
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+        
+        // Register an item in `player_wallet`, giving the inventory of the wallet it was registered to
+        let register_to = |player_wallet: Pubkey, previous_wallet: Pubkey, mint: Pubkey, token_account: Pubkey, slot: u8| {
+            // 30 = RegisterItem instruction
+            solana_program::instruction::Instruction::new_with_bytes(
+                crate::id(),
+                &[30, slot],
+                vec![
+                    solana_program::instruction::AccountMeta::new(payer.pubkey(), true),
+                    solana_program::instruction::AccountMeta::new_readonly(owner.pubkey(), true),
+                    solana_program::instruction::AccountMeta::new_readonly(player_wallet, false),
+                    solana_program::instruction::AccountMeta::new(Inventory::find_address(&player_wallet, &crate::id()).0, false),
+                    solana_program::instruction::AccountMeta::new_readonly(mint, false),
+                    solana_program::instruction::AccountMeta::new_readonly(token_account, false),
+                    solana_program::instruction::AccountMeta::new_readonly(solana_program::system_program::id(), false),
+                    solana_program::instruction::AccountMeta::new(ItemRegistration::find_address(&mint, &crate::id()).0, false),
+                    solana_program::instruction::AccountMeta::new(Inventory::find_address(&previous_wallet, &crate::id()).0, false),
+                ],
+            )
+        };
+        let register = |mint: Pubkey, token_account: Pubkey, slot: u8| {
+            register_to(player_wallet_address, player_wallet_address, mint, token_account, slot)
+        };
+        let equip = |tag: u8, mint: Pubkey, token_account: Option<Pubkey>| {
+            let mut instruction_data = vec![tag];
+            instruction_data.extend(mint.try_to_vec().unwrap());
+            let mut accounts = vec![
+                solana_program::instruction::AccountMeta::new_readonly(owner.pubkey(), true),
+                solana_program::instruction::AccountMeta::new_readonly(player_wallet_address, false),
+                solana_program::instruction::AccountMeta::new(inventory_address, false),
+            ];
+            accounts.extend(token_account.map(solana_program::instruction::AccountMeta::new_readonly));
+            solana_program::instruction::Instruction::new_with_bytes(crate::id(), &instruction_data, accounts)
+        };
+        let send = |instruction: solana_program::instruction::Instruction| {
+            let mut transaction = Transaction::new_with_payer(&[instruction], Some(&payer.pubkey()));
+            transaction.sign(&[&payer, &owner], recent_blockhash);
+            transaction
+        };
+        
+        // The first item creates the inventory, the ninth grows it
+        for (i, (mint, token_account)) in items.iter().enumerate() {
+            banks_client.process_transaction(send(register(*mint, *token_account, i as u8 % 2))).await.unwrap();
+            
+            let account = banks_client.get_account(inventory_address).await.unwrap().unwrap();
+            let inventory = unpack_account::<Inventory>(&account.data).unwrap();
+            assert_eq!(inventory.items.len(), i + 1);
+            let capacity = if i < INVENTORY_CAPACITY_INCREMENT { INVENTORY_CAPACITY_INCREMENT } else { 2 * INVENTORY_CAPACITY_INCREMENT };
+            assert_eq!(inventory.capacity as usize, capacity);
+            assert_eq!(account.data.len(), Inventory::get_account_size(capacity));
+        }
+        
+        // Registering twice, fungible tokens, mintable tokens and items held by others all fail
+        let (mint_a, account_a) = items[0];
+        assert!(banks_client.process_transaction(send(register(mint_a, account_a, 1))).await.is_err());
+        assert!(banks_client.process_transaction(send(register(coin_mint, coin_account, 0))).await.is_err());
+        assert!(banks_client.process_transaction(send(register(open_mint, open_account, 0))).await.is_err());
+        assert!(banks_client.process_transaction(send(register(other_mint, other_account, 0))).await.is_err());
+        
+        // Equipping an item swaps out the item in the same slot
+        let (mint_b, account_b) = items[2];
+        banks_client.process_transaction(send(equip(31, mint_a, Some(account_a)))).await.unwrap();
+        banks_client.process_transaction(send(equip(31, mint_b, Some(account_b)))).await.unwrap();
+        let account = banks_client.get_account(inventory_address).await.unwrap().unwrap();
+        let inventory = unpack_account::<Inventory>(&account.data).unwrap();
+        assert!(!inventory.items[0].equipped);
+        assert!(inventory.items[2].equipped);
+        
+        // The token account must prove the item is still held
+        assert!(banks_client.process_transaction(send(equip(31, mint_a, Some(account_b)))).await.is_err());
+        
+        banks_client.process_transaction(send(equip(32, mint_b, None))).await.unwrap();
+        let account = banks_client.get_account(inventory_address).await.unwrap().unwrap();
+        let inventory = unpack_account::<Inventory>(&account.data).unwrap();
+        assert!(inventory.items.iter().all(|item| !item.equipped));
+        
+        // Registering the item in another profile moves it out of the first one
+        let instruction = register_to(second_wallet_address, player_wallet_address, mint_a, account_a, 0);
+        banks_client.process_transaction(send(instruction)).await.unwrap();
+        let account = banks_client.get_account(inventory_address).await.unwrap().unwrap();
+        let inventory = unpack_account::<Inventory>(&account.data).unwrap();
+        assert!(inventory.items.iter().all(|item| item.mint != mint_a));
+        let account = banks_client.get_account(second_inventory_address).await.unwrap().unwrap();
+        let inventory = unpack_account::<Inventory>(&account.data).unwrap();
+        assert_eq!(inventory.items[0].mint, mint_a);
+        
+        let registration_address = ItemRegistration::find_address(&mint_a, &crate::id()).0;
+        let account = banks_client.get_account(registration_address).await.unwrap().unwrap();
+        let registration = unpack_account::<ItemRegistration>(&account.data).unwrap();
+        assert_eq!(registration.player_wallet, second_wallet_address);
+        
+        // The inventory the item was registered to must be given
+        let instruction = register_to(player_wallet_address, player_wallet_address, mint_a, account_a, 0);
+        assert!(banks_client.process_transaction(send(instruction)).await.is_err());
+    }
+    
+    #[test]
+    fn test_inventory_slots() {
+        let mut inventory = Inventory {
+            is_initialized: true,
+            player_wallet: Pubkey::new_unique(),
+            bump: 255,
+            capacity: 0,
+            items: Vec::new(),
+        };
+        let helmet = Pubkey::new_unique();
+        let crown = Pubkey::new_unique();
+        let sword = Pubkey::new_unique();
+        
+        // Capacity grows by whole increments
+        assert!(inventory.add(InventoryItem { mint: helmet, slot: 0, equipped: false, usable_until: 0 }).unwrap());
+        assert!(!inventory.add(InventoryItem { mint: crown, slot: 0, equipped: false, usable_until: 0 }).unwrap());
+        assert!(!inventory.add(InventoryItem { mint: sword, slot: 1, equipped: false, usable_until: 0 }).unwrap());
+        assert_eq!(inventory.capacity as usize, INVENTORY_CAPACITY_INCREMENT);
+        assert!(inventory.add(InventoryItem { mint: sword, slot: 1, equipped: false, usable_until: 0 }).is_err());
+        
+        // One item per slot
+        inventory.equip(&helmet).unwrap();
+        inventory.equip(&sword).unwrap();
+        inventory.equip(&crown).unwrap();
+        let equipped: Vec<Pubkey> = inventory.items.iter().filter(|item| item.equipped).map(|item| item.mint).collect();
+        assert_eq!(equipped, vec![crown, sword]);
+        
+        inventory.unequip(&crown).unwrap();
+        assert!(inventory.equip(&Pubkey::new_unique()).is_err());
+        assert_eq!(inventory.items.iter().filter(|item| item.equipped).count(), 1);
+        
+        // Removing an item keeps the capacity
+        assert_eq!(inventory.remove(&sword).unwrap().mint, sword);
+        assert!(inventory.remove(&sword).is_err());
+        assert_eq!(inventory.items.len(), 2);
+        assert_eq!(inventory.capacity as usize, INVENTORY_CAPACITY_INCREMENT);
+    }
+    
+    #[tokio::test]
+    async fn test_marketplace() {
+        let mut program_test = program_test();
+        let (currency_config_address, currency_mint) = add_currency(&mut program_test);
+        
+        // A 5% fee
+        let (marketplace_config_address, fee_account) = add_marketplace_config(&mut program_test, 500);
+        
+        // The seller holds the item, the buyer tracks the game currency
+        let seller = Keypair::new();
+        let seller_wallet_address = add_player_wallet(&mut program_test, &PlayerWallet::new("Seller", seller.pubkey()).unwrap());
+        let buyer = Keypair::new();
+        let buyer_wallet_address = add_player_wallet(&mut program_test, &PlayerWallet::new("Buyer", buyer.pubkey()).unwrap());
+        for owner in [&seller, &buyer] {
+            program_test.add_account(owner.pubkey(), Account { lamports: 1_000_000_000, ..Account::default() });
+        }
+        let (buyer_holdings_address, buyer_holdings_bump) = Holdings::find_address(&buyer_wallet_address, &crate::id());
+        add_state_account(
+            &mut program_test,
+            buyer_holdings_address,
+            &Holdings {
+                is_initialized: true,
+                player_wallet: buyer_wallet_address,
+                bump: buyer_holdings_bump,
+                entries: vec![Holding { mint: currency_mint, amount: 1_000, decimals: 0, last_price: 0, vault_amount: 0, token_program: spl_token::id() }],
+            },
+        );
+        let seller_holdings_address = Holdings::find_address(&seller_wallet_address, &crate::id()).0;
+        let buyer_currency_account = spl_associated_token_account::get_associated_token_address(&buyer.pubkey(), &currency_mint);
+        add_token_account(&mut program_test, buyer_currency_account, currency_mint, buyer.pubkey(), 1_000);
+        let seller_currency_account = spl_associated_token_account::get_associated_token_address(&seller.pubkey(), &currency_mint);
+        
+        let item_mint = Pubkey::new_unique();
+        add_mint(&mut program_test, item_mint, None, 0, 1);
+        let seller_item_account = spl_associated_token_account::get_associated_token_address(&seller.pubkey(), &item_mint);
+        add_token_account(&mut program_test, seller_item_account, item_mint, seller.pubkey(), 1);
+        let buyer_item_account = spl_associated_token_account::get_associated_token_address(&buyer.pubkey(), &item_mint);
+        let listing_address = Listing::find_address(&item_mint, &crate::id()).0;
+        let escrow_account = spl_associated_token_account::get_associated_token_address(&listing_address, &item_mint);
+        
+        // The seller has the item equipped
+        let (seller_inventory_address, seller_inventory_bump) = Inventory::find_address(&seller_wallet_address, &crate::id());
+        add_state_account(
+            &mut program_test,
+            seller_inventory_address,
+            &Inventory {
+                is_initialized: true,
+                player_wallet: seller_wallet_address,
+                bump: seller_inventory_bump,
+                capacity: 1,
+                items: vec![InventoryItem { mint: item_mint, slot: 0, equipped: true, usable_until: 0 }],
+            },
+        );
+        
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+        
+        // 34 = ListItem instruction, 35 = CancelListing instruction
+        let list_or_cancel = |owner: &Keypair, player_wallet: Pubkey, token_account: Pubkey, price: Option<u64>| {
+            let mut instruction_data = match price {
+                Some(price) => [vec![34], price.try_to_vec().unwrap()].concat(),
+                None => vec![35],
+            };
+            let mut accounts = vec![
+                solana_program::instruction::AccountMeta::new(owner.pubkey(), true),
+                solana_program::instruction::AccountMeta::new_readonly(player_wallet, false),
+                solana_program::instruction::AccountMeta::new(listing_address, false),
+                solana_program::instruction::AccountMeta::new_readonly(item_mint, false),
+                solana_program::instruction::AccountMeta::new(token_account, false),
+                solana_program::instruction::AccountMeta::new(escrow_account, false),
+                solana_program::instruction::AccountMeta::new_readonly(spl_token::id(), false),
+            ];
+            if price.is_some() {
+                accounts.push(solana_program::instruction::AccountMeta::new_readonly(spl_associated_token_account::id(), false));
+                accounts.push(solana_program::instruction::AccountMeta::new_readonly(solana_program::system_program::id(), false));
+                accounts.push(solana_program::instruction::AccountMeta::new(
+                    Inventory::find_address(&player_wallet, &crate::id()).0,
+                    false,
+                ));
+            }
+            let mut transaction = Transaction::new_with_payer(
+                &[solana_program::instruction::Instruction::new_with_bytes(crate::id(), &instruction_data, accounts)],
+                Some(&payer.pubkey()),
+            );
+            transaction.sign(&[&payer, owner], recent_blockhash);
+            transaction
+        };
+        // 36 = BuyItem instruction
+        let buy = |price: u64| {
+            let mut instruction_data = vec![36];
+            instruction_data.extend(price.try_to_vec().unwrap());
+            let mut transaction = Transaction::new_with_payer(
+                &[solana_program::instruction::Instruction::new_with_bytes(
+                    crate::id(),
+                    &instruction_data,
+                    vec![
+                        solana_program::instruction::AccountMeta::new(buyer.pubkey(), true),
+                        solana_program::instruction::AccountMeta::new(buyer_wallet_address, false),
+                        solana_program::instruction::AccountMeta::new(buyer_holdings_address, false),
+                        solana_program::instruction::AccountMeta::new(buyer_item_account, false),
+                        solana_program::instruction::AccountMeta::new(buyer_currency_account, false),
+                        solana_program::instruction::AccountMeta::new(seller_wallet_address, false),
+                        solana_program::instruction::AccountMeta::new(seller_holdings_address, false),
+                        solana_program::instruction::AccountMeta::new(seller.pubkey(), false),
+                        solana_program::instruction::AccountMeta::new(seller_currency_account, false),
+                        solana_program::instruction::AccountMeta::new(listing_address, false),
+                        solana_program::instruction::AccountMeta::new_readonly(item_mint, false),
+                        solana_program::instruction::AccountMeta::new(escrow_account, false),
+                        solana_program::instruction::AccountMeta::new_readonly(currency_config_address, false),
+                        solana_program::instruction::AccountMeta::new_readonly(currency_mint, false),
+                        solana_program::instruction::AccountMeta::new_readonly(marketplace_config_address, false),
+                        solana_program::instruction::AccountMeta::new(fee_account, false),
+                        solana_program::instruction::AccountMeta::new_readonly(spl_token::id(), false),
+                        solana_program::instruction::AccountMeta::new_readonly(spl_token::id(), false),
+                        solana_program::instruction::AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+                        solana_program::instruction::AccountMeta::new_readonly(solana_program::system_program::id(), false),
+                        solana_program::instruction::AccountMeta::new(Leaderboard::find_address(&crate::id()).0, false),
+                        solana_program::instruction::AccountMeta::new_readonly(SeasonClock::find_address(&crate::id()).0, false),
+                    ],
+                )],
+                Some(&payer.pubkey()),
+            );
+            transaction.sign(&[&payer, &buyer], recent_blockhash);
+            transaction
+        };
+        let token_balance = |account: Option<Account>| {
+            account.map_or(0, |account| spl_token::state::Account::unpack(&account.data).unwrap().amount)
+        };
+        
+        // Listing escrows the item
+        banks_client.process_transaction(list_or_cancel(&seller, seller_wallet_address, seller_item_account, Some(500))).await.unwrap();
+        assert_eq!(token_balance(banks_client.get_account(escrow_account).await.unwrap()), 1);
+        assert_eq!(token_balance(banks_client.get_account(seller_item_account).await.unwrap()), 0);
+        let listing_account = banks_client.get_account(listing_address).await.unwrap().unwrap();
+        let listing = unpack_account::<Listing>(&listing_account.data).unwrap();
+        assert_eq!(listing.seller, seller_wallet_address);
+        assert_eq!(listing.price, 500);
+        
+        // The escrowed item left the seller's inventory
+        let seller_inventory_account = banks_client.get_account(seller_inventory_address).await.unwrap().unwrap();
+        let seller_inventory = unpack_account::<Inventory>(&seller_inventory_account.data).unwrap();
+        assert!(seller_inventory.items.is_empty());
+        
+        // Only the seller can cancel, and the buyer must agree to the listed price
+        assert!(banks_client.process_transaction(list_or_cancel(&buyer, buyer_wallet_address, buyer_currency_account, None)).await.is_err());
+        assert!(banks_client.process_transaction(buy(400)).await.is_err());
+        
+        banks_client.process_transaction(buy(500)).await.unwrap();
+        assert_eq!(token_balance(banks_client.get_account(buyer_item_account).await.unwrap()), 1);
+        assert_eq!(token_balance(banks_client.get_account(buyer_currency_account).await.unwrap()), 500);
+        assert_eq!(token_balance(banks_client.get_account(seller_currency_account).await.unwrap()), 475);
+        assert_eq!(token_balance(banks_client.get_account(fee_account).await.unwrap()), 25);
+        assert!(banks_client.get_account(listing_address).await.unwrap().is_none());
+        assert!(banks_client.get_account(escrow_account).await.unwrap().is_none());
+        
+        // Both wallets record the trade, and the buyer's holding follows the payment
+        let buyer_wallet_account = banks_client.get_account(buyer_wallet_address).await.unwrap().unwrap();
+        let buyer_wallet = PlayerWallet::load(&buyer_wallet_account.data).unwrap();
+        assert_eq!(buyer_wallet.nb_transactions, 1);
+        assert_eq!(buyer_wallet.amount_total_tokens, 500);
+        let seller_wallet_account = banks_client.get_account(seller_wallet_address).await.unwrap().unwrap();
+        assert_eq!(PlayerWallet::load(&seller_wallet_account.data).unwrap().nb_transactions, 1);
+        
+        // The new owner can list and cancel, getting the item back
+        banks_client.process_transaction(list_or_cancel(&buyer, buyer_wallet_address, buyer_item_account, Some(900))).await.unwrap();
+        assert_eq!(token_balance(banks_client.get_account(buyer_item_account).await.unwrap()), 0);
+        banks_client.process_transaction(list_or_cancel(&buyer, buyer_wallet_address, buyer_item_account, None)).await.unwrap();
+        assert_eq!(token_balance(banks_client.get_account(buyer_item_account).await.unwrap()), 1);
+        assert!(banks_client.get_account(listing_address).await.unwrap().is_none());
+    }
+    
+    #[tokio::test]
+    async fn test_english_auction() {
+        let mut program_test = program_test();
+        let (currency_config_address, currency_mint) = add_currency(&mut program_test);
+        
+        // A 5% fee
+        let (marketplace_config_address, fee_account) = add_marketplace_config(&mut program_test, 500);
+        
+        // The seller holds the item, two bidders hold the game currency
+        let seller = Keypair::new();
+        let seller_wallet_address = add_player_wallet(&mut program_test, &PlayerWallet::new("Seller", seller.pubkey()).unwrap());
+        let bidders = [Keypair::new(), Keypair::new()];
+        let bidder_wallet_addresses: Vec<Pubkey> = bidders.iter()
+            .zip(["Alice", "Bob"])
+            .map(|(bidder, name)| add_player_wallet(&mut program_test, &PlayerWallet::new(name, bidder.pubkey()).unwrap()))
+            .collect();
+        let currency_account = |owner: &Pubkey| spl_associated_token_account::get_associated_token_address(owner, &currency_mint);
+        for owner in [&seller, &bidders[0], &bidders[1]] {
+            program_test.add_account(owner.pubkey(), Account { lamports: 1_000_000_000, ..Account::default() });
+        }
+        for bidder in &bidders {
+            add_token_account(&mut program_test, currency_account(&bidder.pubkey()), currency_mint, bidder.pubkey(), 1_000);
+        }
+        
+        let item_mint = Pubkey::new_unique();
+        add_mint(&mut program_test, item_mint, None, 0, 1);
+        let item_account = |owner: &Pubkey| spl_associated_token_account::get_associated_token_address(owner, &item_mint);
+        add_token_account(&mut program_test, item_account(&seller.pubkey()), item_mint, seller.pubkey(), 1);
+        let auction_address = Auction::find_address(&item_mint, &crate::id()).0;
+        let escrow_account = item_account(&auction_address);
+        let bid_escrow_account = currency_account(&auction_address);
+        
+        let mut context = program_test.start_with_context().await;
+        let clock: solana_program::clock::Clock = context.banks_client.get_sysvar().await.unwrap();
+        
+        // 37 = CreateAuction instruction, open for 100 seconds with a reserve of 100
+        let terms = AuctionTerms {
+            kind: AuctionKind::English,
+            start_time: clock.unix_timestamp,
+            end_time: clock.unix_timestamp + 100,
+            start_price: 100,
+            end_price: 0,
+            min_bid_increment: 10,
+        };
+        let mut instruction_data = vec![37];
+        instruction_data.extend(terms.try_to_vec().unwrap());
+        let create_instruction = solana_program::instruction::Instruction::new_with_bytes(
+            crate::id(),
+            &instruction_data,
+            vec![
+                solana_program::instruction::AccountMeta::new(seller.pubkey(), true),
+                solana_program::instruction::AccountMeta::new_readonly(seller_wallet_address, false),
+                solana_program::instruction::AccountMeta::new(auction_address, false),
+                solana_program::instruction::AccountMeta::new_readonly(item_mint, false),
+                solana_program::instruction::AccountMeta::new(item_account(&seller.pubkey()), false),
+                solana_program::instruction::AccountMeta::new(escrow_account, false),
+                solana_program::instruction::AccountMeta::new_readonly(currency_config_address, false),
+                solana_program::instruction::AccountMeta::new_readonly(currency_mint, false),
+                solana_program::instruction::AccountMeta::new(bid_escrow_account, false),
+                solana_program::instruction::AccountMeta::new_readonly(spl_token::id(), false),
+                solana_program::instruction::AccountMeta::new_readonly(spl_token::id(), false),
+                solana_program::instruction::AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+                solana_program::instruction::AccountMeta::new_readonly(solana_program::system_program::id(), false),
+                solana_program::instruction::AccountMeta::new(Inventory::find_address(&seller_wallet_address, &crate::id()).0, false),
+            ],
+        );
+        let mut transaction = Transaction::new_with_payer(&[create_instruction], Some(&context.payer.pubkey()));
+        transaction.sign(&[&context.payer, &seller], context.last_blockhash);
+        context.banks_client.process_transaction(transaction).await.unwrap();
+        
+        // 38 = PlaceBid instruction
+        let bid_instruction = |bidder: usize, amount: u64, outbid: usize| {
+            let mut instruction_data = vec![38];
+            instruction_data.extend(amount.try_to_vec().unwrap());
+            solana_program::instruction::Instruction::new_with_bytes(
+                crate::id(),
+                &instruction_data,
+                vec![
+                    solana_program::instruction::AccountMeta::new(bidders[bidder].pubkey(), true),
+                    solana_program::instruction::AccountMeta::new_readonly(bidder_wallet_addresses[bidder], false),
+                    solana_program::instruction::AccountMeta::new(auction_address, false),
+                    solana_program::instruction::AccountMeta::new_readonly(item_mint, false),
+                    solana_program::instruction::AccountMeta::new_readonly(currency_config_address, false),
+                    solana_program::instruction::AccountMeta::new_readonly(currency_mint, false),
+                    solana_program::instruction::AccountMeta::new(currency_account(&bidders[bidder].pubkey()), false),
+                    solana_program::instruction::AccountMeta::new(bid_escrow_account, false),
+                    solana_program::instruction::AccountMeta::new(currency_account(&bidders[outbid].pubkey()), false),
+                    solana_program::instruction::AccountMeta::new_readonly(bidders[outbid].pubkey(), false),
+                    solana_program::instruction::AccountMeta::new_readonly(spl_token::id(), false),
+                    solana_program::instruction::AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+                    solana_program::instruction::AccountMeta::new_readonly(solana_program::system_program::id(), false),
+                ],
+            )
+        };
+        // 39 = SettleAuction instruction
+        let settle_instruction = |payer: &Pubkey| {
+            let winner = &bidders[1].pubkey();
+            solana_program::instruction::Instruction::new_with_bytes(
+                crate::id(),
+                &[39],
+                vec![
+                    solana_program::instruction::AccountMeta::new(*payer, true),
+                    solana_program::instruction::AccountMeta::new(seller_wallet_address, false),
+                    solana_program::instruction::AccountMeta::new(Holdings::find_address(&seller_wallet_address, &crate::id()).0, false),
+                    solana_program::instruction::AccountMeta::new(seller.pubkey(), false),
+                    solana_program::instruction::AccountMeta::new(currency_account(&seller.pubkey()), false),
+                    solana_program::instruction::AccountMeta::new(bidder_wallet_addresses[1], false),
+                    solana_program::instruction::AccountMeta::new(Holdings::find_address(&bidder_wallet_addresses[1], &crate::id()).0, false),
+                    solana_program::instruction::AccountMeta::new_readonly(*winner, false),
+                    solana_program::instruction::AccountMeta::new_readonly(currency_account(winner), false),
+                    solana_program::instruction::AccountMeta::new(item_account(winner), false),
+                    solana_program::instruction::AccountMeta::new(auction_address, false),
+                    solana_program::instruction::AccountMeta::new_readonly(item_mint, false),
+                    solana_program::instruction::AccountMeta::new(escrow_account, false),
+                    solana_program::instruction::AccountMeta::new_readonly(currency_config_address, false),
+                    solana_program::instruction::AccountMeta::new_readonly(currency_mint, false),
+                    solana_program::instruction::AccountMeta::new(bid_escrow_account, false),
+                    solana_program::instruction::AccountMeta::new_readonly(marketplace_config_address, false),
+                    solana_program::instruction::AccountMeta::new(fee_account, false),
+                    solana_program::instruction::AccountMeta::new_readonly(spl_token::id(), false),
+                    solana_program::instruction::AccountMeta::new_readonly(spl_token::id(), false),
+                    solana_program::instruction::AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+                    solana_program::instruction::AccountMeta::new_readonly(solana_program::system_program::id(), false),
+                    solana_program::instruction::AccountMeta::new(Leaderboard::find_address(&crate::id()).0, false),
+                    solana_program::instruction::AccountMeta::new_readonly(SeasonClock::find_address(&crate::id()).0, false),
+                ],
+            )
+        };
+        
+        // Bids must meet the reserve, then raise by the increment; settling waits for the end
+        for (instruction, signer, succeeds) in [
+            (bid_instruction(0, 99, 1), 0, false),
+            (bid_instruction(0, 100, 1), 0, true),
+            (bid_instruction(1, 105, 0), 1, false),
+            (bid_instruction(1, 150, 0), 1, true),
+            (settle_instruction(&bidders[1].pubkey()), 1, false),
+        ] {
+            let mut transaction = Transaction::new_with_payer(&[instruction], Some(&context.payer.pubkey()));
+            transaction.sign(&[&context.payer, &bidders[signer]], context.last_blockhash);
+            assert_eq!(context.banks_client.process_transaction(transaction).await.is_ok(), succeeds);
+        }
+        
+        // The outbid bidder got their bid back
+        let token_balance = |account: Option<Account>| {
+            account.map_or(0, |account| spl_token::state::Account::unpack(&account.data).unwrap().amount)
+        };
+        assert_eq!(token_balance(context.banks_client.get_account(currency_account(&bidders[0].pubkey())).await.unwrap()), 1_000);
+        assert_eq!(token_balance(context.banks_client.get_account(bid_escrow_account).await.unwrap()), 150);
+        
+        // Once the auction is over, bids are refused and anyone can settle
+        let mut clock: solana_program::clock::Clock = context.banks_client.get_sysvar().await.unwrap();
+        clock.unix_timestamp = terms.end_time;
+        context.last_blockhash = context.banks_client
+            .get_new_latest_blockhash(&context.last_blockhash)
+            .await
+            .unwrap();
+        context.set_sysvar(&clock);
+        let mut transaction = Transaction::new_with_payer(&[bid_instruction(0, 500, 1)], Some(&context.payer.pubkey()));
+        transaction.sign(&[&context.payer, &bidders[0]], context.last_blockhash);
+        assert!(context.banks_client.process_transaction(transaction).await.is_err());
+        
+        // Tokens sent straight to the bid escrow cannot block settling, and go to the seller
+        let mut transaction = Transaction::new_with_payer(
+            &[spl_token::instruction::transfer(
+                &spl_token::id(),
+                &currency_account(&bidders[0].pubkey()),
+                &bid_escrow_account,
+                &bidders[0].pubkey(),
+                &[],
+                1,
+            )
+            .unwrap()],
+            Some(&context.payer.pubkey()),
+        );
+        transaction.sign(&[&context.payer, &bidders[0]], context.last_blockhash);
+        context.banks_client.process_transaction(transaction).await.unwrap();
+        let mut transaction = Transaction::new_with_payer(&[settle_instruction(&context.payer.pubkey())], Some(&context.payer.pubkey()));
+        transaction.sign(&[&context.payer], context.last_blockhash);
+        context.banks_client.process_transaction(transaction).await.unwrap();
+        
+        assert_eq!(token_balance(context.banks_client.get_account(item_account(&bidders[1].pubkey())).await.unwrap()), 1);
+        assert_eq!(token_balance(context.banks_client.get_account(currency_account(&bidders[1].pubkey())).await.unwrap()), 850);
+        assert_eq!(token_balance(context.banks_client.get_account(currency_account(&seller.pubkey())).await.unwrap()), 144);
+        assert_eq!(token_balance(context.banks_client.get_account(fee_account).await.unwrap()), 7);
+        for closed in [auction_address, escrow_account, bid_escrow_account] {
+            assert!(context.banks_client.get_account(closed).await.unwrap().is_none());
+        }
+        let seller_wallet_account = context.banks_client.get_account(seller_wallet_address).await.unwrap().unwrap();
+        assert_eq!(PlayerWallet::load(&seller_wallet_account.data).unwrap().nb_transactions, 1);
+        let winner_wallet_account = context.banks_client.get_account(bidder_wallet_addresses[1]).await.unwrap().unwrap();
+        assert_eq!(PlayerWallet::load(&winner_wallet_account.data).unwrap().nb_transactions, 1);
+    }
+    
+    #[tokio::test]
+    async fn test_dutch_auction() {
+        let mut program_test = program_test();
+        let (currency_config_address, currency_mint) = add_currency(&mut program_test);
+        
+        // A 5% fee
+        let (marketplace_config_address, fee_account) = add_marketplace_config(&mut program_test, 500);
+        
+        // The seller holds the item, the bidder holds the game currency
+        let seller = Keypair::new();
+        let seller_wallet_address = add_player_wallet(&mut program_test, &PlayerWallet::new("Seller", seller.pubkey()).unwrap());
+        let bidder = Keypair::new();
+        let bidder_wallet_address = add_player_wallet(&mut program_test, &PlayerWallet::new("Bidder", bidder.pubkey()).unwrap());
+        let currency_account = |owner: &Pubkey| spl_associated_token_account::get_associated_token_address(owner, &currency_mint);
+        for owner in [&seller, &bidder] {
+            program_test.add_account(owner.pubkey(), Account { lamports: 1_000_000_000, ..Account::default() });
+        }
+        add_token_account(&mut program_test, currency_account(&bidder.pubkey()), currency_mint, bidder.pubkey(), 1_000);
+        
+        let item_mint = Pubkey::new_unique();
+        add_mint(&mut program_test, item_mint, None, 0, 1);
+        let item_account = |owner: &Pubkey| spl_associated_token_account::get_associated_token_address(owner, &item_mint);
+        add_token_account(&mut program_test, item_account(&seller.pubkey()), item_mint, seller.pubkey(), 1);
+        let auction_address = Auction::find_address(&item_mint, &crate::id()).0;
+        let escrow_account = item_account(&auction_address);
+        let bid_escrow_account = currency_account(&auction_address);
+        
+        let mut context = program_test.start_with_context().await;
+        let mut clock: solana_program::clock::Clock = context.banks_client.get_sysvar().await.unwrap();
+        
+        // 37 = CreateAuction instruction, falling from 1,000 to 500 over 100 seconds
+        let terms = AuctionTerms {
+            kind: AuctionKind::Dutch,
+            start_time: clock.unix_timestamp,
+            end_time: clock.unix_timestamp + 100,
+            start_price: 1_000,
+            end_price: 500,
+            min_bid_increment: 0,
+        };
+        let mut instruction_data = vec![37];
+        instruction_data.extend(terms.try_to_vec().unwrap());
+        let create_instruction = solana_program::instruction::Instruction::new_with_bytes(
+            crate::id(),
+            &instruction_data,
+            vec![
+                solana_program::instruction::AccountMeta::new(seller.pubkey(), true),
+                solana_program::instruction::AccountMeta::new_readonly(seller_wallet_address, false),
+                solana_program::instruction::AccountMeta::new(auction_address, false),
+                solana_program::instruction::AccountMeta::new_readonly(item_mint, false),
+                solana_program::instruction::AccountMeta::new(item_account(&seller.pubkey()), false),
+                solana_program::instruction::AccountMeta::new(escrow_account, false),
+                solana_program::instruction::AccountMeta::new_readonly(currency_config_address, false),
+                solana_program::instruction::AccountMeta::new_readonly(currency_mint, false),
+                solana_program::instruction::AccountMeta::new(bid_escrow_account, false),
+                solana_program::instruction::AccountMeta::new_readonly(spl_token::id(), false),
+                solana_program::instruction::AccountMeta::new_readonly(spl_token::id(), false),
+                solana_program::instruction::AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+                solana_program::instruction::AccountMeta::new_readonly(solana_program::system_program::id(), false),
+                solana_program::instruction::AccountMeta::new(Inventory::find_address(&seller_wallet_address, &crate::id()).0, false),
+            ],
+        );
+        let mut transaction = Transaction::new_with_payer(&[create_instruction], Some(&context.payer.pubkey()));
+        transaction.sign(&[&context.payer, &seller], context.last_blockhash);
+        context.banks_client.process_transaction(transaction).await.unwrap();
+        
+        // 38 = PlaceBid instruction, with no earlier bid to refund
+        let bid_instruction = |amount: u64| {
+            let mut instruction_data = vec![38];
+            instruction_data.extend(amount.try_to_vec().unwrap());
+            solana_program::instruction::Instruction::new_with_bytes(
+                crate::id(),
+                &instruction_data,
+                vec![
+                    solana_program::instruction::AccountMeta::new(bidder.pubkey(), true),
+                    solana_program::instruction::AccountMeta::new_readonly(bidder_wallet_address, false),
+                    solana_program::instruction::AccountMeta::new(auction_address, false),
+                    solana_program::instruction::AccountMeta::new_readonly(item_mint, false),
+                    solana_program::instruction::AccountMeta::new_readonly(currency_config_address, false),
+                    solana_program::instruction::AccountMeta::new_readonly(currency_mint, false),
+                    solana_program::instruction::AccountMeta::new(currency_account(&bidder.pubkey()), false),
+                    solana_program::instruction::AccountMeta::new(bid_escrow_account, false),
+                    solana_program::instruction::AccountMeta::new(currency_account(&bidder.pubkey()), false),
+                    solana_program::instruction::AccountMeta::new_readonly(bidder.pubkey(), false),
+                    solana_program::instruction::AccountMeta::new_readonly(spl_token::id(), false),
+                    solana_program::instruction::AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+                    solana_program::instruction::AccountMeta::new_readonly(solana_program::system_program::id(), false),
+                ],
+            )
+        };
+        
+        // Halfway through, the price has fallen to 750
+        clock.unix_timestamp = terms.start_time + 50;
+        context.last_blockhash = context.banks_client
+            .get_new_latest_blockhash(&context.last_blockhash)
+            .await
+            .unwrap();
+        context.set_sysvar(&clock);
+        
+        // A bid below the current price fails; a higher one pays the current price
+        // and wins outright, closing the auction early
+        for (amount, succeeds) in [(700, false), (800, true), (900, false)] {
+            let mut transaction = Transaction::new_with_payer(&[bid_instruction(amount)], Some(&context.payer.pubkey()));
+            transaction.sign(&[&context.payer, &bidder], context.last_blockhash);
+            assert_eq!(context.banks_client.process_transaction(transaction).await.is_ok(), succeeds);
+        }
+        let auction_account = context.banks_client.get_account(auction_address).await.unwrap().unwrap();
+        let auction = unpack_account::<Auction>(&auction_account.data).unwrap();
+        assert_eq!(auction.highest_bid, 750);
+        assert_eq!(auction.highest_bidder, bidder_wallet_address);
+        
+        // 39 = SettleAuction instruction, possible before the scheduled end
+        let mut transaction = Transaction::new_with_payer(
+            &[solana_program::instruction::Instruction::new_with_bytes(
+                crate::id(),
+                &[39],
+                vec![
+                    solana_program::instruction::AccountMeta::new(context.payer.pubkey(), true),
+                    solana_program::instruction::AccountMeta::new(seller_wallet_address, false),
+                    solana_program::instruction::AccountMeta::new(Holdings::find_address(&seller_wallet_address, &crate::id()).0, false),
+                    solana_program::instruction::AccountMeta::new(seller.pubkey(), false),
+                    solana_program::instruction::AccountMeta::new(currency_account(&seller.pubkey()), false),
+                    solana_program::instruction::AccountMeta::new(bidder_wallet_address, false),
+                    solana_program::instruction::AccountMeta::new(Holdings::find_address(&bidder_wallet_address, &crate::id()).0, false),
+                    solana_program::instruction::AccountMeta::new_readonly(bidder.pubkey(), false),
+                    solana_program::instruction::AccountMeta::new_readonly(currency_account(&bidder.pubkey()), false),
+                    solana_program::instruction::AccountMeta::new(item_account(&bidder.pubkey()), false),
+                    solana_program::instruction::AccountMeta::new(auction_address, false),
+                    solana_program::instruction::AccountMeta::new_readonly(item_mint, false),
+                    solana_program::instruction::AccountMeta::new(escrow_account, false),
+                    solana_program::instruction::AccountMeta::new_readonly(currency_config_address, false),
+                    solana_program::instruction::AccountMeta::new_readonly(currency_mint, false),
+                    solana_program::instruction::AccountMeta::new(bid_escrow_account, false),
+                    solana_program::instruction::AccountMeta::new_readonly(marketplace_config_address, false),
+                    solana_program::instruction::AccountMeta::new(fee_account, false),
+                    solana_program::instruction::AccountMeta::new_readonly(spl_token::id(), false),
+                    solana_program::instruction::AccountMeta::new_readonly(spl_token::id(), false),
+                    solana_program::instruction::AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+                    solana_program::instruction::AccountMeta::new_readonly(solana_program::system_program::id(), false),
+                    solana_program::instruction::AccountMeta::new(Leaderboard::find_address(&crate::id()).0, false),
+                    solana_program::instruction::AccountMeta::new_readonly(SeasonClock::find_address(&crate::id()).0, false),
+                ],
+            )],
+            Some(&context.payer.pubkey()),
+        );
+        transaction.sign(&[&context.payer], context.last_blockhash);
+        context.banks_client.process_transaction(transaction).await.unwrap();
+        
+        let token_balance = |account: Option<Account>| {
+            account.map_or(0, |account| spl_token::state::Account::unpack(&account.data).unwrap().amount)
+        };
+        assert_eq!(token_balance(context.banks_client.get_account(item_account(&bidder.pubkey())).await.unwrap()), 1);
+        assert_eq!(token_balance(context.banks_client.get_account(currency_account(&bidder.pubkey())).await.unwrap()), 250);
+        assert_eq!(token_balance(context.banks_client.get_account(currency_account(&seller.pubkey())).await.unwrap()), 713);
+        assert_eq!(token_balance(context.banks_client.get_account(fee_account).await.unwrap()), 37);
+        for closed in [auction_address, escrow_account, bid_escrow_account] {
+            assert!(context.banks_client.get_account(closed).await.unwrap().is_none());
+        }
+    }
+    
+    #[test]
+    fn test_auction_pricing() {
+        let mut auction = Auction {
+            is_initialized: true,
+            kind: AuctionKind::Dutch,
+            seller: Pubkey::new_unique(),
+            mint: Pubkey::new_unique(),
+            start_time: 1_000,
+            end_time: 1_100,
+            start_price: 1_000,
+            end_price: 500,
+            min_bid_increment: 0,
+            highest_bid: 0,
+            highest_bidder: Pubkey::default(),
+            highest_bidder_owner: Pubkey::default(),
+            bump: 255,
+        };
+        
+        // Dutch prices fall linearly and stop at the end price
+        assert_eq!(auction.min_bid(900).unwrap(), 1_000);
+        assert_eq!(auction.min_bid(1_000).unwrap(), 1_000);
+        assert_eq!(auction.min_bid(1_050).unwrap(), 750);
+        assert_eq!(auction.min_bid(1_099).unwrap(), 505);
+        assert_eq!(auction.min_bid(2_000).unwrap(), 500);
+        
+        // Bidding is open between the start and end times, and a Dutch bid ends it early
+        assert!(!auction.is_open(999));
+        assert!(auction.is_open(1_000));
+        assert!(!auction.is_open(1_100));
+        assert!(!auction.is_over(1_050));
+        auction.highest_bid = 750;
+        auction.highest_bidder = Pubkey::new_unique();
+        assert!(!auction.is_open(1_050));
+        assert!(auction.is_over(1_050));
+        
+        // English auctions need the reserve, then a raise of at least one unit
+        auction.kind = AuctionKind::English;
+        assert!(auction.is_open(1_050));
+        assert!(!auction.is_over(1_050));
+        assert_eq!(auction.min_bid(1_050).unwrap(), 751);
+        auction.min_bid_increment = 50;
+        assert_eq!(auction.min_bid(1_050).unwrap(), 800);
+        auction.highest_bidder = Pubkey::default();
+        assert_eq!(auction.min_bid(1_050).unwrap(), 1_000);
+    }
+    
+    #[tokio::test]
+    async fn test_item_rental() {
+        let mut program_test = program_test();
+        let (currency_config_address, currency_mint) = add_currency(&mut program_test);
+        
+        // The lender holds the item, the renter holds the game currency
+        let lender = Keypair::new();
+        let lender_wallet_address = add_player_wallet(&mut program_test, &PlayerWallet::new("Lender", lender.pubkey()).unwrap());
+        let renter = Keypair::new();
+        let renter_wallet_address = add_player_wallet(&mut program_test, &PlayerWallet::new("Renter", renter.pubkey()).unwrap());
+        let currency_account = |owner: &Pubkey| spl_associated_token_account::get_associated_token_address(owner, &currency_mint);
+        for owner in [&lender, &renter] {
+            program_test.add_account(owner.pubkey(), Account { lamports: 1_000_000_000, ..Account::default() });
+        }
+        add_token_account(&mut program_test, currency_account(&renter.pubkey()), currency_mint, renter.pubkey(), 1_000);
+        
+        let item_mint = Pubkey::new_unique();
+        add_mint(&mut program_test, item_mint, None, 0, 1);
+        let item_account = |owner: &Pubkey| spl_associated_token_account::get_associated_token_address(owner, &item_mint);
+        add_token_account(&mut program_test, item_account(&lender.pubkey()), item_mint, lender.pubkey(), 1);
+        let rental_address = Rental::find_address(&item_mint, &crate::id()).0;
+        let escrow_account = item_account(&rental_address);
+        let renter_inventory_address = Inventory::find_address(&renter_wallet_address, &crate::id()).0;
+        
+        // The lender has the item equipped
+        let (lender_inventory_address, lender_inventory_bump) = Inventory::find_address(&lender_wallet_address, &crate::id());
+        add_state_account(
+            &mut program_test,
+            lender_inventory_address,
+            &Inventory {
+                is_initialized: true,
+                player_wallet: lender_wallet_address,
+                bump: lender_inventory_bump,
+                capacity: 1,
+                items: vec![InventoryItem { mint: item_mint, slot: 3, equipped: true, usable_until: 0 }],
+            },
+        );
+        
+        let mut context = program_test.start_with_context().await;
+        
+        // 40 = CreateRental instruction, 100 per 50 seconds
+        let mut instruction_data = vec![40];
+        instruction_data.extend(100u64.try_to_vec().unwrap());
+        instruction_data.extend(50i64.try_to_vec().unwrap());
+        let create_instruction = solana_program::instruction::Instruction::new_with_bytes(
+            crate::id(),
+            &instruction_data,
+            vec![
+                solana_program::instruction::AccountMeta::new(lender.pubkey(), true),
+                solana_program::instruction::AccountMeta::new_readonly(lender_wallet_address, false),
+                solana_program::instruction::AccountMeta::new(rental_address, false),
+                solana_program::instruction::AccountMeta::new_readonly(item_mint, false),
+                solana_program::instruction::AccountMeta::new(item_account(&lender.pubkey()), false),
+                solana_program::instruction::AccountMeta::new(escrow_account, false),
+                solana_program::instruction::AccountMeta::new_readonly(spl_token::id(), false),
+                solana_program::instruction::AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+                solana_program::instruction::AccountMeta::new_readonly(solana_program::system_program::id(), false),
+                solana_program::instruction::AccountMeta::new(lender_inventory_address, false),
+            ],
+        );
+        let mut transaction = Transaction::new_with_payer(&[create_instruction], Some(&context.payer.pubkey()));
+        transaction.sign(&[&context.payer, &lender], context.last_blockhash);
+        context.banks_client.process_transaction(transaction).await.unwrap();
+        
+        // The lent item leaves the lender's inventory
+        let lender_inventory = |account: Account| unpack_account::<Inventory>(&account.data).unwrap().items;
+        let inventory_account = context.banks_client.get_account(lender_inventory_address).await.unwrap().unwrap();
+        assert!(lender_inventory(inventory_account).is_empty());
+        
+        // 41 = RentItem instruction, into slot 2
+        let mut instruction_data = vec![41];
+        instruction_data.extend(100u64.try_to_vec().unwrap());
+        instruction_data.extend(2u8.try_to_vec().unwrap());
+        let rent_instruction = solana_program::instruction::Instruction::new_with_bytes(
+            crate::id(),
+            &instruction_data,
+            vec![
+                solana_program::instruction::AccountMeta::new(renter.pubkey(), true),
+                solana_program::instruction::AccountMeta::new(renter_wallet_address, false),
+                solana_program::instruction::AccountMeta::new(Holdings::find_address(&renter_wallet_address, &crate::id()).0, false),
+                solana_program::instruction::AccountMeta::new(renter_inventory_address, false),
+                solana_program::instruction::AccountMeta::new(currency_account(&renter.pubkey()), false),
+                solana_program::instruction::AccountMeta::new(lender_wallet_address, false),
+                solana_program::instruction::AccountMeta::new(Holdings::find_address(&lender_wallet_address, &crate::id()).0, false),
+                solana_program::instruction::AccountMeta::new_readonly(lender.pubkey(), false),
+                solana_program::instruction::AccountMeta::new(currency_account(&lender.pubkey()), false),
+                solana_program::instruction::AccountMeta::new(rental_address, false),
+                solana_program::instruction::AccountMeta::new_readonly(item_mint, false),
+                solana_program::instruction::AccountMeta::new_readonly(currency_config_address, false),
+                solana_program::instruction::AccountMeta::new_readonly(currency_mint, false),
+                solana_program::instruction::AccountMeta::new_readonly(spl_token::id(), false),
+                solana_program::instruction::AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+                solana_program::instruction::AccountMeta::new_readonly(solana_program::system_program::id(), false),
+                solana_program::instruction::AccountMeta::new(Leaderboard::find_address(&crate::id()).0, false),
+                solana_program::instruction::AccountMeta::new_readonly(SeasonClock::find_address(&crate::id()).0, false),
+            ],
+        );
+        let mut transaction = Transaction::new_with_payer(&[rent_instruction], Some(&context.payer.pubkey()));
+        transaction.sign(&[&context.payer, &renter], context.last_blockhash);
+        context.banks_client.process_transaction(transaction).await.unwrap();
+        
+        let token_balance = |account: Option<Account>| {
+            account.map_or(0, |account| spl_token::state::Account::unpack(&account.data).unwrap().amount)
+        };
+        assert_eq!(token_balance(context.banks_client.get_account(currency_account(&lender.pubkey())).await.unwrap()), 100);
+        let rental_account = context.banks_client.get_account(rental_address).await.unwrap().unwrap();
+        let rental = unpack_account::<Rental>(&rental_account.data).unwrap();
+        assert_eq!(rental.renter, renter_wallet_address);
+        let inventory_account = context.banks_client.get_account(renter_inventory_address).await.unwrap().unwrap();
+        let inventory = unpack_account::<Inventory>(&inventory_account.data).unwrap();
+        assert_eq!(inventory.items, vec![InventoryItem { mint: item_mint, slot: 2, equipped: false, usable_until: rental.expires_at }]);
+        
+        // 31 = EquipItem instruction, with the rental escrow holding the item
+        let mut instruction_data = vec![31];
+        instruction_data.extend(item_mint.try_to_vec().unwrap());
+        let equip_instruction = solana_program::instruction::Instruction::new_with_bytes(
+            crate::id(),
+            &instruction_data,
+            vec![
+                solana_program::instruction::AccountMeta::new_readonly(renter.pubkey(), true),
+                solana_program::instruction::AccountMeta::new_readonly(renter_wallet_address, false),
+                solana_program::instruction::AccountMeta::new(renter_inventory_address, false),
+                solana_program::instruction::AccountMeta::new_readonly(escrow_account, false),
+            ],
+        );
+        // 42 = ReclaimRental instruction, callable by anyone
+        let reclaim_instruction = solana_program::instruction::Instruction::new_with_bytes(
+            crate::id(),
+            &[42],
+            vec![
+                solana_program::instruction::AccountMeta::new(context.payer.pubkey(), true),
+                solana_program::instruction::AccountMeta::new_readonly(lender_wallet_address, false),
+                solana_program::instruction::AccountMeta::new(lender.pubkey(), false),
+                solana_program::instruction::AccountMeta::new(item_account(&lender.pubkey()), false),
+                solana_program::instruction::AccountMeta::new(renter_inventory_address, false),
+                solana_program::instruction::AccountMeta::new(rental_address, false),
+                solana_program::instruction::AccountMeta::new_readonly(item_mint, false),
+                solana_program::instruction::AccountMeta::new(escrow_account, false),
+                solana_program::instruction::AccountMeta::new_readonly(spl_token::id(), false),
+                solana_program::instruction::AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+                solana_program::instruction::AccountMeta::new_readonly(solana_program::system_program::id(), false),
+                solana_program::instruction::AccountMeta::new(lender_inventory_address, false),
+            ],
+        );
+        
+        // The renter can use the item, and it cannot be reclaimed yet
+        let mut transaction = Transaction::new_with_payer(&[equip_instruction.clone()], Some(&context.payer.pubkey()));
+        transaction.sign(&[&context.payer, &renter], context.last_blockhash);
+        context.banks_client.process_transaction(transaction).await.unwrap();
+        let mut transaction = Transaction::new_with_payer(&[reclaim_instruction.clone()], Some(&context.payer.pubkey()));
+        transaction.sign(&[&context.payer], context.last_blockhash);
+        assert!(context.banks_client.process_transaction(transaction).await.is_err());
+        
+        // Once the rental expires, the item can no longer be equipped and anyone can return it
+        let mut clock: solana_program::clock::Clock = context.banks_client.get_sysvar().await.unwrap();
+        clock.unix_timestamp = rental.expires_at;
+        context.last_blockhash = context.banks_client
+            .get_new_latest_blockhash(&context.last_blockhash)
+            .await
+            .unwrap();
+        context.set_sysvar(&clock);
+        let mut transaction = Transaction::new_with_payer(&[equip_instruction], Some(&context.payer.pubkey()));
+        transaction.sign(&[&context.payer, &renter], context.last_blockhash);
+        assert!(context.banks_client.process_transaction(transaction).await.is_err());
+        let mut transaction = Transaction::new_with_payer(&[reclaim_instruction], Some(&context.payer.pubkey()));
+        transaction.sign(&[&context.payer], context.last_blockhash);
+        context.banks_client.process_transaction(transaction).await.unwrap();
+        
+        assert_eq!(token_balance(context.banks_client.get_account(item_account(&lender.pubkey())).await.unwrap()), 1);
+        for closed in [rental_address, escrow_account] {
+            assert!(context.banks_client.get_account(closed).await.unwrap().is_none());
+        }
+        let inventory_account = context.banks_client.get_account(renter_inventory_address).await.unwrap().unwrap();
+        assert!(unpack_account::<Inventory>(&inventory_account.data).unwrap().items.is_empty());
+        
+        // The lender gets the item back in its slot, unequipped
+        let inventory_account = context.banks_client.get_account(lender_inventory_address).await.unwrap().unwrap();
+        assert_eq!(
+            lender_inventory(inventory_account),
+            vec![InventoryItem { mint: item_mint, slot: 3, equipped: false, usable_until: 0 }]
+        );
+        let renter_wallet_account = context.banks_client.get_account(renter_wallet_address).await.unwrap().unwrap();
+        assert_eq!(PlayerWallet::load(&renter_wallet_account.data).unwrap().nb_transactions, 1);
+    }
+    
+    #[tokio::test]
+    async fn test_leaderboard_update() {
+        let mut program_test = program_test();
+        
+        // A top-2 board by transactions
+        let leaderboard_address = Leaderboard::find_address(&crate::id()).0;
+        let mut data = pack_account(&Leaderboard {
+            is_initialized: true,
+            metric: WalletMetric::Transactions,
+            size: 2,
+            bump: 255,
+            entries: Vec::new(),
+        }).unwrap();
+        data.resize(Leaderboard::LEN, 0);
+        program_test.add_account(
+            leaderboard_address,
+            Account {
+                lamports: Rent::default().minimum_balance(data.len()),
+                data,
+                owner: crate::id(),
+                ..Account::default()
+            },
+        );
+        
+        // Each player has deposited some SOL to transfer
+        let rent_exempt_minimum = Rent::default().minimum_balance(PlayerWallet::LEN);
+        let players = [Keypair::new(), Keypair::new(), Keypair::new()];
+        let player_wallet_addresses: Vec<Pubkey> = players.iter()
+            .zip(["Alice", "Bob", "Carol"])
+            .map(|(player, name)| {
+                let mut player_wallet = PlayerWallet::new(name, player.pubkey()).unwrap();
+                player_wallet.sol_balance = 100;
+                let address = Pubkey::new_unique();
+                program_test.add_account(
+                    address,
+                    Account {
+                        lamports: rent_exempt_minimum + 100,
+                        data: bytemuck::bytes_of(&player_wallet).to_vec(),
+                        owner: crate::id(),
+                        ..Account::default()
+                    },
+                );
+                address
+            })
+            .collect();
+        
+        let mut context = program_test.start_with_context().await;
+        
+        // 20 = Transfer instruction, ranking both players
+        let transfer_instruction = |sender: usize, recipient: usize, leaderboard: Pubkey| {
+            let mut instruction_data = vec![20];
+            instruction_data.extend(10u64.try_to_vec().unwrap());
+            solana_program::instruction::Instruction::new_with_bytes(
+                crate::id(),
+                &instruction_data,
+                vec![
+                    solana_program::instruction::AccountMeta::new_readonly(players[sender].pubkey(), true),
+                    solana_program::instruction::AccountMeta::new(player_wallet_addresses[sender], false),
+                    solana_program::instruction::AccountMeta::new(player_wallet_addresses[recipient], false),
+                    solana_program::instruction::AccountMeta::new(leaderboard, false),
+                    solana_program::instruction::AccountMeta::new_readonly(SeasonClock::find_address(&crate::id()).0, false),
+                ],
+            )
+        };
+        
+        // The leaderboard cannot be left out
+        let mut transaction = Transaction::new_with_payer(
+            &[transfer_instruction(0, 1, Pubkey::new_unique())],
+            Some(&context.payer.pubkey()),
+        );
+        transaction.sign(&[&context.payer, &players[0]], context.last_blockhash);
+        assert!(context.banks_client.process_transaction(transaction).await.is_err());
+        
+        for (sender, recipient, expected) in [
+            (0, 1, vec![(0, 1), (1, 1)]),
+            // A tie does not displace the player who reached the score first
+            (2, 0, vec![(0, 2), (1, 1)]),
+            // Beating the lowest score takes its place
+            (2, 1, vec![(0, 2), (2, 2)]),
+        ] {
+            let mut transaction = Transaction::new_with_payer(
+                &[transfer_instruction(sender, recipient, leaderboard_address)],
+                Some(&context.payer.pubkey()),
+            );
+            transaction.sign(&[&context.payer, &players[sender]], context.last_blockhash);
+            context.banks_client.process_transaction(transaction).await.unwrap();
+            
+            let account = context.banks_client.get_account(leaderboard_address).await.unwrap().unwrap();
+            let leaderboard = unpack_account::<Leaderboard>(&account.data).unwrap();
+            let ranked: Vec<(Pubkey, u64)> = leaderboard.entries.iter().map(|entry| (entry.player_wallet, entry.score)).collect();
+            let expected: Vec<(Pubkey, u64)> = expected.into_iter().map(|(player, score)| (player_wallet_addresses[player], score)).collect();
+            assert_eq!(ranked, expected);
+        }
+    }
+    
+    #[test]
+    fn test_leaderboard_ranking() {
+        let mut leaderboard = Leaderboard {
+            is_initialized: true,
+            metric: WalletMetric::PortfolioValue,
+            size: 3,
+            bump: 255,
+            entries: Vec::new(),
+        };
+        let players: Vec<Pubkey> = (0..4).map(|_| Pubkey::new_unique()).collect();
+        let ranked = |leaderboard: &Leaderboard| -> Vec<Pubkey> {
+            leaderboard.entries.iter().map(|entry| entry.player_wallet).collect()
+        };
+        
+        // Higher scores rank first, ties go to whoever reached the score first
+        assert!(leaderboard.record(&players[0], 100, 10));
+        assert!(leaderboard.record(&players[1], 300, 11));
+        assert!(leaderboard.record(&players[2], 100, 9));
+        assert_eq!(ranked(&leaderboard), vec![players[1], players[2], players[0]]);
+        
+        // A full board only admits a score above the lowest one
+        assert!(!leaderboard.record(&players[3], 100, 12));
+        assert!(leaderboard.record(&players[3], 101, 13));
+        assert_eq!(ranked(&leaderboard), vec![players[1], players[3], players[2]]);
+        
+        // Ranked players move with their score and keep their timestamp while it holds
+        assert!(leaderboard.record(&players[1], 50, 14));
+        assert!(leaderboard.record(&players[3], 101, 15));
+        assert_eq!(leaderboard.entries[0], LeaderboardEntry { player_wallet: players[3], score: 101, updated_at: 13 });
+        assert_eq!(ranked(&leaderboard), vec![players[3], players[2], players[1]]);
+    }
+    
+    #[tokio::test]
+    async fn test_season_snapshot() {
+        let mut program_test = program_test();
+        let rent_exempt_minimum = Rent::default().minimum_balance(PlayerWallet::LEN);
+        
+        // A player with some earlier activity and SOL to transfer
+        let owner = Keypair::new();
+        let mut player_wallet = PlayerWallet::new("Seasoned", owner.pubkey()).unwrap();
+        player_wallet.nb_transactions = 5;
+        player_wallet.amount_total_value_stablecoin = 1_000;
+        player_wallet.sol_balance = 100;
+        let player_wallet_address = Pubkey::new_unique();
+        program_test.add_account(
+            player_wallet_address,
+            Account {
+                lamports: rent_exempt_minimum + 100,
+                data: bytemuck::bytes_of(&player_wallet).to_vec(),
+                owner: crate::id(),
+                ..Account::default()
+            },
+        );
+        let recipient_address = add_player_wallet(&mut program_test, &PlayerWallet::new("Recipient", Pubkey::new_unique()).unwrap());
+        
+        let mut context = program_test.start_with_context().await;
+        initialize_program(&mut context).await;
+        let clock: solana_program::clock::Clock = context.banks_client.get_sysvar().await.unwrap();
+        let config_address = ProgramConfig::find_address(&crate::id()).0;
+        let season_clock_address = SeasonClock::find_address(&crate::id()).0;
+        let season_address = |id: u32| Season::find_address(id, &crate::id()).0;
+        let season_stats_address = |id: u32| SeasonStats::find_address(id, &player_wallet_address, &crate::id()).0;
+        
+        // 44 = CreateSeason instruction
+        let create_instruction = |id: u32| {
+            let mut instruction_data = vec![44];
+            instruction_data.extend(id.try_to_vec().unwrap());
+            instruction_data.extend(clock.unix_timestamp.try_to_vec().unwrap());
+            instruction_data.extend((clock.unix_timestamp + 1_000).try_to_vec().unwrap());
+            solana_program::instruction::Instruction::new_with_bytes(
+                crate::id(),
+                &instruction_data,
+                vec![
+                    solana_program::instruction::AccountMeta::new(context.payer.pubkey(), true),
+                    solana_program::instruction::AccountMeta::new_readonly(config_address, false),
+                    solana_program::instruction::AccountMeta::new(season_address(id), false),
+                    solana_program::instruction::AccountMeta::new_readonly(solana_program::system_program::id(), false),
+                    solana_program::instruction::AccountMeta::new(season_clock_address, false),
+                ],
+            )
+        };
+        // 45 = CloseSeason instruction
+        let close_instruction = |id: u32| {
+            let mut instruction_data = vec![45];
+            instruction_data.extend(id.try_to_vec().unwrap());
+            solana_program::instruction::Instruction::new_with_bytes(
+                crate::id(),
+                &instruction_data,
+                vec![
+                    solana_program::instruction::AccountMeta::new(context.payer.pubkey(), true),
+                    solana_program::instruction::AccountMeta::new_readonly(config_address, false),
+                    solana_program::instruction::AccountMeta::new(season_address(id), false),
+                    solana_program::instruction::AccountMeta::new(season_clock_address, false),
+                ],
+            )
+        };
+        // 46 = SnapshotSeasonStats instruction
+        let snapshot_instruction = |id: u32| {
+            let mut instruction_data = vec![46];
+            instruction_data.extend(id.try_to_vec().unwrap());
+            solana_program::instruction::Instruction::new_with_bytes(
+                crate::id(),
+                &instruction_data,
+                vec![
+                    solana_program::instruction::AccountMeta::new(context.payer.pubkey(), true),
+                    solana_program::instruction::AccountMeta::new_readonly(player_wallet_address, false),
+                    solana_program::instruction::AccountMeta::new_readonly(season_address(id), false),
+                    solana_program::instruction::AccountMeta::new(season_stats_address(id), false),
+                    solana_program::instruction::AccountMeta::new_readonly(solana_program::system_program::id(), false),
+                ],
+            )
+        };
+        // 20 = Transfer instruction, counting a transaction
+        let mut instruction_data = vec![20];
+        instruction_data.extend(10u64.try_to_vec().unwrap());
+        let transfer_instruction = solana_program::instruction::Instruction::new_with_bytes(
+            crate::id(),
+            &instruction_data,
+            vec![
+                solana_program::instruction::AccountMeta::new_readonly(owner.pubkey(), true),
+                solana_program::instruction::AccountMeta::new(player_wallet_address, false),
+                solana_program::instruction::AccountMeta::new(recipient_address, false),
+                solana_program::instruction::AccountMeta::new(Leaderboard::find_address(&crate::id()).0, false),
+                solana_program::instruction::AccountMeta::new_readonly(season_clock_address, false),
+            ],
+        );
+        // 2 = UpdatePlayerWallet instruction, changing the portfolio date
+        let mut instruction_data = vec![2];
+        instruction_data.extend(42u64.try_to_vec().unwrap());
+        let update_instruction = solana_program::instruction::Instruction::new_with_bytes(
+            crate::id(),
+            &instruction_data,
+            vec![
+                solana_program::instruction::AccountMeta::new_readonly(owner.pubkey(), true),
+                solana_program::instruction::AccountMeta::new(player_wallet_address, false),
+                solana_program::instruction::AccountMeta::new_readonly(season_clock_address, false),
+            ],
+        );
+        
+        // Two transactions in season 1 and one after it closes. Stats can only be
+        // snapshotted once a season is closed, in any order, and only once, and
+        // keep the metrics of the close even if the wallet changed since.
+        for (instruction, succeeds) in [
+            (create_instruction(1), true),
+            (create_instruction(2), true),
+            (transfer_instruction.clone(), true),
+            (transfer_instruction.clone(), true),
+            (snapshot_instruction(1), false),
+            (close_instruction(1), true),
+            (transfer_instruction, true),
+            (close_instruction(2), true),
+            (snapshot_instruction(1), true),
+            (update_instruction, true),
+            (snapshot_instruction(2), true),
+            (snapshot_instruction(1), false),
+        ] {
+            context.last_blockhash = context.banks_client
+                .get_new_latest_blockhash(&context.last_blockhash)
+                .await
+                .unwrap();
+            let signers: Vec<&Keypair> = if instruction.accounts[0].pubkey == owner.pubkey() {
+                vec![&context.payer, &owner]
+            } else {
+                vec![&context.payer]
+            };
+            let mut transaction = Transaction::new_with_payer(&[instruction], Some(&context.payer.pubkey()));
+            transaction.sign(&signers, context.last_blockhash);
+            assert_eq!(context.banks_client.process_transaction(transaction).await.is_ok(), succeeds);
+        }
+        
+        // Seasons ended when they were closed, in order
+        let season_account = context.banks_client.get_account(season_address(1)).await.unwrap().unwrap();
+        let season = unpack_account::<Season>(&season_account.data).unwrap();
+        assert!(season.is_closed);
+        assert!(season.end_time < clock.unix_timestamp + 1_000);
+        assert_eq!(season.close_index, 1);
+        
+        // Each snapshot counts the season's own transactions and the lifetime count is kept
+        for (id, nb_transactions) in [(1, 2), (2, 1)] {
+            let season_stats_account = context.banks_client.get_account(season_stats_address(id)).await.unwrap().unwrap();
+            let season_stats = unpack_account::<SeasonStats>(&season_stats_account.data).unwrap();
+            assert_eq!(season_stats.player_wallet, player_wallet_address);
+            assert_eq!(season_stats.nb_transactions, nb_transactions);
+            assert_eq!(season_stats.amount_total_value_stablecoin, 1_000);
+            assert_eq!(season_stats.date_portfolio, 0);
+        }
+        let player_wallet_account = context.banks_client.get_account(player_wallet_address).await.unwrap().unwrap();
+        let player_wallet = PlayerWallet::load(&player_wallet_account.data).unwrap();
+        assert_eq!(player_wallet.nb_transactions, 8);
+        assert_eq!(player_wallet.season_transactions, 0);
+        assert_eq!(player_wallet.date_portfolio, 42);
+    }
+    
+    #[test]
+    fn test_season_rollover() {
+        let mut player_wallet = PlayerWallet::new("Seasoned", Pubkey::new_unique()).unwrap();
+        let transactions = |player_wallet: &PlayerWallet, close_index: u32| {
+            player_wallet.season_metrics_for(close_index).map(|metrics| metrics.nb_transactions)
+        };
+        
+        // Two transactions before the first close, one before the second
+        player_wallet.record_transaction(0).unwrap();
+        player_wallet.record_transaction(0).unwrap();
+        player_wallet.amount_total_tokens = 500;
+        assert_eq!(transactions(&player_wallet, 1), Some(2));
+        player_wallet.record_transaction(1).unwrap();
+        assert_eq!(transactions(&player_wallet, 1), Some(2));
+        assert_eq!(transactions(&player_wallet, 2), Some(1));
+        
+        // Metrics changed after a close do not leak into the closed season
+        player_wallet.amount_total_tokens = 700;
+        assert_eq!(player_wallet.season_metrics_for(1).unwrap().amount_total_tokens, 500);
+        assert_eq!(player_wallet.season_metrics_for(2).unwrap().amount_total_tokens, 700);
+        
+        // A season without transactions counts none, and a rolled over season
+        // is only kept until the next rollover
+        player_wallet.record_transaction(3).unwrap();
+        assert_eq!(transactions(&player_wallet, 1), None);
+        assert_eq!(transactions(&player_wallet, 2), Some(1));
+        assert_eq!(transactions(&player_wallet, 3), Some(0));
+        assert_eq!(transactions(&player_wallet, 4), Some(1));
+        assert_eq!(player_wallet.season_metrics_for(3).unwrap().amount_total_tokens, 700);
+        assert_eq!(player_wallet.nb_transactions, 4);
+    }
+    
+    #[tokio::test]
+    async fn test_achievements() {
+        let mut program_test = program_test();
+        let owner = Keypair::new();
+        let mut player_wallet = PlayerWallet::new("Achiever", owner.pubkey()).unwrap();
+        player_wallet.sol_balance = 100;
+        let player_wallet_address = Pubkey::new_unique();
+        program_test.add_account(
+            player_wallet_address,
+            Account {
+                lamports: Rent::default().minimum_balance(PlayerWallet::LEN) + 100,
+                data: bytemuck::bytes_of(&player_wallet).to_vec(),
+                owner: crate::id(),
+                ..Account::default()
+            },
+        );
+        let recipient_address = add_player_wallet(&mut program_test, &PlayerWallet::new("Recipient", Pubkey::new_unique()).unwrap());
+        let player_achievements_address = PlayerAchievements::find_address(&player_wallet_address, &crate::id()).0;
+        
+        let mut context = program_test.start_with_context().await;
+        initialize_program(&mut context).await;
+        let config_address = ProgramConfig::find_address(&crate::id()).0;
+        
+        // 47 = CreateAchievement instruction, one for the first transaction and one for the second
+        let mut instructions = Vec::new();
+        for (id, name, metric, threshold) in [
+            (0u8, "FirstSteps", WalletMetric::Transactions, 1u64),
+            (9u8, "Regular", WalletMetric::Transactions, 2u64),
+        ] {
+            let mut instruction_data = vec![47, id];
+            instruction_data.extend(name.to_string().try_to_vec().unwrap());
+            instruction_data.extend(metric.try_to_vec().unwrap());
+            instruction_data.extend(threshold.try_to_vec().unwrap());
+            instructions.push(solana_program::instruction::Instruction::new_with_bytes(
+                crate::id(),
+                &instruction_data,
+                vec![
+                    solana_program::instruction::AccountMeta::new(context.payer.pubkey(), true),
+                    solana_program::instruction::AccountMeta::new_readonly(config_address, false),
+                    solana_program::instruction::AccountMeta::new(Achievement::find_address(id, &crate::id()).0, false),
+                    solana_program::instruction::AccountMeta::new_readonly(solana_program::system_program::id(), false),
+                ],
+            ));
+        }
+        // 48 = CreatePlayerAchievements instruction
+        instructions.push(solana_program::instruction::Instruction::new_with_bytes(
+            crate::id(),
+            &[48],
+            vec![
+                solana_program::instruction::AccountMeta::new(context.payer.pubkey(), true),
+                solana_program::instruction::AccountMeta::new_readonly(player_wallet_address, false),
+                solana_program::instruction::AccountMeta::new(player_achievements_address, false),
+                solana_program::instruction::AccountMeta::new_readonly(solana_program::system_program::id(), false),
+            ],
+        ));
+        let mut transaction = Transaction::new_with_payer(&instructions, Some(&context.payer.pubkey()));
+        transaction.sign(&[&context.payer], context.last_blockhash);
+        context.banks_client.process_transaction(transaction).await.unwrap();
+        
+        // 20 = Transfer instruction, checking both achievements against the transactions counted by the program
+        for (amount, unlocked) in [(10u64, [true, false]), (20u64, [true, true])] {
+            let mut instruction_data = vec![20];
+            instruction_data.extend(amount.try_to_vec().unwrap());
+            let instruction = solana_program::instruction::Instruction::new_with_bytes(
+                crate::id(),
+                &instruction_data,
+                vec![
+                    solana_program::instruction::AccountMeta::new_readonly(owner.pubkey(), true),
+                    solana_program::instruction::AccountMeta::new(player_wallet_address, false),
+                    solana_program::instruction::AccountMeta::new(recipient_address, false),
+                    solana_program::instruction::AccountMeta::new(Leaderboard::find_address(&crate::id()).0, false),
+                    solana_program::instruction::AccountMeta::new_readonly(SeasonClock::find_address(&crate::id()).0, false),
+                    solana_program::instruction::AccountMeta::new(player_achievements_address, false),
+                    solana_program::instruction::AccountMeta::new_readonly(Achievement::find_address(0, &crate::id()).0, false),
+                    solana_program::instruction::AccountMeta::new_readonly(Achievement::find_address(9, &crate::id()).0, false),
+                ],
+            );
+            let mut transaction = Transaction::new_with_payer(&[instruction], Some(&context.payer.pubkey()));
+            transaction.sign(&[&context.payer, &owner], context.last_blockhash);
+            let result = context.banks_client.process_transaction_with_metadata(transaction).await.unwrap();
+            assert!(result.result.is_ok());
+            
+            // Each unlock is logged once as an event
+            let events = result.metadata.unwrap().log_messages.iter()
+                .filter(|log| log.starts_with("Program data: "))
+                .count();
+            assert_eq!(events, 1);
+            
+            let account = context.banks_client.get_account(player_achievements_address).await.unwrap().unwrap();
+            let player_achievements = unpack_account::<PlayerAchievements>(&account.data).unwrap();
+            assert_eq!([player_achievements.is_unlocked(0), player_achievements.is_unlocked(9)], unlocked);
+        }
+    }
+    
+    #[test]
+    fn test_achievement_thresholds() {
+        let mut player_wallet = PlayerWallet::new("Achiever", Pubkey::new_unique()).unwrap();
+        let achievement = Achievement {
+            is_initialized: true,
+            id: 200,
+            name: "HighRoller".to_string(),
+            metric: WalletMetric::PortfolioValue,
+            threshold: 10_000,
+            bump: 255,
+        };
+        let mut player_achievements = PlayerAchievements {
+            is_initialized: true,
+            player_wallet: Pubkey::new_unique(),
+            bump: 255,
+            unlocked: [0; 32],
+        };
+        
+        // The threshold itself is enough
+        player_wallet.amount_total_value_stablecoin = 9_999;
+        assert!(!achievement.is_reached(&player_wallet));
+        player_wallet.amount_total_value_stablecoin = 10_000;
+        assert!(achievement.is_reached(&player_wallet));
+        
+        // Achievements unlock once and only set their own bit
+        assert!(player_achievements.unlock(achievement.id));
+        assert!(!player_achievements.unlock(achievement.id));
+        assert!(player_achievements.is_unlocked(200));
+        assert!(!player_achievements.is_unlocked(201));
+        assert_eq!(player_achievements.unlocked.iter().map(|byte| byte.count_ones()).sum::<u32>(), 1);
+        assert!(player_achievements.unlock(255));
+        assert_eq!((player_achievements.unlocked[25], player_achievements.unlocked[31]), (0b1, 0b1000_0000));
+        
+        // Events encode to a fixed size
+        let event = AchievementUnlocked {
+            player_wallet: player_achievements.player_wallet,
+            achievement_id: achievement.id,
+            metric: achievement.metric,
+            value: 10_000,
+            unlocked_at: 0,
+        };
+        assert_eq!(event.try_to_vec().unwrap().len(), 32 + 1 + 1 + 8 + 8);
+    }
+    
+    #[tokio::test]
+    async fn test_token_2022_vault_with_transfer_fee() {
+        let mut program_test = program_test();
+        
+        // A Token-2022 mint charging a 1% transfer fee
+        let mint = Pubkey::new_unique();
+        let mint_data = token_2022_mint_data(10_000, Some(100), false);
+        program_test.add_account(
+            mint,
+            Account {
+                lamports: Rent::default().minimum_balance(mint_data.len()),
+                data: mint_data,
+                owner: spl_token_2022::id(),
+                ..Account::default()
+            },
+        );
+        
+        let owner = Keypair::new();
+        let player_wallet = PlayerWallet::new("FeePayer", owner.pubkey()).unwrap();
+        let player_wallet_address = add_player_wallet(&mut program_test, &player_wallet);
+        let (holdings_address, holdings_bump) = Holdings::find_address(&player_wallet_address, &crate::id());
+        add_state_account(
+            &mut program_test,
+            holdings_address,
+            &Holdings {
+                is_initialized: true,
+                player_wallet: player_wallet_address,
+                bump: holdings_bump,
+                entries: vec![Holding { mint, amount: 10_000, decimals: 0, last_price: 0, vault_amount: 0, token_program: spl_token_2022::id() }],
+            },
+        );
+        let owner_token_account = spl_associated_token_account::get_associated_token_address_with_program_id(
+            &owner.pubkey(),
+            &mint,
+            &spl_token_2022::id(),
+        );
+        add_token_2022_account(&mut program_test, owner_token_account, mint, owner.pubkey(), 10_000);
+        let vault_authority = find_vault_authority_address(&player_wallet_address, &crate::id()).0;
+        let vault_token_account = spl_associated_token_account::get_associated_token_address_with_program_id(
+            &vault_authority,
+            &mint,
+            &spl_token_2022::id(),
+        );
+        
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+        
+        // 16 = Deposit instruction
+        let mut instruction_data = vec![16];
+        instruction_data.extend(1_000u64.try_to_vec().unwrap());
+        let mut transaction = Transaction::new_with_payer(
+            &[solana_program::instruction::Instruction::new_with_bytes(
+                crate::id(),
+                &instruction_data,
+                vec![
+                    solana_program::instruction::AccountMeta::new(payer.pubkey(), true),
+                    solana_program::instruction::AccountMeta::new_readonly(owner.pubkey(), true),
+                    solana_program::instruction::AccountMeta::new(player_wallet_address, false),
+                    solana_program::instruction::AccountMeta::new(holdings_address, false),
+                    solana_program::instruction::AccountMeta::new_readonly(mint, false),
+                    solana_program::instruction::AccountMeta::new(owner_token_account, false),
+                    solana_program::instruction::AccountMeta::new_readonly(vault_authority, false),
+                    solana_program::instruction::AccountMeta::new(vault_token_account, false),
+                    solana_program::instruction::AccountMeta::new_readonly(spl_token_2022::id(), false),
+                    solana_program::instruction::AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+                    solana_program::instruction::AccountMeta::new_readonly(solana_program::system_program::id(), false),
+                    solana_program::instruction::AccountMeta::new(Leaderboard::find_address(&crate::id()).0, false),
+                    solana_program::instruction::AccountMeta::new_readonly(SeasonClock::find_address(&crate::id()).0, false),
+                ],
+            )],
+            Some(&payer.pubkey()),
+        );
+        transaction.sign(&[&payer, &owner], recent_blockhash);
+        banks_client.process_transaction(transaction).await.unwrap();
+        
+        // The vault only received the amount net of the fee
+        let holdings_account = banks_client.get_account(holdings_address).await.unwrap().unwrap();
+        let holdings = unpack_account::<Holdings>(&holdings_account.data).unwrap();
+        assert_eq!(holdings.entries[0].amount, 9_000);
+        assert_eq!(holdings.entries[0].vault_amount, 990);
+        let player_wallet_account = banks_client.get_account(player_wallet_address).await.unwrap().unwrap();
+        assert_eq!(PlayerWallet::load(&player_wallet_account.data).unwrap().amount_total_tokens, 9_990);
+        
+        // Syncing reads the Token-2022 associated account
+        let mut transaction = Transaction::new_with_payer(
+            &[solana_program::instruction::Instruction::new_with_bytes(
+                crate::id(),
+                &[12],
+                vec![
+                    solana_program::instruction::AccountMeta::new(player_wallet_address, false),
+                    solana_program::instruction::AccountMeta::new(holdings_address, false),
+                    solana_program::instruction::AccountMeta::new(Leaderboard::find_address(&crate::id()).0, false),
+                    solana_program::instruction::AccountMeta::new_readonly(SeasonClock::find_address(&crate::id()).0, false),
+                    solana_program::instruction::AccountMeta::new_readonly(owner_token_account, false),
+                ],
+            )],
+            Some(&payer.pubkey()),
+        );
+        transaction.sign(&[&payer], recent_blockhash);
+        banks_client.process_transaction(transaction).await.unwrap();
+        
+        let player_wallet_account = banks_client.get_account(player_wallet_address).await.unwrap().unwrap();
+        assert_eq!(PlayerWallet::load(&player_wallet_account.data).unwrap().amount_total_tokens, 9_990);
+    }
+    
+    #[test]
+    fn test_mint_extension_checks() {
+        let check = |owner: Pubkey, mut data: Vec<u8>| {
+            let key = Pubkey::new_unique();
+            let mut lamports = 0;
+            let account_info = AccountInfo::new(&key, false, false, &mut lamports, &mut data, &owner, false, 0);
+            crate::token::check_mint_extensions(&account_info)
+        };
+        
+        // Legacy mints and transfer fees are supported
+        let mut legacy_mint = vec![0; spl_token::state::Mint::LEN];
+        spl_token::state::Mint::pack(
+            spl_token::state::Mint { is_initialized: true, ..spl_token::state::Mint::default() },
+            &mut legacy_mint,
+        )
+        .unwrap();
+        assert!(check(spl_token::id(), legacy_mint).is_ok());
+        assert!(check(spl_token_2022::id(), token_2022_mint_data(0, None, false)).is_ok());
+        assert!(check(spl_token_2022::id(), token_2022_mint_data(0, Some(50), false)).is_ok());
+        
+        // Non-transferable tokens cannot be held or traded
+        assert_eq!(
+            check(spl_token_2022::id(), token_2022_mint_data(0, Some(50), true)).err(),
+            Some(crate::error::PlayerWalletError::UnsupportedMintExtension.into()),
+        );
+        
+        // Mints must belong to a token program
+        assert!(check(Pubkey::new_unique(), token_2022_mint_data(0, None, false)).is_err());
+    }
+    
+    #[test]
+    fn test_price_feed_checks() {
+        let mut price_feed = PriceFeed {
+            is_initialized: true,
+            mint: Pubkey::new_unique(),
+            publisher: Pubkey::new_unique(),
+            price: 100_000,
+            expo: -5,
+            conf: 1_000,
+            publish_time: 1_000,
+            bump: 255,
+        };
+        
+        // Fresh and confident prices are accepted
+        assert_eq!(price_feed.get_checked_price(1_000 + MAX_PRICE_AGE_SECONDS), Ok(100_000));
+        assert_eq!(price_feed.value_of(100_000, 1_000, 3), Ok(1_000_000));
+        assert_eq!(price_feed.unit_price(100_000), Ok(1_000_000));
+        
+        // Stale prices are rejected
+        assert_eq!(
+            price_feed.get_checked_price(1_001 + MAX_PRICE_AGE_SECONDS).err(),
+            Some(crate::error::PlayerWalletError::StalePrice.into()),
+        );
+        
+        // Wide confidence intervals are rejected
+        price_feed.conf = 5_000;
+        assert_eq!(
+            price_feed.get_checked_price(1_000).err(),
+            Some(crate::error::PlayerWalletError::PriceConfidenceTooWide.into()),
+        );
+        
+        // Missing or negative prices are rejected
+        price_feed.price = -1;
+        assert_eq!(
+            price_feed.get_checked_price(1_000).err(),
+            Some(crate::error::PlayerWalletError::InvalidPriceFeed.into()),
+        );
+    }
+    
+    #[test]
+    fn test_player_wallet_validation() {
+        // Test name length validation
+        let wallet_address = Pubkey::new_unique();
+        
+        // Test valid name
+        let valid_name = "ValidPlayerName123".to_string();
+        let valid_result = PlayerWallet::new(&valid_name, wallet_address);
+        assert!(valid_result.is_ok());
+        
+        // Test name too long
+        let long_name = "a".repeat(MAX_NAME_LENGTH + 1);
+        let long_result = PlayerWallet::new(&long_name, wallet_address);
+        assert!(long_result.is_err());
+        
+        // Test invalid characters
+        let invalid_name = "Invalid-Name!".to_string();
+        let invalid_result = PlayerWallet::new(&invalid_name, wallet_address);
+        assert!(invalid_result.is_err());
+    }
+    
+    #[test]
+    fn test_name_blocklist_rules() {
+        let blocklist = NameBlocklist {
+            is_initialized: true,
+            rules: vec![
+                BlockedNameRule::exact("admin"),
+                BlockedNameRule::Prefix("moderator".to_string()),
+                BlockedNameRule::Substring("badword".to_string()),
+            ],
+        };
+        
+        // Exact matches are case-insensitive and see through digit look-alikes
+        assert!(blocklist.check_name("Admin").is_err());
+        assert!(blocklist.check_name("4DM1N").is_err());
+        assert!(blocklist.check_name("admin2").is_ok());
+        
+        // Prefix and substring rules
+        assert!(blocklist.check_name("Moderator42").is_err());
+        assert!(blocklist.check_name("xxBadW0rdxx").is_err());
+        
+        // Regular names pass
+        assert!(blocklist.check_name("TestPlayer123").is_ok());
+        assert!(NameBlocklist::default().check_name("admin").is_ok());
+    }
+    
+    #[test]
+    fn test_account_discriminators() {
+        // Discriminators match their type names
+        assert_eq!(PlayerWallet::DISCRIMINATOR, account_discriminator("PlayerWallet"));
+        assert_eq!(ProgramConfig::DISCRIMINATOR, account_discriminator("ProgramConfig"));
+        assert_eq!(NameBlocklist::DISCRIMINATOR, account_discriminator("NameBlocklist"));
+        assert_eq!(Treasury::DISCRIMINATOR, account_discriminator("Treasury"));
+        assert_eq!(ProfileIndex::DISCRIMINATOR, account_discriminator("ProfileIndex"));
+        assert_eq!(CurrencyConfig::DISCRIMINATOR, account_discriminator("CurrencyConfig"));
+        assert_eq!(Distributor::DISCRIMINATOR, account_discriminator("Distributor"));
+        assert_eq!(StakingConfig::DISCRIMINATOR, account_discriminator("StakingConfig"));
+        assert_eq!(StakePosition::DISCRIMINATOR, account_discriminator("StakePosition"));
+        assert_eq!(Inventory::DISCRIMINATOR, account_discriminator("Inventory"));
+        assert_eq!(ItemRegistration::DISCRIMINATOR, account_discriminator("ItemRegistration"));
+        assert_eq!(MarketplaceConfig::DISCRIMINATOR, account_discriminator("MarketplaceConfig"));
+        assert_eq!(Listing::DISCRIMINATOR, account_discriminator("Listing"));
+        assert_eq!(Auction::DISCRIMINATOR, account_discriminator("Auction"));
+        assert_eq!(Rental::DISCRIMINATOR, account_discriminator("Rental"));
+        assert_eq!(Leaderboard::DISCRIMINATOR, account_discriminator("Leaderboard"));
+        assert_eq!(Season::DISCRIMINATOR, account_discriminator("Season"));
+        assert_eq!(SeasonStats::DISCRIMINATOR, account_discriminator("SeasonStats"));
+        assert_eq!(SeasonClock::DISCRIMINATOR, account_discriminator("SeasonClock"));
+        assert_eq!(Achievement::DISCRIMINATOR, account_discriminator("Achievement"));
+        assert_eq!(PlayerAchievements::DISCRIMINATOR, account_discriminator("PlayerAchievements"));
+        assert_eq!(AchievementUnlocked::DISCRIMINATOR, event_discriminator("AchievementUnlocked"));
+        assert_eq!(PriceFeed::DISCRIMINATOR, account_discriminator("PriceFeed"));
+        
+        // An account of one type cannot be read as another
+        let profile_index = ProfileIndex {
+            is_initialized: true,
+            owner: Pubkey::new_unique(),
+            profile_count: 1,
+            bump: 255,
+        };
+        let data = pack_account(&profile_index).unwrap();
+        assert!(unpack_account::<ProfileIndex>(&data).is_ok());
+        assert_eq!(
+            unpack_account::<Treasury>(&data).err(),
+            Some(crate::error::PlayerWalletError::InvalidAccountType.into()),
+        );
+        assert!(PlayerWallet::load(&data).is_err());
+    }
+    
+    #[test]
+    fn test_player_wallet_filter_offsets() {
+        use std::mem::offset_of;
+        
+        // Published offsets match the in-memory layout
+        assert_eq!(PlayerWallet::LEN, 192);
+        assert_eq!(PlayerWallet::DISCRIMINATOR_OFFSET, offset_of!(PlayerWallet, discriminator));
+        assert_eq!(PlayerWallet::VERSION_OFFSET, offset_of!(PlayerWallet, version));
+        assert_eq!(PlayerWallet::NAME_LEN_OFFSET, offset_of!(PlayerWallet, name_len));
+        assert_eq!(PlayerWallet::STATUS_OFFSET, offset_of!(PlayerWallet, status));
+        assert_eq!(PlayerWallet::WALLET_ADDRESS_OFFSET, offset_of!(PlayerWallet, wallet_address));
+        assert_eq!(PlayerWallet::NAME_OFFSET, offset_of!(PlayerWallet, name));
+        
+        // Every memcmp filter matches a wallet it describes
+        let owner = Pubkey::new_unique();
+        let mut player_wallet = PlayerWallet::new("FilterMe", owner).unwrap();
+        player_wallet.status = WalletStatus::Frozen as u8;
+        let data = bytemuck::bytes_of(&player_wallet);
+        
+        let matches = |filters: Vec<AccountFilter>| filters.iter().all(|filter| match filter {
+            AccountFilter::DataSize(size) => data.len() as u64 == *size,
+            AccountFilter::Memcmp { offset, bytes } => data[*offset..].starts_with(bytes),
+        });
+        assert!(matches(filters::player_wallets()));
+        assert!(matches(filters::player_wallets_by_owner(&owner)));
+        assert!(!matches(filters::player_wallets_by_owner(&Pubkey::new_unique())));
+        assert!(matches(filters::player_wallets_by_status(WalletStatus::Frozen)));
+        assert!(!matches(filters::player_wallets_by_status(WalletStatus::Active)));
+        assert!(matches(filters::player_wallets_by_name("FilterMe")));
+        assert!(!matches(filters::player_wallets_by_name("Filter")));
+    }
+    
+    #[test]
+    fn test_holdings_aggregates() {
+        let mut player_wallet = PlayerWallet::new("Holder", Pubkey::new_unique()).unwrap();
+        let mut holdings = Holdings {
+            is_initialized: true,
+            player_wallet: Pubkey::new_unique(),
+            bump: 255,
+            entries: Vec::new(),
+        };
+        let mint_a = Pubkey::new_unique();
+        let mint_b = Pubkey::new_unique();
+        
+        holdings.add(Holding { mint: mint_a, amount: 100, decimals: 6, last_price: 0, vault_amount: 0, token_program: spl_token::id() }).unwrap();
+        holdings.add(Holding { mint: mint_b, amount: 250, decimals: 9, last_price: 0, vault_amount: 0, token_program: spl_token::id() }).unwrap();
+        assert!(holdings.add(Holding { mint: mint_a, amount: 1, decimals: 6, last_price: 0, vault_amount: 0, token_program: spl_token::id() }).is_err());
+        holdings.apply_aggregates(&mut player_wallet).unwrap();
+        assert_eq!(player_wallet.nb_tokens, 2);
+        assert_eq!(player_wallet.amount_total_tokens, 350);
+        
+        holdings.get_mut(&mint_b).unwrap().amount = 50;
+        holdings.remove(&mint_a).unwrap();
+        holdings.apply_aggregates(&mut player_wallet).unwrap();
+        assert_eq!(player_wallet.nb_tokens, 1);
+        assert_eq!(player_wallet.amount_total_tokens, 50);
+        assert!(holdings.remove(&mint_a).is_err());
+        
+        // Holdings with tokens left in the vault cannot be removed
+        holdings.get_mut(&mint_b).unwrap().vault_amount = 10;
+        holdings.apply_aggregates(&mut player_wallet).unwrap();
+        assert_eq!(player_wallet.amount_total_tokens, 60);
+        assert_eq!(
+            holdings.remove(&mint_b).err(),
+            Some(crate::error::PlayerWalletError::VaultNotEmpty.into()),
+        );
+        
+        // The account size tracks the number of entries
+        assert_eq!(
+            pack_account(&holdings).unwrap().len(),
+            Holdings::get_account_size(holdings.entries.len()),
+        );
+        
+        // The entry cap is enforced
+        for _ in holdings.entries.len()..MAX_HOLDINGS {
+            holdings.add(Holding { mint: Pubkey::new_unique(), amount: 1, decimals: 0, last_price: 0, vault_amount: 0, token_program: spl_token::id() }).unwrap();
+        }
+        assert!(holdings.add(Holding { mint: Pubkey::new_unique(), amount: 1, decimals: 0, last_price: 0, vault_amount: 0, token_program: spl_token::id() }).is_err());
     }
 }
@@ -0,0 +1,102 @@
+// Helpers for instructions moving SPL tokens
+// Both the legacy token program and Token-2022 are accepted. Token-2022
+// accounts are read through `StateWithExtensions`, which also handles the
+// legacy layout, and balances are always re-read from the token accounts
+// after a transfer so transfer fees are reflected as actually charged.
+use solana_program::{
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    msg,
+};
+use spl_associated_token_account::get_associated_token_address_with_program_id;
+use spl_token_2022::{
+    extension::{BaseStateWithExtensions, ExtensionType, StateWithExtensions},
+    state::{Account, Mint},
+};
+
+use crate::error::PlayerWalletError;
+
+/// Mint extensions that do not get in the way of holding, moving or trading tokens
+const SUPPORTED_MINT_EXTENSIONS: &[ExtensionType] = &[
+    ExtensionType::TransferFeeConfig,
+    ExtensionType::MintCloseAuthority,
+    ExtensionType::InterestBearingConfig,
+    ExtensionType::MetadataPointer,
+    ExtensionType::TokenMetadata,
+    ExtensionType::GroupPointer,
+    ExtensionType::TokenGroup,
+    ExtensionType::GroupMemberPointer,
+    ExtensionType::TokenGroupMember,
+];
+
+/// Check whether `program_id` is the legacy token program or Token-2022
+pub fn is_token_program(program_id: &Pubkey) -> bool {
+    *program_id == spl_token::id() || *program_id == spl_token_2022::id()
+}
+
+/// Ensure `token_program_info` is the legacy token program or Token-2022
+pub fn check_token_program(token_program_info: &AccountInfo) -> ProgramResult {
+    if !is_token_program(token_program_info.key) {
+        msg!("Invalid token program");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    Ok(())
+}
+
+/// Associated token account of `wallet` for `mint` under `token_program_id`
+pub fn get_token_account_address(wallet: &Pubkey, mint: &Pubkey, token_program_id: &Pubkey) -> Pubkey {
+    get_associated_token_address_with_program_id(wallet, mint, token_program_id)
+}
+
+/// Read a token account owned by either token program
+pub fn unpack_token_account(token_account_info: &AccountInfo) -> Result<Account, ProgramError> {
+    if !is_token_program(token_account_info.owner) {
+        msg!("Token account {} is not owned by a token program", token_account_info.key);
+        return Err(PlayerWalletError::InvalidTokenAccount.into());
+    }
+    let data = token_account_info.try_borrow_data()?;
+    Ok(StateWithExtensions::<Account>::unpack(&data)?.base)
+}
+
+/// Read a mint owned by either token program
+pub fn unpack_mint(mint_info: &AccountInfo) -> Result<Mint, ProgramError> {
+    if !is_token_program(mint_info.owner) {
+        msg!("Mint {} is not owned by a token program", mint_info.key);
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    let data = mint_info.try_borrow_data()?;
+    Ok(StateWithExtensions::<Mint>::unpack(&data)?.base)
+}
+
+/// Ensure a mint is a game item: a single unit with no decimals that can
+/// never be minted again
+pub fn check_item_mint(mint_info: &AccountInfo) -> ProgramResult {
+    let mint = unpack_mint(mint_info)?;
+    if mint.decimals != 0 || mint.supply != 1 || mint.mint_authority.is_some() {
+        msg!("Mint {} is not a non-fungible item", mint_info.key);
+        return Err(PlayerWalletError::InvalidItemMint.into());
+    }
+    Ok(())
+}
+
+/// Ensure a mint only uses extensions the program can hold and move safely
+/// Non-transferable tokens cannot be traded, permanent delegates could empty
+/// vaults, transfer hooks need accounts the program does not pass along, and
+/// confidential balances cannot be tracked.
+pub fn check_mint_extensions(mint_info: &AccountInfo) -> ProgramResult {
+    if !is_token_program(mint_info.owner) {
+        msg!("Mint {} is not owned by a token program", mint_info.key);
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    let data = mint_info.try_borrow_data()?;
+    let mint = StateWithExtensions::<Mint>::unpack(&data)?;
+    for extension in mint.get_extension_types()? {
+        if !SUPPORTED_MINT_EXTENSIONS.contains(&extension) {
+            msg!("Mint {} uses unsupported extension {:?}", mint_info.key, extension);
+            return Err(PlayerWalletError::UnsupportedMintExtension.into());
+        }
+    }
+    Ok(())
+}
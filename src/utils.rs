@@ -0,0 +1,119 @@
+use solana_program::{
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    system_instruction,
+    system_program,
+    sysvar::{rent::Rent, Sysvar},
+};
+
+/// Create a program-owned account at a PDA, funded by `payer`
+/// A PDA that was pre-funded by someone else is topped up, allocated and
+/// assigned instead, since `create_account` refuses accounts with lamports
+pub fn create_pda_account<'a>(
+    payer_info: &AccountInfo<'a>,
+    new_account_info: &AccountInfo<'a>,
+    system_program_info: &AccountInfo<'a>,
+    program_id: &Pubkey,
+    space: usize,
+    signer_seeds: &[&[u8]],
+) -> ProgramResult {
+    let rent = Rent::get()?;
+    let rent_lamports = rent.minimum_balance(space);
+    let current_lamports = new_account_info.lamports();
+
+    if current_lamports == 0 {
+        return invoke_signed(
+            &system_instruction::create_account(
+                payer_info.key,
+                new_account_info.key,
+                rent_lamports,
+                space as u64,
+                program_id,
+            ),
+            &[
+                payer_info.clone(),
+                new_account_info.clone(),
+                system_program_info.clone(),
+            ],
+            &[signer_seeds],
+        );
+    }
+
+    if rent_lamports > current_lamports {
+        invoke(
+            &system_instruction::transfer(
+                payer_info.key,
+                new_account_info.key,
+                rent_lamports - current_lamports,
+            ),
+            &[
+                payer_info.clone(),
+                new_account_info.clone(),
+                system_program_info.clone(),
+            ],
+        )?;
+    }
+    invoke_signed(
+        &system_instruction::allocate(new_account_info.key, space as u64),
+        &[new_account_info.clone(), system_program_info.clone()],
+        &[signer_seeds],
+    )?;
+    invoke_signed(
+        &system_instruction::assign(new_account_info.key, program_id),
+        &[new_account_info.clone(), system_program_info.clone()],
+        &[signer_seeds],
+    )
+}
+
+/// Resize a program-owned account, topping up rent from `payer` when it grows
+/// and refunding the excess to `payer` when it shrinks
+pub fn resize_account<'a>(
+    account_info: &AccountInfo<'a>,
+    payer_info: &AccountInfo<'a>,
+    system_program_info: &AccountInfo<'a>,
+    new_size: usize,
+) -> ProgramResult {
+    let rent = Rent::get()?;
+    let required_lamports = rent.minimum_balance(new_size);
+    let current_lamports = account_info.lamports();
+
+    if required_lamports > current_lamports {
+        invoke(
+            &system_instruction::transfer(
+                payer_info.key,
+                account_info.key,
+                required_lamports - current_lamports,
+            ),
+            &[
+                payer_info.clone(),
+                account_info.clone(),
+                system_program_info.clone(),
+            ],
+        )?;
+    } else if current_lamports > required_lamports {
+        let excess = current_lamports - required_lamports;
+        **account_info.try_borrow_mut_lamports()? -= excess;
+        **payer_info.try_borrow_mut_lamports()? += excess;
+    }
+
+    account_info.realloc(new_size, false)
+}
+
+/// Close a program-owned account, sending its lamports to `destination_info`
+/// The account is emptied and handed back to the system program, so its
+/// address can be reused once the transaction completes
+pub fn close_account(account_info: &AccountInfo, destination_info: &AccountInfo) -> ProgramResult {
+    let destination_lamports = destination_info
+        .lamports()
+        .checked_add(account_info.lamports())
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    **account_info.try_borrow_mut_lamports()? = 0;
+    **destination_info.try_borrow_mut_lamports()? = destination_lamports;
+
+    account_info.realloc(0, false)?;
+    account_info.assign(&system_program::id());
+    Ok(())
+}